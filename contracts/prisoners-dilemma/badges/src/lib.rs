@@ -0,0 +1,198 @@
+// Achievement badges for the Prisoner's Dilemma contract
+//
+// A companion contract holding the game's badges as soulbound ERC-721 tokens
+// (ERC-5192: always locked). The game contract reports each milestone its
+// rules detect through `mint`; the tokens and their on-chain JSON metadata
+// live here so the game contract stays within the Stylus size limit.
+//
+// Note: this code is a template-only and has not been audited.
+//
+// Allow `cargo stylus export-abi` to generate a main function.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::sol;
+use prisoners_dilemma_core::Badge;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, U256, U8},
+    prelude::*,
+    stylus_core,
+};
+
+sol_storage! {
+    #[entrypoint]
+    pub struct PrisonersDilemmaBadges {
+        // The game contract, the only account allowed to mint
+        address game;
+        // Owner and kind of each token, each player's tokens, and a bitmask of
+        // the badge kinds they hold
+        uint256 badge_counter;
+        mapping(uint256 => address) badge_owners;
+        mapping(uint256 => uint8) badge_kinds;
+        mapping(address => uint256[]) player_badges;
+        mapping(address => uint8) player_badge_kinds;
+    }
+}
+
+// Events and Errors
+sol! {
+    event BadgeEarned(uint256 indexed cell_id, address indexed player, uint8 badge, uint256 token_id);
+    event Transfer(address indexed from, address indexed to, uint256 indexed token_id);
+    event Locked(uint256 token_id);
+    error NotGame(address sender);
+    error InvalidBadge(uint8 badge);
+    error BadgeNotFound(uint256 token_id);
+    error Soulbound(uint256 token_id);
+}
+
+#[derive(SolidityError)]
+pub enum PrisonersDilemmaBadgesErrors {
+    NotGame(NotGame),
+    InvalidBadge(InvalidBadge),
+    BadgeNotFound(BadgeNotFound),
+    Soulbound(Soulbound),
+}
+
+#[public]
+impl PrisonersDilemmaBadges {
+    // Binds the badges to the game contract that mints them; later calls do nothing
+    pub fn initialize(&mut self, game: Address) {
+        if self.game.get() == Address::ZERO {
+            self.game.set(game);
+        }
+    }
+
+    pub fn get_game(&self) -> Address {
+        self.game.get()
+    }
+
+    // Game contract only: mints `badge` to `player` for a milestone in
+    // `cell_id`, unless they already hold one of that kind
+    pub fn mint(&mut self, cell_id: U256, player: Address, badge: u8) -> Result<(), PrisonersDilemmaBadgesErrors> {
+        let sender = self.vm().msg_sender();
+        if sender != self.game.get() {
+            return Err(PrisonersDilemmaBadgesErrors::NotGame(NotGame { sender }));
+        }
+        let Some(kind) = Badge::from_u8(badge) else {
+            return Err(PrisonersDilemmaBadgesErrors::InvalidBadge(InvalidBadge { badge }));
+        };
+        let held = self.player_badge_kinds.get(player).to::<u8>();
+        if held & kind.mask() != 0 {
+            return Ok(());
+        }
+
+        let token_id = self.badge_counter.get() + U256::from(1);
+        self.badge_counter.set(token_id);
+        self.badge_owners.setter(token_id).set(player);
+        self.badge_kinds.setter(token_id).set(U8::from(badge));
+        self.player_badges.setter(player).push(token_id);
+        self.player_badge_kinds.setter(player).set(U8::from(held | kind.mask()));
+
+        stylus_core::log(self.vm(), Transfer { from: Address::ZERO, to: player, token_id });
+        stylus_core::log(self.vm(), Locked { token_id });
+        stylus_core::log(self.vm(), BadgeEarned { cell_id, player, badge, token_id });
+        Ok(())
+    }
+
+    // Token ids and badge kinds held by `player`, in the order they were earned
+    pub fn get_player_badges(&self, player: Address) -> (Vec<U256>, Vec<u8>) {
+        let tokens = self.player_badges.get(player);
+        let token_ids: Vec<U256> = (0..tokens.len()).filter_map(|i| tokens.get(i)).collect();
+        let kinds = token_ids.iter().map(|&token_id| self.badge_kinds.get(token_id).to::<u8>()).collect();
+        (token_ids, kinds)
+    }
+
+    pub fn name(&self) -> String {
+        "Prisoner's Dilemma Badges".into()
+    }
+
+    pub fn symbol(&self) -> String {
+        "PDB".into()
+    }
+
+    #[selector(name = "tokenURI")]
+    pub fn token_uri(&self, token_id: U256) -> Result<String, PrisonersDilemmaBadgesErrors> {
+        let (_, badge) = self.badge_of(token_id)?;
+        Ok(badge.token_uri(token_id))
+    }
+
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        U256::from(self.player_badges.get(owner).len())
+    }
+
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, PrisonersDilemmaBadgesErrors> {
+        Ok(self.badge_of(token_id)?.0)
+    }
+
+    pub fn locked(&self, token_id: U256) -> Result<bool, PrisonersDilemmaBadgesErrors> {
+        self.badge_of(token_id)?;
+        Ok(true)
+    }
+
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, PrisonersDilemmaBadgesErrors> {
+        self.badge_of(token_id)?;
+        Ok(Address::ZERO)
+    }
+
+    pub fn is_approved_for_all(&self, _owner: Address, _operator: Address) -> bool {
+        false
+    }
+
+    pub fn approve(&mut self, _to: Address, token_id: U256) -> Result<(), PrisonersDilemmaBadgesErrors> {
+        Err(soulbound(token_id))
+    }
+
+    pub fn set_approval_for_all(&mut self, _operator: Address, _approved: bool) -> Result<(), PrisonersDilemmaBadgesErrors> {
+        Err(soulbound(U256::ZERO))
+    }
+
+    pub fn transfer_from(&mut self, _from: Address, _to: Address, token_id: U256) -> Result<(), PrisonersDilemmaBadgesErrors> {
+        Err(soulbound(token_id))
+    }
+
+    pub fn safe_transfer_from(&mut self, _from: Address, _to: Address, token_id: U256) -> Result<(), PrisonersDilemmaBadgesErrors> {
+        Err(soulbound(token_id))
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        _from: Address,
+        _to: Address,
+        token_id: U256,
+        _data: Bytes,
+    ) -> Result<(), PrisonersDilemmaBadgesErrors> {
+        Err(soulbound(token_id))
+    }
+
+    // ERC-165, advertising ERC-721 with metadata and ERC-5192
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        const INTERFACES: [[u8; 4]; 4] = [
+            [0x01, 0xff, 0xc9, 0xa7], // ERC-165
+            [0x80, 0xac, 0x58, 0xcd], // ERC-721
+            [0x5b, 0x5e, 0x13, 0x9f], // ERC-721 metadata
+            [0xb4, 0x5a, 0x3c, 0x0e], // ERC-5192
+        ];
+        INTERFACES.contains(&interface_id.0)
+    }
+}
+
+impl PrisonersDilemmaBadges {
+    fn badge_of(&self, token_id: U256) -> Result<(Address, Badge), PrisonersDilemmaBadgesErrors> {
+        let owner = self.badge_owners.get(token_id);
+        let badge = Badge::from_u8(self.badge_kinds.get(token_id).to::<u8>());
+        match badge {
+            Some(badge) if owner != Address::ZERO => Ok((owner, badge)),
+            _ => Err(PrisonersDilemmaBadgesErrors::BadgeNotFound(BadgeNotFound { token_id })),
+        }
+    }
+}
+
+fn soulbound(token_id: U256) -> PrisonersDilemmaBadgesErrors {
+    PrisonersDilemmaBadgesErrors::Soulbound(Soulbound { token_id })
+}
@@ -0,0 +1,95 @@
+// The badge companion: minting for the game contract, the ERC-721 views and
+// metadata, and transfers that always revert
+use alloy_sol_types::SolEvent;
+use prisoners_dilemma_badges::{BadgeEarned, PrisonersDilemmaBadges, PrisonersDilemmaBadgesErrors};
+use prisoners_dilemma_core::Badge;
+use stylus_sdk::alloy_primitives::{Address, FixedBytes, U256};
+use stylus_sdk::testing::TestVM;
+
+const GAME: Address = Address::repeat_byte(0xcc);
+const P1: Address = Address::repeat_byte(1);
+const P2: Address = Address::repeat_byte(2);
+
+fn deployed() -> TestVM {
+    let vm = TestVM::new();
+    vm.set_contract_address(Address::repeat_byte(0xbb));
+    call(&vm, P1, |c| c.initialize(GAME));
+    vm
+}
+
+fn call<T>(vm: &TestVM, sender: Address, f: impl FnOnce(&mut PrisonersDilemmaBadges) -> T) -> T {
+    vm.set_sender(sender);
+    f(&mut PrisonersDilemmaBadges::from(vm))
+}
+
+fn mint(vm: &TestVM, player: Address, badge: Badge) {
+    call(vm, GAME, |c| c.mint(U256::from(7), player, badge as u8)).map_err(Vec::<u8>::from).unwrap();
+}
+
+#[test]
+fn each_kind_is_minted_once_per_player() {
+    let vm = deployed();
+    assert_eq!(call(&vm, P1, |c| c.get_game()), GAME);
+    mint(&vm, P1, Badge::FirstGame);
+    mint(&vm, P2, Badge::FirstGame);
+    mint(&vm, P1, Badge::Forgiver);
+    // A repeat of a kind already held is ignored
+    mint(&vm, P1, Badge::FirstGame);
+
+    let ids = |n: u64| U256::from(n);
+    assert_eq!(call(&vm, P1, |c| c.get_player_badges(P1)), (vec![ids(1), ids(3)], vec![0, 3]));
+    assert_eq!(call(&vm, P1, |c| c.get_player_badges(P2)), (vec![ids(2)], vec![0]));
+    assert_eq!(call(&vm, P1, |c| c.balance_of(P1)), ids(2));
+    assert_eq!(call(&vm, P1, |c| c.owner_of(ids(2))).ok(), Some(P2));
+    assert_eq!(call(&vm, P1, |c| c.locked(ids(3))).ok(), Some(true));
+
+    let earned: Vec<_> = vm
+        .get_emitted_logs()
+        .into_iter()
+        .filter(|(topics, _)| topics[0] == BadgeEarned::SIGNATURE_HASH)
+        .collect();
+    assert_eq!(earned.len(), 3);
+}
+
+#[test]
+fn metadata_is_an_inline_json_uri() {
+    let vm = deployed();
+    mint(&vm, P1, Badge::MutualStreak);
+
+    let uri = call(&vm, P1, |c| c.token_uri(U256::from(1))).ok().unwrap();
+    assert_eq!(uri, Badge::MutualStreak.token_uri(U256::from(1)));
+    assert!(uri.starts_with("data:application/json;utf8,{\"name\":\"Mutual Streak #1\""));
+    assert!(matches!(call(&vm, P1, |c| c.token_uri(U256::from(2))), Err(PrisonersDilemmaBadgesErrors::BadgeNotFound(_))));
+}
+
+#[test]
+fn badges_cannot_be_transferred_or_approved() {
+    let vm = deployed();
+    mint(&vm, P1, Badge::FirstGame);
+    let token_id = U256::from(1);
+
+    assert!(matches!(call(&vm, P1, |c| c.approve(P2, token_id)), Err(PrisonersDilemmaBadgesErrors::Soulbound(_))));
+    assert!(matches!(call(&vm, P1, |c| c.transfer_from(P1, P2, token_id)), Err(PrisonersDilemmaBadgesErrors::Soulbound(_))));
+    assert_eq!(call(&vm, P1, |c| c.owner_of(token_id)).ok(), Some(P1));
+    // ERC-165 advertises ERC-721 and ERC-5192 but nothing else
+    assert!(call(&vm, P1, |c| c.supports_interface(FixedBytes([0xb4, 0x5a, 0x3c, 0x0e]))));
+    assert!(!call(&vm, P1, |c| c.supports_interface(FixedBytes([0xff; 4]))));
+}
+
+#[test]
+fn only_the_game_mints() {
+    let vm = deployed();
+    // Initializing again leaves the game in place
+    call(&vm, P1, |c| c.initialize(P1));
+
+    let minted = call(&vm, P1, |c| c.mint(U256::ZERO, P1, Badge::FirstGame as u8));
+    assert!(matches!(minted, Err(PrisonersDilemmaBadgesErrors::NotGame(_))));
+}
+
+#[test]
+fn unknown_kinds_are_rejected() {
+    let vm = deployed();
+
+    let minted = call(&vm, GAME, |c| c.mint(U256::ZERO, P1, 9));
+    assert!(matches!(minted, Err(PrisonersDilemmaBadgesErrors::InvalidBadge(_))));
+}
@@ -0,0 +1,620 @@
+// Command-line player for the Prisoner's Dilemma contract
+//
+// Connection settings follow the contract's `.env` conventions:
+// - `RPC_URL`: JSON-RPC endpoint
+// - `STYLUS_CONTRACT_ADDRESS`: deployed contract address
+// - `PRIV_KEY_PATH`: file holding the player's private key (only needed to send transactions)
+mod output;
+
+use std::{fs, sync::Arc};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use ethers::{
+    middleware::SignerMiddleware,
+    providers::{Http, Middleware, Provider, StreamExt},
+    signers::{LocalWallet, Signer},
+    types::{Address, Bytes, H256, I256, U256},
+    utils::parse_ether,
+};
+use eyre::{eyre, Result, WrapErr};
+use prisoners_dilemma_client::{
+    creator_seal, sealed_strategy, to_core_i256, ClientError, ContinuationPolicy, Move, PrisonersDilemmaClient,
+    SealedStrategy,
+};
+
+use output::Format;
+
+#[derive(Parser)]
+#[command(version, about = "Play the Prisoner's Dilemma contract from the command line")]
+struct Cli {
+    #[arg(long, env = "RPC_URL", global = true, default_value = "http://localhost:8547")]
+    rpc_url: String,
+
+    #[arg(long, env = "STYLUS_CONTRACT_ADDRESS", global = true)]
+    contract: Option<Address>,
+
+    #[arg(long, env = "PRIV_KEY_PATH", global = true)]
+    priv_key_path: Option<String>,
+
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a new cell and wait for an opponent
+    Create {
+        /// Number of rounds the cell can last
+        #[arg(long, default_value_t = 5)]
+        rounds: u8,
+        /// Stake in ETH (defaults to the contract minimum)
+        #[arg(long)]
+        stake: Option<String>,
+        /// Probability in basis points that a move is flipped; moves then use `commit`/`reveal`
+        #[arg(long, default_value_t = 0)]
+        noise: u16,
+        /// Probability in basis points of another round, replacing continuation votes
+        #[arg(long, default_value_t = 0)]
+        continuation: u16,
+        /// Seal your move for every round up front, like `ccd`; sets the number of rounds
+        #[arg(long, value_name = "MOVES", conflicts_with_all = ["noise", "continuation"])]
+        sealed: Option<String>,
+        /// Credit each round's payouts to your withdrawable balance as it resolves
+        #[arg(long, conflicts_with = "sealed")]
+        streamed: bool,
+    },
+    /// Join an open cell, matching its stake
+    Join {
+        cell_id: U256,
+        /// Seal your move for every round of a sealed cell, like `ccd`
+        #[arg(long, value_name = "MOVES")]
+        sealed: Option<String>,
+    },
+    /// Cancel a cell nobody has joined yet, refunding your stake
+    Cancel { cell_id: U256 },
+    /// List cells waiting for an opponent
+    Lobby {
+        /// Only cells staking at least this much ETH
+        #[arg(long)]
+        min_stake: Option<String>,
+        /// Only cells staking at most this much ETH
+        #[arg(long)]
+        max_stake: Option<String>,
+        /// Number of matching cells to skip
+        #[arg(long, default_value_t = 0)]
+        offset: u64,
+        /// Maximum number of cells to list (the contract caps pages at 100)
+        #[arg(long, default_value_t = 20)]
+        limit: u64,
+    },
+    /// Submit a move for the current round
+    Move {
+        cell_id: U256,
+        choice: Choice,
+        /// Also decide now whether to play another round after this one
+        #[arg(long = "continue", value_name = "DECISION")]
+        decision: Option<Decision>,
+    },
+    /// Commit to a move without revealing it, printing the salt needed to reveal
+    Commit { cell_id: U256, choice: Choice },
+    /// Reveal a committed move once your opponent has committed too
+    Reveal {
+        cell_id: U256,
+        choice: Choice,
+        /// Salt printed by `commit`, as 0x-prefixed hex
+        salt: H256,
+    },
+    /// Claim the pot of a cell whose opponent let the reveal window pass after your reveal
+    Timeout { cell_id: U256 },
+    /// Reveal the moves you sealed, resolving each round your opponent has revealed too
+    RevealSealed {
+        cell_id: U256,
+        /// The moves you sealed, like `ccd`
+        moves: String,
+        /// Seed printed when you sealed them, as 0x-prefixed hex
+        seed: H256,
+        /// Only reveal this round (defaults to every round)
+        #[arg(long)]
+        round: Option<u8>,
+    },
+    /// Offer, accept or cancel a rematch of a completed cell with the same settings
+    Rematch {
+        cell_id: U256,
+        #[arg(value_enum, default_value_t = RematchAction::Offer)]
+        action: RematchAction,
+    },
+    /// Decide whether to play another round
+    Continue { cell_id: U256, decision: Decision },
+    /// Propose a higher stake in ETH for the next round, sending the top-up
+    Raise { cell_id: U256, stake: String },
+    /// Match your opponent's proposed raise, sending the same top-up
+    AcceptRaise { cell_id: U256 },
+    /// Set a standing continuation vote, cast for you after every round you don't vote in
+    Policy {
+        cell_id: U256,
+        policy: PolicyChoice,
+        /// Rounds to play before stopping, for `stop-after`
+        #[arg(long)]
+        rounds: Option<u8>,
+        /// Net payout floor in ETH, may be negative, for `stop-below`
+        #[arg(long, allow_hyphen_values = true)]
+        floor: Option<String>,
+    },
+    /// Show a cell's state (defaults to all of your active cells)
+    Status { cell_id: Option<U256> },
+    /// Show every round played in a cell
+    History { cell_id: U256 },
+    /// Show what each player has earned and could still win or lose in a cell
+    Preview { cell_id: U256 },
+    /// List the achievement badges a player holds (defaults to you)
+    Badges { player: Option<Address> },
+    /// Play a cell off-chain: sign states with your opponent, then settle the latest one
+    Channel {
+        #[command(subcommand)]
+        action: ChannelAction,
+    },
+    /// Withdraw what streamed cells, lapsed raises and failed payouts have credited you
+    Withdraw,
+    /// Stream contract events as they happen
+    Watch {
+        /// Only show events for this cell
+        cell_id: Option<U256>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChannelAction {
+    /// Sign the state after the given rounds, printing it and your signature for your opponent
+    Sign {
+        cell_id: U256,
+        /// Every round so far as player 1's then player 2's move, e.g. `cc,cd,dd`
+        rounds: String,
+    },
+    /// Submit a state signed by both players, opening its challenge window
+    Settle {
+        cell_id: U256,
+        /// The rounds both players signed, as for `sign`
+        rounds: String,
+        /// Player 1's signature, as 0x-prefixed hex
+        sig1: Bytes,
+        /// Player 2's signature, as 0x-prefixed hex
+        sig2: Bytes,
+    },
+    /// Apply the submitted state once its challenge window has closed
+    Finalize { cell_id: U256 },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Choice {
+    Cooperate,
+    Defect,
+}
+
+impl From<Choice> for Move {
+    fn from(choice: Choice) -> Self {
+        match choice {
+            Choice::Cooperate => Move::Cooperate,
+            Choice::Defect => Move::Defect,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RematchAction {
+    Offer,
+    Accept,
+    Cancel,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Decision {
+    Yes,
+    No,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PolicyChoice {
+    /// Vote every round with `continue`
+    Manual,
+    Always,
+    /// Stop after your opponent defects
+    StopWhenDefected,
+    /// Stop after `--rounds` rounds
+    StopAfter,
+    /// Stop once your payouts minus the stake of each round played fall below `--floor`
+    StopBelow,
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    let format = if cli.json { Format::Json } else { Format::Text };
+
+    if let Err(err) = run(cli, format).await {
+        output::print_error(format, &err);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli, format: Format) -> Result<()> {
+    let address = cli
+        .contract
+        .ok_or_else(|| eyre!("no contract address, set STYLUS_CONTRACT_ADDRESS or pass --contract"))?;
+    let provider = Provider::<Http>::try_from(cli.rpc_url.as_str())
+        .wrap_err_with(|| format!("invalid RPC URL {}", cli.rpc_url))?;
+
+    match cli.command {
+        Command::Status { cell_id } => {
+            // Reads don't need a key unless we have to look up our own cell
+            match (cell_id, cli.priv_key_path) {
+                (Some(cell_id), _) => {
+                    let client = PrisonersDilemmaClient::new(address, Arc::new(provider));
+                    status(&client, cell_id, format).await
+                }
+                (None, key_path) => {
+                    let client = signing_client(provider, address, key_path).await?;
+                    let player = client.contract().client().address();
+                    let cell_ids = client.get_player_active_cells(player).await.map_err(revert)?;
+                    if cell_ids.is_empty() {
+                        return Err(eyre!("{player:?} is not in an active cell"));
+                    }
+                    for cell_id in cell_ids {
+                        status(&client, cell_id, format).await?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+        Command::History { cell_id } => {
+            let client = PrisonersDilemmaClient::new(address, Arc::new(provider));
+            let cell = client.get_cell(cell_id).await.map_err(revert)?;
+            output::print_history(format, &cell);
+            Ok(())
+        }
+        Command::Preview { cell_id } => {
+            let client = PrisonersDilemmaClient::new(address, Arc::new(provider));
+            let preview = client.preview_settlement(cell_id).await.map_err(revert)?;
+            output::print_settlement(format, cell_id, preview);
+            Ok(())
+        }
+        Command::Badges { player } => {
+            let player = match player {
+                Some(player) => player,
+                None => {
+                    let client = signing_client(provider.clone(), address, cli.priv_key_path).await?;
+                    client.contract().client().address()
+                }
+            };
+            let client = PrisonersDilemmaClient::new(address, Arc::new(provider));
+            let badges = client.get_player_badges(player).await.map_err(revert)?;
+            output::print_badges(format, player, &badges);
+            Ok(())
+        }
+        Command::Lobby { min_stake, max_stake, offset, limit } => {
+            let client = PrisonersDilemmaClient::new(address, Arc::new(provider));
+            let cells = client
+                .get_open_cells(offset.into(), limit.into(), parse_stake(min_stake)?, parse_stake(max_stake)?)
+                .await
+                .map_err(revert)?;
+            output::print_open_cells(format, &cells);
+            Ok(())
+        }
+        Command::Watch { cell_id } => {
+            let client = PrisonersDilemmaClient::new(address, Arc::new(provider));
+            let events = match cell_id {
+                Some(cell_id) => client.cell_events(cell_id),
+                None => client.events(),
+            };
+            let mut stream = events.stream().await?;
+            while let Some(event) = stream.next().await {
+                output::print_event(format, &event?);
+            }
+            Ok(())
+        }
+        Command::Channel { action } => {
+            let client = signing_client(provider, address, cli.priv_key_path).await?;
+            channel(&client, action, format).await
+        }
+        command => {
+            let client = signing_client(provider, address, cli.priv_key_path).await?;
+            transact(&client, command, format).await
+        }
+    }
+}
+
+async fn transact<M: Middleware + 'static>(
+    client: &PrisonersDilemmaClient<M>,
+    command: Command,
+    format: Format,
+) -> Result<()> {
+    match command {
+        Command::Create { rounds, stake, noise, continuation, sealed, streamed } => {
+            let stake = match stake {
+                Some(stake) => parse_ether(&stake).wrap_err_with(|| format!("invalid stake {stake}"))?,
+                None => client.get_min_stake().await.map_err(revert)?,
+            };
+            if let Some(moves) = sealed {
+                // A fresh nonce seals the strategy for whichever id the cell gets
+                let nonce = U256::from_big_endian(&ethers::core::rand::random::<[u8; 32]>());
+                let seal = creator_seal(sender(client), nonce);
+                let strategy = player_strategy(client, seal, &moves, ethers::core::rand::random())?;
+                let cell_id = client.create_sealed_cell(nonce, &strategy, stake).await.map_err(revert)?;
+                output::print_sealed_seed(format, cell_id, &moves, H256(strategy.seed.0));
+                return Ok(());
+            }
+            let cell_id = if streamed {
+                client
+                    .create_streamed_cell(rounds, noise, continuation, stake)
+                    .await
+                    .map_err(revert)?
+            } else if noise > 0 || continuation > 0 {
+                client
+                    .create_research_cell(rounds, noise, continuation, stake)
+                    .await
+                    .map_err(revert)?
+            } else {
+                client.create_cell(rounds, stake).await.map_err(revert)?
+            };
+            status(client, cell_id, format).await
+        }
+        Command::Join { cell_id, sealed: Some(moves) } => {
+            let cell = client.get_cell(cell_id).await.map_err(revert)?;
+            let seal = client.get_cell_seal(cell_id).await.map_err(revert)?;
+            let strategy = player_strategy(client, seal, &moves, ethers::core::rand::random())?;
+            if strategy.moves.len() != cell.total_rounds as usize {
+                return Err(eyre!("cell #{cell_id} has {} rounds, seal a move for each", cell.total_rounds));
+            }
+            client.join_sealed_cell(cell_id, &strategy, cell.stake_amount).await.map_err(revert)?;
+            output::print_sealed_seed(format, cell_id, &moves, H256(strategy.seed.0));
+            Ok(())
+        }
+        Command::Join { cell_id, sealed: None } => {
+            let cell = client.get_cell(cell_id).await.map_err(revert)?;
+            client.join_cell(cell_id, cell.stake_amount).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::Cancel { cell_id } => {
+            client.cancel_cell(cell_id).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::Move { cell_id, choice, decision } => {
+            match decision {
+                Some(decision) => {
+                    let wants_continue = matches!(decision, Decision::Yes);
+                    client
+                        .submit_move_with_continuation(cell_id, choice.into(), wants_continue)
+                        .await
+                        .map_err(revert)?;
+                }
+                None => {
+                    client.submit_move(cell_id, choice.into()).await.map_err(revert)?;
+                }
+            }
+            status(client, cell_id, format).await
+        }
+        Command::Commit { cell_id, choice } => {
+            let salt: [u8; 32] = ethers::core::rand::random();
+            client.commit_move(cell_id, choice.into(), salt).await.map_err(revert)?;
+            output::print_salt(format, cell_id, H256(salt));
+            Ok(())
+        }
+        Command::Reveal { cell_id, choice, salt } => {
+            client.reveal_move(cell_id, choice.into(), salt.0).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::Timeout { cell_id } => {
+            client.claim_reveal_timeout(cell_id).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::RevealSealed { cell_id, moves, seed, round } => {
+            let seal = client.get_cell_seal(cell_id).await.map_err(revert)?;
+            let strategy = player_strategy(client, seal, &moves, seed.0)?;
+            let rounds = match round {
+                Some(round) => vec![round],
+                None => (1..=strategy.moves.len() as u8).collect(),
+            };
+            let reveals = rounds
+                .into_iter()
+                .map(|round| strategy.reveal(round).ok_or_else(|| eyre!("no sealed move for round {round}")))
+                .collect::<Result<Vec<_>>>()?;
+            client.reveal_sealed_moves(cell_id, &reveals).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::Withdraw => {
+            let player = sender(client);
+            let amount = client.get_withdrawable(player).await.map_err(revert)?;
+            client.withdraw().await.map_err(revert)?;
+            output::print_withdrawal(format, player, amount);
+            Ok(())
+        }
+        Command::Rematch { cell_id, action } => {
+            let previous = client.get_cell(cell_id).await.map_err(revert)?;
+            match action {
+                RematchAction::Offer => {
+                    let rematch_id =
+                        client.offer_rematch(cell_id, previous.stake_amount).await.map_err(revert)?;
+                    status(client, rematch_id, format).await
+                }
+                RematchAction::Accept => {
+                    // The offer may stake differently from the previous cell
+                    let (_, offered_id) = client.get_rematch_links(cell_id).await.map_err(revert)?;
+                    let offered = client.get_cell(offered_id).await.map_err(revert)?;
+                    let rematch_id =
+                        client.accept_rematch(cell_id, offered.stake_amount).await.map_err(revert)?;
+                    status(client, rematch_id, format).await
+                }
+                RematchAction::Cancel => {
+                    let (_, rematch_id) = client.get_rematch_links(cell_id).await.map_err(revert)?;
+                    client.cancel_cell(rematch_id).await.map_err(revert)?;
+                    status(client, rematch_id, format).await
+                }
+            }
+        }
+        Command::Continue { cell_id, decision } => {
+            let wants_continue = matches!(decision, Decision::Yes);
+            client
+                .submit_continuation_decision(cell_id, wants_continue)
+                .await
+                .map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::Raise { cell_id, stake } => {
+            let stake = parse_ether(&stake).wrap_err_with(|| format!("invalid stake {stake}"))?;
+            let cell = client.get_cell(cell_id).await.map_err(revert)?;
+            let current = cell.rounds.last().map_or(cell.stake_amount, |round| round.stake);
+            // Stakes that are not above the current one are rejected by the contract
+            let top_up = stake.saturating_sub(current);
+            client.propose_raise(cell_id, stake, top_up).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::AcceptRaise { cell_id } => {
+            let raise = client.get_pending_raise(cell_id).await.map_err(revert)?;
+            let raise = raise.ok_or_else(|| eyre!("no raise is pending on cell #{cell_id}"))?;
+            client.accept_raise(cell_id, raise.top_up).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        Command::Policy { cell_id, policy, rounds, floor } => {
+            let policy = match policy {
+                PolicyChoice::Manual => ContinuationPolicy::Manual,
+                PolicyChoice::Always => ContinuationPolicy::Always,
+                PolicyChoice::StopWhenDefected => ContinuationPolicy::StopWhenDefected,
+                PolicyChoice::StopAfter => {
+                    let rounds = rounds.ok_or_else(|| eyre!("stop-after needs --rounds"))?;
+                    ContinuationPolicy::StopAfterRounds(rounds)
+                }
+                PolicyChoice::StopBelow => {
+                    let floor = floor.ok_or_else(|| eyre!("stop-below needs --floor"))?;
+                    ContinuationPolicy::StopBelowNet(to_core_i256(parse_signed_ether(&floor)?))
+                }
+            };
+            client.set_continuation_policy(cell_id, policy).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        _ => unreachable!("read-only commands are handled in `run`"),
+    }
+}
+
+async fn channel(
+    client: &PrisonersDilemmaClient<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    action: ChannelAction,
+    format: Format,
+) -> Result<()> {
+    match action {
+        ChannelAction::Sign { cell_id, rounds } => {
+            let cell = client.get_cell(cell_id).await.map_err(revert)?;
+            let state = cell.channel_state(parse_rounds(&rounds)?);
+            let digest = client.channel_digest(&state).await.map_err(revert)?;
+            let signature = client.contract().client().signer().sign_hash(digest)?;
+            output::print_channel_signature(format, &state, &signature.to_vec().into());
+            Ok(())
+        }
+        ChannelAction::Settle { cell_id, rounds, sig1, sig2 } => {
+            let cell = client.get_cell(cell_id).await.map_err(revert)?;
+            let state = cell.channel_state(parse_rounds(&rounds)?);
+            client.settle_offchain(cell_id, &state, sig1, sig2).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+        ChannelAction::Finalize { cell_id } => {
+            client.finalize_offchain(cell_id).await.map_err(revert)?;
+            status(client, cell_id, format).await
+        }
+    }
+}
+
+// Rounds written as player 1's then player 2's move, `c` or `d`, separated by commas
+fn parse_rounds(rounds: &str) -> Result<Vec<(Move, Move)>> {
+    let parse = |c: char| match c.to_ascii_lowercase() {
+        'c' => Ok(Move::Cooperate),
+        'd' => Ok(Move::Defect),
+        _ => Err(eyre!("invalid move {c:?} in {rounds:?}, use c or d")),
+    };
+    rounds
+        .split(',')
+        .map(|round| match round.trim().chars().collect::<Vec<_>>()[..] {
+            [p1, p2] => Ok((parse(p1)?, parse(p2)?)),
+            _ => Err(eyre!("invalid round {round:?}, write both moves like cd")),
+        })
+        .collect()
+}
+
+// The sender's sealed strategy in the cell with `seal` for moves written like `ccd`
+fn player_strategy<M: Middleware + 'static>(
+    client: &PrisonersDilemmaClient<M>,
+    seal: [u8; 32],
+    moves: &str,
+    seed: [u8; 32],
+) -> Result<SealedStrategy> {
+    let moves = moves
+        .trim()
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            'c' => Ok(Move::Cooperate),
+            'd' => Ok(Move::Defect),
+            _ => Err(eyre!("invalid move {c:?} in {moves:?}, use c or d")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(sealed_strategy(seal, sender(client), moves, seed))
+}
+
+fn sender<M: Middleware + 'static>(client: &PrisonersDilemmaClient<M>) -> Address {
+    client.contract().client().default_sender().unwrap_or_default()
+}
+
+async fn status<M: Middleware + 'static>(
+    client: &PrisonersDilemmaClient<M>,
+    cell_id: U256,
+    format: Format,
+) -> Result<()> {
+    let cell = client.get_cell(cell_id).await.map_err(revert)?;
+    output::print_cell(format, &cell);
+    Ok(())
+}
+
+// An optional ETH amount, where absent means zero
+fn parse_stake(stake: Option<String>) -> Result<U256> {
+    match stake {
+        Some(stake) => parse_ether(&stake).wrap_err_with(|| format!("invalid stake {stake}")),
+        None => Ok(U256::zero()),
+    }
+}
+
+// An ETH amount that may be negative
+fn parse_signed_ether(amount: &str) -> Result<I256> {
+    let (negative, digits) = match amount.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, amount),
+    };
+    let wei = parse_ether(digits).wrap_err_with(|| format!("invalid amount {amount}"))?;
+    let wei = I256::try_from(wei).wrap_err_with(|| format!("amount {amount} is too large"))?;
+    Ok(if negative { -wei } else { wei })
+}
+
+async fn signing_client(
+    provider: Provider<Http>,
+    address: Address,
+    key_path: Option<String>,
+) -> Result<PrisonersDilemmaClient<SignerMiddleware<Provider<Http>, LocalWallet>>> {
+    let key_path = key_path.ok_or_else(|| eyre!("no private key, set PRIV_KEY_PATH or pass --priv-key-path"))?;
+    let key = fs::read_to_string(&key_path).wrap_err_with(|| format!("could not read {key_path}"))?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let wallet = key
+        .trim()
+        .trim_start_matches("0x")
+        .parse::<LocalWallet>()
+        .wrap_err("invalid private key")?
+        .with_chain_id(chain_id);
+    Ok(PrisonersDilemmaClient::new(address, Arc::new(SignerMiddleware::new(provider, wallet))))
+}
+
+// Reverts are reported by their contract error name, everything else as-is
+fn revert<M: Middleware>(err: ClientError<M>) -> eyre::Report {
+    match err {
+        ClientError::Revert(revert) => output::RevertError(revert).into(),
+        err => eyre!("{err}"),
+    }
+}
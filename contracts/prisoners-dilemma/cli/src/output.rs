@@ -0,0 +1,865 @@
+// Human-readable and JSON rendering of cells, rounds, events and errors
+use std::fmt;
+
+use ethers::{
+    types::{Address, Bytes, H256, I256, U256},
+    utils::format_ether,
+};
+use prisoners_dilemma_client::{
+    Badge, Cell, CellState, ContinuationPolicy, Move, OpenCell, PrisonersDilemmaErrors, PrisonersDilemmaEvents, Round,
+    ChannelState, SettlementPreview, from_core_u256, to_core_i256,
+};
+use serde_json::{json, Value};
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+pub fn print_cell(format: Format, cell: &Cell) {
+    match format {
+        Format::Json => println!("{}", cell_json(cell)),
+        Format::Text => {
+            println!("Cell #{}", cell.cell_id);
+            println!("  player 1:  {:?}", cell.player1);
+            if cell.player2.is_zero() {
+                println!("  player 2:  waiting for opponent");
+            } else {
+                println!("  player 2:  {:?}", cell.player2);
+            }
+            println!("  stake:     {} ETH", format_ether(cell.stake_amount));
+            println!("  round:     {} of {}", cell.current_round, cell.total_rounds);
+            println!("  status:    {}", status(cell));
+
+            let continuation = cell.continuation;
+            if !cell.is_complete && (continuation.player1_decided || continuation.player2_decided) {
+                println!(
+                    "  continue:  player 1 {}, player 2 {}",
+                    decision(continuation.player1_decided, continuation.player1_wants),
+                    decision(continuation.player2_decided, continuation.player2_wants),
+                );
+            }
+            if let Some(round) = cell.rounds.last().filter(|round| round.is_finished) {
+                println!("  last:      {}", outcome(round));
+            }
+        }
+    }
+}
+
+pub fn print_history(format: Format, cell: &Cell) {
+    match format {
+        Format::Json => println!("{}", cell_json(cell)),
+        Format::Text => {
+            println!("Cell #{} ({})", cell.cell_id, status(cell));
+            for (idx, round) in cell.rounds.iter().enumerate() {
+                // Rounds after a raise note the stake they were played at
+                let raised = if round.stake > cell.stake_amount {
+                    format!(" at {} ETH", format_ether(round.stake))
+                } else {
+                    String::new()
+                };
+                if round.is_finished {
+                    println!("  round {}: {}{raised}", idx + 1, outcome(round));
+                } else {
+                    println!("  round {}: in progress{raised}", idx + 1);
+                }
+            }
+            let (total_p1, total_p2) = totals(cell);
+            println!(
+                "  total:   player 1 {} ETH, player 2 {} ETH",
+                format_ether(total_p1),
+                format_ether(total_p2)
+            );
+        }
+    }
+}
+
+pub fn print_event(format: Format, event: &PrisonersDilemmaEvents) {
+    let (name, cell_id, text, details) = match event {
+        PrisonersDilemmaEvents::CellCreatedFilter(e) => (
+            "CellCreated",
+            e.cell_id,
+            format!("created by {:?} staking {} ETH", e.player_1, format_ether(e.stake)),
+            json!({ "player1": e.player_1, "stake": e.stake.to_string() }),
+        ),
+        PrisonersDilemmaEvents::PlayerJoinedFilter(e) => (
+            "PlayerJoined",
+            e.cell_id,
+            format!("joined by {:?}", e.player_2),
+            json!({ "player2": e.player_2 }),
+        ),
+        PrisonersDilemmaEvents::GroupPlayerJoinedFilter(e) => (
+            "GroupPlayerJoined",
+            e.cell_id,
+            format!("joined by {:?}", e.player),
+            json!({ "player": e.player }),
+        ),
+        PrisonersDilemmaEvents::RoundCompleteFilter(e) => (
+            "RoundComplete",
+            e.cell_id,
+            format!("round {} complete", e.round_num),
+            json!({ "round": e.round_num }),
+        ),
+        PrisonersDilemmaEvents::WagerPlacedFilter(e) => (
+            "WagerPlaced",
+            e.cell_id,
+            format!("{:?} bet {} ETH on bucket {}", e.bettor, format_ether(e.amount), e.bucket),
+            json!({ "bettor": e.bettor, "bucket": e.bucket, "amount": e.amount.to_string() }),
+        ),
+        PrisonersDilemmaEvents::WagersSettledFilter(e) => (
+            "WagersSettled",
+            e.cell_id,
+            format!("wagers settled, winning buckets {:#07b}", e.winning_buckets),
+            json!({ "winning_buckets": e.winning_buckets }),
+        ),
+        PrisonersDilemmaEvents::WagerClaimedFilter(e) => (
+            "WagerClaimed",
+            e.cell_id,
+            format!("{:?} claimed {} ETH", e.bettor, format_ether(e.amount)),
+            json!({ "bettor": e.bettor, "amount": e.amount.to_string() }),
+        ),
+        PrisonersDilemmaEvents::MoveCommittedFilter(e) => (
+            "MoveCommitted",
+            e.cell_id,
+            format!("{:?} committed a move for round {}", e.player, e.round_num),
+            json!({ "player": e.player, "round": e.round_num }),
+        ),
+        PrisonersDilemmaEvents::ContinuationDrawnFilter(e) => (
+            "ContinuationDrawn",
+            e.cell_id,
+            format!(
+                "drew {} after round {}: {}",
+                e.draw,
+                e.round_num,
+                if e.continues { "playing on" } else { "game over" }
+            ),
+            json!({ "round": e.round_num, "draw": e.draw, "continues": e.continues }),
+        ),
+        PrisonersDilemmaEvents::ContinuationPolicySetFilter(e) => (
+            "ContinuationPolicySet",
+            e.cell_id,
+            format!("{:?} set continuation policy: {}", e.player, policy_name(e.policy, e.param)),
+            json!({ "player": e.player, "policy": e.policy, "param": e.param.to_string() }),
+        ),
+        PrisonersDilemmaEvents::CellStateChangedFilter(e) => (
+            "CellStateChanged",
+            e.cell_id,
+            format!("{} -> {}", state_name(e.from), state_name(e.to)),
+            json!({ "from": e.from, "to": e.to }),
+        ),
+        PrisonersDilemmaEvents::ChannelStateSubmittedFilter(e) => (
+            "ChannelStateSubmitted",
+            e.cell_id,
+            format!("off-chain state after {} rounds submitted, challengeable until {}", e.rounds, e.challenge_deadline),
+            json!({ "rounds": e.rounds, "challenge_deadline": e.challenge_deadline.to_string() }),
+        ),
+        PrisonersDilemmaEvents::StrategySealedFilter(e) => (
+            "StrategySealed",
+            e.cell_id,
+            format!("{:?} sealed their strategy", e.player),
+            json!({ "player": e.player, "root": H256(e.root) }),
+        ),
+        PrisonersDilemmaEvents::SealedMovesRevealedFilter(e) => (
+            "SealedMovesRevealed",
+            e.cell_id,
+            format!("{:?} revealed {} sealed moves", e.player, e.count),
+            json!({ "player": e.player, "count": e.count }),
+        ),
+        PrisonersDilemmaEvents::RematchOfferedFilter(e) => (
+            "RematchOffered",
+            e.previous_cell_id,
+            format!("{:?} offered a rematch as cell #{}", e.player, e.cell_id),
+            json!({ "player": e.player, "rematch_cell_id": e.cell_id.to_string() }),
+        ),
+        PrisonersDilemmaEvents::PayoutCreditedFilter(e) => (
+            "PayoutCredited",
+            e.cell_id,
+            format!("{:?} credited {} ETH for round {}", e.player, format_ether(e.amount), e.round_num),
+            json!({ "player": e.player, "round": e.round_num, "amount": e.amount.to_string() }),
+        ),
+        PrisonersDilemmaEvents::RaiseProposedFilter(e) => (
+            "RaiseProposed",
+            e.cell_id,
+            format!("{:?} proposed raising the stake to {} ETH", e.player, format_ether(e.stake)),
+            json!({ "player": e.player, "stake": e.stake.to_string() }),
+        ),
+        PrisonersDilemmaEvents::RaiseAcceptedFilter(e) => (
+            "RaiseAccepted",
+            e.cell_id,
+            format!("{:?} deposited for a stake of {} ETH", e.player, format_ether(e.stake)),
+            json!({ "player": e.player, "stake": e.stake.to_string() }),
+        ),
+        PrisonersDilemmaEvents::RaiseRefundedFilter(e) => (
+            "RaiseRefunded",
+            e.cell_id,
+            format!("{:?} credited back {} ETH from a lapsed raise", e.player, format_ether(e.amount)),
+            json!({ "player": e.player, "amount": e.amount.to_string() }),
+        ),
+        // Not tied to a cell; `withdraw` reports it to the player
+        PrisonersDilemmaEvents::WithdrawnFilter(_) => return,
+        PrisonersDilemmaEvents::CellCancelledFilter(e) => {
+            ("CellCancelled", e.cell_id, "cancelled, stake refunded".to_string(), json!({}))
+        }
+        PrisonersDilemmaEvents::CellCompleteFilter(e) => {
+            ("CellComplete", e.cell_id, "complete".to_string(), json!({}))
+        }
+    };
+
+    match format {
+        Format::Json => {
+            let mut value = json!({ "event": name, "cell_id": cell_id.to_string() });
+            value.as_object_mut().unwrap().extend(details.as_object().unwrap().clone());
+            println!("{value}");
+        }
+        Format::Text => println!("cell #{cell_id}: {text}"),
+    }
+}
+
+pub fn print_open_cells(format: Format, cells: &[OpenCell]) {
+    match format {
+        Format::Json => {
+            let cells: Vec<Value> = cells
+                .iter()
+                .map(|cell| {
+                    json!({
+                        "cell_id": cell.cell_id.to_string(),
+                        "creator": cell.creator,
+                        "stake_amount": cell.stake_amount.to_string(),
+                        "total_rounds": cell.total_rounds,
+                        "noise_bps": cell.noise_bps,
+                        "continuation_bps": cell.continuation_bps,
+                    })
+                })
+                .collect();
+            println!("{}", json!(cells));
+        }
+        Format::Text if cells.is_empty() => println!("No open cells"),
+        Format::Text => {
+            for cell in cells {
+                let mut settings = format!("{} rounds", cell.total_rounds);
+                if cell.noise_bps > 0 {
+                    settings += &format!(", noise {} bps", cell.noise_bps);
+                }
+                if cell.continuation_bps > 0 {
+                    settings += &format!(", continuation {} bps", cell.continuation_bps);
+                }
+                println!(
+                    "Cell #{}  {} ETH  {}  by {:?}",
+                    cell.cell_id,
+                    format_ether(cell.stake_amount),
+                    settings,
+                    cell.creator
+                );
+            }
+        }
+    }
+}
+
+pub fn print_badges(format: Format, player: Address, badges: &[(U256, Badge)]) {
+    match format {
+        Format::Json => {
+            let badges: Vec<Value> = badges
+                .iter()
+                .map(|&(token_id, badge)| {
+                    json!({ "token_id": token_id.to_string(), "badge": badge as u8, "name": badge.name() })
+                })
+                .collect();
+            println!("{}", json!({ "player": player, "badges": badges }));
+        }
+        Format::Text if badges.is_empty() => println!("{player:?} has no badges yet"),
+        Format::Text => {
+            println!("Badges of {player:?}");
+            for &(token_id, badge) in badges {
+                println!("  #{token_id}  {}: {}", badge.name(), badge.description());
+            }
+        }
+    }
+}
+
+pub fn print_settlement(format: Format, cell_id: U256, (p1, p2): (SettlementPreview, SettlementPreview)) {
+    match format {
+        Format::Json => {
+            let side = |p: SettlementPreview| {
+                json!({
+                    "accumulated": p.accumulated.to_string(),
+                    "if_ended": p.if_ended.to_string(),
+                    "best_case": p.best_case.to_string(),
+                    "worst_case": p.worst_case.to_string(),
+                })
+            };
+            println!("{}", json!({ "cell_id": cell_id.to_string(), "player1": side(p1), "player2": side(p2) }));
+        }
+        Format::Text => {
+            println!("Cell #{cell_id} settlement (ETH)");
+            println!("  {:<10} {:>12} {:>12} {:>12} {:>12}", "", "so far", "if ended", "best", "worst");
+            for (name, p) in [("player 1", p1), ("player 2", p2)] {
+                println!(
+                    "  {:<10} {:>12} {:>12} {:>12} {:>12}",
+                    name,
+                    format_ether(p.accumulated),
+                    format_ether(p.if_ended),
+                    format_ether(p.best_case),
+                    format_ether(p.worst_case),
+                );
+            }
+        }
+    }
+}
+
+pub fn print_channel_signature(format: Format, state: &ChannelState, signature: &Bytes) {
+    match format {
+        Format::Json => println!(
+            "{}",
+            json!({
+                "cell_id": state.cell_id.to_string(),
+                "rounds": state.rounds.len(),
+                "player1_total": state.player1_total.to_string(),
+                "player2_total": state.player2_total.to_string(),
+                "state": Bytes::from(state.encode()),
+                "signature": signature,
+            })
+        ),
+        Format::Text => {
+            println!("Signed cell #{} after {} rounds", state.cell_id, state.rounds.len());
+            println!(
+                "  totals:    player 1 {} ETH, player 2 {} ETH",
+                format_ether(from_core_u256(state.player1_total)),
+                format_ether(from_core_u256(state.player2_total))
+            );
+            println!("  signature: {signature}");
+        }
+    }
+}
+
+pub fn print_salt(format: Format, cell_id: U256, salt: H256) {
+    match format {
+        Format::Json => println!("{}", json!({ "cell_id": cell_id.to_string(), "salt": salt })),
+        Format::Text => {
+            println!("Committed a move in cell #{cell_id}. Reveal it once your opponent has committed with:");
+            println!("  prisoners-dilemma-cli reveal {cell_id} <same choice> {salt:?}");
+        }
+    }
+}
+
+pub fn print_withdrawal(format: Format, player: Address, amount: U256) {
+    match format {
+        Format::Json => println!("{}", json!({ "player": player, "amount": amount.to_string() })),
+        Format::Text => println!("Withdrew {} ETH to {player:?}", format_ether(amount)),
+    }
+}
+
+pub fn print_sealed_seed(format: Format, cell_id: U256, moves: &str, seed: H256) {
+    match format {
+        Format::Json => println!("{}", json!({ "cell_id": cell_id.to_string(), "moves": moves, "seed": seed })),
+        Format::Text => {
+            println!("Sealed your moves in cell #{cell_id}. Once your opponent has joined, reveal them with:");
+            println!("  prisoners-dilemma-cli reveal-sealed {cell_id} {moves} {seed:?}");
+        }
+    }
+}
+
+pub fn print_error(format: Format, err: &eyre::Report) {
+    match format {
+        Format::Json => {
+            let value = match err.downcast_ref::<RevertError>() {
+                Some(revert) => json!({
+                    "error": revert.name(),
+                    "cell_id": revert.cell_id().map(|id| id.to_string()),
+                    "message": revert.to_string(),
+                }),
+                None => json!({ "error": "Error", "message": format!("{err:#}") }),
+            };
+            println!("{value}");
+        }
+        Format::Text => eprintln!("error: {err:#}"),
+    }
+}
+
+// A contract revert decoded into its named `PrisonersDilemmaErrors` variant
+#[derive(Debug)]
+pub struct RevertError(pub PrisonersDilemmaErrors);
+
+impl RevertError {
+    pub fn name(&self) -> &'static str {
+        match &self.0 {
+            PrisonersDilemmaErrors::StakeTooLow(_) => "StakeTooLow",
+            PrisonersDilemmaErrors::AlreadyInCell(_) => "AlreadyInCell",
+            PrisonersDilemmaErrors::CellFull(_) => "CellFull",
+            PrisonersDilemmaErrors::WrongStake(_) => "WrongStake",
+            PrisonersDilemmaErrors::CellIsComplete(_) => "CellIsComplete",
+            PrisonersDilemmaErrors::NeedPlayer2(_) => "NeedPlayer2",
+            PrisonersDilemmaErrors::NotInCell(_) => "NotInCell",
+            PrisonersDilemmaErrors::NoRoundStarted(_) => "NoRoundStarted",
+            PrisonersDilemmaErrors::RoundNotReady(_) => "RoundNotReady",
+            PrisonersDilemmaErrors::RoundAlreadyFinished(_) => "RoundAlreadyFinished",
+            PrisonersDilemmaErrors::MaxRoundsReached(_) => "MaxRoundsReached",
+            PrisonersDilemmaErrors::InvalidCellData(_) => "InvalidCellData",
+            PrisonersDilemmaErrors::InvalidGroupSize(_) => "InvalidGroupSize",
+            PrisonersDilemmaErrors::InvalidMultiplier(_) => "InvalidMultiplier",
+            PrisonersDilemmaErrors::WaitingForPlayers(_) => "WaitingForPlayers",
+            PrisonersDilemmaErrors::InvalidBucket(_) => "InvalidBucket",
+            PrisonersDilemmaErrors::BettingClosed(_) => "BettingClosed",
+            PrisonersDilemmaErrors::PlayerCannotBet(_) => "PlayerCannotBet",
+            PrisonersDilemmaErrors::WagersNotSettled(_) => "WagersNotSettled",
+            PrisonersDilemmaErrors::NoWager(_) => "NoWager",
+            PrisonersDilemmaErrors::TransferFailed(_) => "TransferFailed",
+            PrisonersDilemmaErrors::InvalidProbability(_) => "InvalidProbability",
+            PrisonersDilemmaErrors::CommitRequired(_) => "CommitRequired",
+            PrisonersDilemmaErrors::AlreadyCommitted(_) => "AlreadyCommitted",
+            PrisonersDilemmaErrors::NoCommitment(_) => "NoCommitment",
+            PrisonersDilemmaErrors::WaitingForCommitment(_) => "WaitingForCommitment",
+            PrisonersDilemmaErrors::InvalidReveal(_) => "InvalidReveal",
+            PrisonersDilemmaErrors::ContinuationIsRandom(_) => "ContinuationIsRandom",
+            PrisonersDilemmaErrors::TooManyActiveCells(_) => "TooManyActiveCells",
+            PrisonersDilemmaErrors::NotOwner(_) => "NotOwner",
+            PrisonersDilemmaErrors::CellNotFound(_) => "CellNotFound",
+            PrisonersDilemmaErrors::CellNotComplete(_) => "CellNotComplete",
+            PrisonersDilemmaErrors::RematchUnavailable(_) => "RematchUnavailable",
+            PrisonersDilemmaErrors::NoRematchOffer(_) => "NoRematchOffer",
+            PrisonersDilemmaErrors::OpponentJoined(_) => "OpponentJoined",
+            PrisonersDilemmaErrors::TooManyCells(_) => "TooManyCells",
+            PrisonersDilemmaErrors::Reentrancy(_) => "Reentrancy",
+            PrisonersDilemmaErrors::InvalidPolicy(_) => "InvalidPolicy",
+            PrisonersDilemmaErrors::InvalidTransition(_) => "InvalidTransition",
+            PrisonersDilemmaErrors::ChannelUnavailable(_) => "ChannelUnavailable",
+            PrisonersDilemmaErrors::InvalidChannelState(_) => "InvalidChannelState",
+            PrisonersDilemmaErrors::StaleChannelState(_) => "StaleChannelState",
+            PrisonersDilemmaErrors::NoChannelState(_) => "NoChannelState",
+            PrisonersDilemmaErrors::ChannelDisputed(_) => "ChannelDisputed",
+            PrisonersDilemmaErrors::InvalidSignature(_) => "InvalidSignature",
+            PrisonersDilemmaErrors::ChallengeWindowOpen(_) => "ChallengeWindowOpen",
+            PrisonersDilemmaErrors::ChallengeWindowClosed(_) => "ChallengeWindowClosed",
+            PrisonersDilemmaErrors::SealedCell(_) => "SealedCell",
+            PrisonersDilemmaErrors::NotSealed(_) => "NotSealed",
+            PrisonersDilemmaErrors::InvalidProof(_) => "InvalidProof",
+            PrisonersDilemmaErrors::NothingToWithdraw(_) => "NothingToWithdraw",
+            PrisonersDilemmaErrors::RaiseUnavailable(_) => "RaiseUnavailable",
+            PrisonersDilemmaErrors::InvalidRaise(_) => "InvalidRaise",
+            PrisonersDilemmaErrors::RaisePending(_) => "RaisePending",
+            PrisonersDilemmaErrors::NoRaiseProposed(_) => "NoRaiseProposed",
+            PrisonersDilemmaErrors::NoRevealPending(_) => "NoRevealPending",
+            PrisonersDilemmaErrors::RevealWindowOpen(_) => "RevealWindowOpen",
+            PrisonersDilemmaErrors::TooManyReveals(_) => "TooManyReveals",
+            PrisonersDilemmaErrors::SealUsed(_) => "SealUsed",
+            PrisonersDilemmaErrors::NotCommitReveal(_) => "NotCommitReveal",
+            PrisonersDilemmaErrors::RevertString(_) => "RevertString",
+        }
+    }
+
+    pub fn cell_id(&self) -> Option<U256> {
+        match &self.0 {
+            PrisonersDilemmaErrors::StakeTooLow(e) => Some(e.0),
+            PrisonersDilemmaErrors::AlreadyInCell(e) => Some(e.0),
+            PrisonersDilemmaErrors::CellFull(e) => Some(e.0),
+            PrisonersDilemmaErrors::WrongStake(e) => Some(e.0),
+            PrisonersDilemmaErrors::CellIsComplete(e) => Some(e.0),
+            PrisonersDilemmaErrors::NeedPlayer2(e) => Some(e.0),
+            PrisonersDilemmaErrors::NotInCell(e) => Some(e.0),
+            PrisonersDilemmaErrors::NoRoundStarted(e) => Some(e.0),
+            PrisonersDilemmaErrors::RoundNotReady(e) => Some(e.0),
+            PrisonersDilemmaErrors::RoundAlreadyFinished(e) => Some(e.0),
+            PrisonersDilemmaErrors::MaxRoundsReached(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidCellData(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidGroupSize(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidMultiplier(e) => Some(e.0),
+            PrisonersDilemmaErrors::WaitingForPlayers(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidBucket(e) => Some(e.0),
+            PrisonersDilemmaErrors::BettingClosed(e) => Some(e.0),
+            PrisonersDilemmaErrors::PlayerCannotBet(e) => Some(e.0),
+            PrisonersDilemmaErrors::WagersNotSettled(e) => Some(e.0),
+            PrisonersDilemmaErrors::NoWager(e) => Some(e.0),
+            PrisonersDilemmaErrors::TransferFailed(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidProbability(e) => Some(e.0),
+            PrisonersDilemmaErrors::CommitRequired(e) => Some(e.0),
+            PrisonersDilemmaErrors::AlreadyCommitted(e) => Some(e.0),
+            PrisonersDilemmaErrors::NoCommitment(e) => Some(e.0),
+            PrisonersDilemmaErrors::WaitingForCommitment(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidReveal(e) => Some(e.0),
+            PrisonersDilemmaErrors::ContinuationIsRandom(e) => Some(e.0),
+            PrisonersDilemmaErrors::TooManyActiveCells(e) => Some(e.0),
+            PrisonersDilemmaErrors::NotOwner(e) => Some(e.0),
+            PrisonersDilemmaErrors::CellNotFound(e) => Some(e.0),
+            PrisonersDilemmaErrors::CellNotComplete(e) => Some(e.0),
+            PrisonersDilemmaErrors::RematchUnavailable(e) => Some(e.0),
+            PrisonersDilemmaErrors::NoRematchOffer(e) => Some(e.0),
+            PrisonersDilemmaErrors::OpponentJoined(e) => Some(e.0),
+            PrisonersDilemmaErrors::TooManyCells(e) => Some(e.0),
+            PrisonersDilemmaErrors::Reentrancy(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidPolicy(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidTransition(e) => Some(e.0),
+            PrisonersDilemmaErrors::ChannelUnavailable(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidChannelState(e) => Some(e.0),
+            PrisonersDilemmaErrors::StaleChannelState(e) => Some(e.0),
+            PrisonersDilemmaErrors::NoChannelState(e) => Some(e.0),
+            PrisonersDilemmaErrors::ChannelDisputed(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidSignature(e) => Some(e.0),
+            PrisonersDilemmaErrors::ChallengeWindowOpen(e) => Some(e.0),
+            PrisonersDilemmaErrors::ChallengeWindowClosed(e) => Some(e.0),
+            PrisonersDilemmaErrors::SealedCell(e) => Some(e.0),
+            PrisonersDilemmaErrors::NotSealed(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidProof(e) => Some(e.0),
+            PrisonersDilemmaErrors::NothingToWithdraw(e) => Some(e.0),
+            PrisonersDilemmaErrors::RaiseUnavailable(e) => Some(e.0),
+            PrisonersDilemmaErrors::InvalidRaise(e) => Some(e.0),
+            PrisonersDilemmaErrors::RaisePending(e) => Some(e.0),
+            PrisonersDilemmaErrors::NoRaiseProposed(e) => Some(e.0),
+            PrisonersDilemmaErrors::NoRevealPending(e) => Some(e.0),
+            PrisonersDilemmaErrors::RevealWindowOpen(e) => Some(e.0),
+            PrisonersDilemmaErrors::TooManyReveals(e) => Some(e.0),
+            PrisonersDilemmaErrors::SealUsed(e) => Some(e.0),
+            PrisonersDilemmaErrors::NotCommitReveal(e) => Some(e.0),
+            PrisonersDilemmaErrors::RevertString(_) => None,
+        }
+    }
+
+    fn message(&self) -> String {
+        match &self.0 {
+            PrisonersDilemmaErrors::StakeTooLow(_) => "stake is below the contract minimum".into(),
+            PrisonersDilemmaErrors::AlreadyInCell(_) => "you are already a player in this cell".into(),
+            PrisonersDilemmaErrors::CellFull(_) => "the cell has no free seats".into(),
+            PrisonersDilemmaErrors::WrongStake(_) => "stake must match the cell's stake".into(),
+            PrisonersDilemmaErrors::CellIsComplete(_) => "the cell is already complete".into(),
+            PrisonersDilemmaErrors::NeedPlayer2(_) => "the cell is still waiting for an opponent".into(),
+            PrisonersDilemmaErrors::NotInCell(_) => "you are not a player in this cell".into(),
+            PrisonersDilemmaErrors::NoRoundStarted(_) => "no round has started yet".into(),
+            PrisonersDilemmaErrors::RoundNotReady(_) => "the current round is not ready".into(),
+            PrisonersDilemmaErrors::RoundAlreadyFinished(_) => {
+                "you already moved this round, or it is finished".into()
+            }
+            PrisonersDilemmaErrors::MaxRoundsReached(_) => "the cell has reached its last round".into(),
+            PrisonersDilemmaErrors::InvalidCellData(_) => "the stored cell data is invalid".into(),
+            PrisonersDilemmaErrors::InvalidGroupSize(_) => "group cells take between 3 and 10 players".into(),
+            PrisonersDilemmaErrors::InvalidMultiplier(_) => {
+                "the pot multiplier must be above 1x and below the number of players".into()
+            }
+            PrisonersDilemmaErrors::WaitingForPlayers(_) => "the group cell is still waiting for players".into(),
+            PrisonersDilemmaErrors::InvalidBucket(_) => "wager buckets are 0-4".into(),
+            PrisonersDilemmaErrors::BettingClosed(_) => {
+                "betting is only open while the cell waits for its second player".into()
+            }
+            PrisonersDilemmaErrors::PlayerCannotBet(_) => "players cannot bet on their own cell".into(),
+            PrisonersDilemmaErrors::WagersNotSettled(_) => "the cell has not completed yet".into(),
+            PrisonersDilemmaErrors::NoWager(_) => "you have no unclaimed wager on this cell".into(),
+            PrisonersDilemmaErrors::TransferFailed(_) => "the ETH transfer failed".into(),
+            PrisonersDilemmaErrors::InvalidProbability(_) => "probabilities must be at most 10000 basis points".into(),
+            PrisonersDilemmaErrors::CommitRequired(_) => "moves in this cell must be committed and revealed".into(),
+            PrisonersDilemmaErrors::AlreadyCommitted(_) => "you already committed a move this round".into(),
+            PrisonersDilemmaErrors::NoCommitment(_) => "you have not committed a move this round".into(),
+            PrisonersDilemmaErrors::WaitingForCommitment(_) => "the opponent has not committed a move yet".into(),
+            PrisonersDilemmaErrors::InvalidReveal(_) => "the move and salt do not match your commitment".into(),
+            PrisonersDilemmaErrors::ContinuationIsRandom(_) => "this cell continues by chance, not by vote".into(),
+            PrisonersDilemmaErrors::TooManyActiveCells(_) => {
+                "you are already in as many cells as the contract allows".into()
+            }
+            PrisonersDilemmaErrors::NotOwner(_) => "only the contract owner can do this".into(),
+            PrisonersDilemmaErrors::CellNotFound(_) => "no cell exists with this id".into(),
+            PrisonersDilemmaErrors::CellNotComplete(_) => "the cell has not completed yet".into(),
+            PrisonersDilemmaErrors::RematchUnavailable(_) => {
+                "a rematch of this cell is already offered or played".into()
+            }
+            PrisonersDilemmaErrors::NoRematchOffer(_) => {
+                "there is no rematch offer from your opponent on this cell".into()
+            }
+            PrisonersDilemmaErrors::OpponentJoined(_) => "an opponent has already joined this cell".into(),
+            PrisonersDilemmaErrors::TooManyCells(_) => "too many cell ids in one call, the limit is 50".into(),
+            PrisonersDilemmaErrors::Reentrancy(_) => "the contract is already processing a call".into(),
+            PrisonersDilemmaErrors::InvalidPolicy(_) => {
+                "unknown continuation policy, or a round count above 255".into()
+            }
+            PrisonersDilemmaErrors::InvalidTransition(_) => "the cell's lifecycle state does not allow this".into(),
+            PrisonersDilemmaErrors::ChannelUnavailable(_) => {
+                "noise, continuation-probability, sealed, streamed and raised cells cannot be played off-chain".into()
+            }
+            PrisonersDilemmaErrors::InvalidChannelState(_) => {
+                "the channel state is malformed or does not match the cell".into()
+            }
+            PrisonersDilemmaErrors::StaleChannelState(_) => {
+                "the channel state has no rounds beyond those already recorded".into()
+            }
+            PrisonersDilemmaErrors::NoChannelState(_) => "the cell has no channel state to finalize".into(),
+            PrisonersDilemmaErrors::ChannelDisputed(_) => {
+                "the cell is waiting for its channel state's challenge window to close".into()
+            }
+            PrisonersDilemmaErrors::InvalidSignature(_) => "the channel state is not signed by both players".into(),
+            PrisonersDilemmaErrors::ChallengeWindowOpen(_) => "the challenge window is still open".into(),
+            PrisonersDilemmaErrors::ChallengeWindowClosed(_) => {
+                "the challenge window has closed, finalize the cell instead".into()
+            }
+            PrisonersDilemmaErrors::SealedCell(_) => "the cell is played from sealed strategies, use reveal-sealed".into(),
+            PrisonersDilemmaErrors::NotSealed(_) => "the cell has no sealed strategies, or the root is zero".into(),
+            PrisonersDilemmaErrors::InvalidProof(_) => "a revealed move does not match the sealed strategy".into(),
+            PrisonersDilemmaErrors::NothingToWithdraw(_) => "there is no credited balance to withdraw".into(),
+            PrisonersDilemmaErrors::RaiseUnavailable(_) => {
+                "raises are only made while a voted cell waits on continuation votes with rounds left".into()
+            }
+            PrisonersDilemmaErrors::InvalidRaise(_) => {
+                "the raised stake is not above the current one, or the deposit is not its top-up".into()
+            }
+            PrisonersDilemmaErrors::RaisePending(_) => {
+                "a raise is already proposed, or you already deposited for it".into()
+            }
+            PrisonersDilemmaErrors::NoRaiseProposed(_) => "no raise is pending on the cell".into(),
+            PrisonersDilemmaErrors::NoRevealPending(_) => {
+                "nobody is waiting on a reveal in the cell's current round".into()
+            }
+            PrisonersDilemmaErrors::RevealWindowOpen(_) => {
+                "your opponent can still reveal, the reveal window has not closed".into()
+            }
+            PrisonersDilemmaErrors::TooManyReveals(_) => {
+                "a reveal can hold at most one move per round of the cell".into()
+            }
+            PrisonersDilemmaErrors::SealUsed(_) => {
+                "you already created a sealed cell with that nonce, seal your strategy with a new one".into()
+            }
+            PrisonersDilemmaErrors::NotCommitReveal(_) => {
+                "this cell does not use commit-reveal moves; submit moves directly".into()
+            }
+            PrisonersDilemmaErrors::RevertString(reason) => reason.clone(),
+        }
+    }
+}
+
+impl fmt::Display for RevertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cell_id() {
+            Some(cell_id) if !cell_id.is_zero() => {
+                write!(f, "{} (cell #{}): {}", self.name(), cell_id, self.message())
+            }
+            _ => write!(f, "{}: {}", self.name(), self.message()),
+        }
+    }
+}
+
+impl std::error::Error for RevertError {}
+
+fn status(cell: &Cell) -> &'static str {
+    cell.state.name()
+}
+
+fn state_name(state: u8) -> &'static str {
+    CellState::from_u8(state).map_or("unknown", CellState::name)
+}
+
+fn policy_name(policy: u8, param: I256) -> String {
+    match ContinuationPolicy::from_parts(policy, to_core_i256(param)) {
+        Some(ContinuationPolicy::Manual) => "vote by hand".to_string(),
+        Some(ContinuationPolicy::Always) => "always continue".to_string(),
+        Some(ContinuationPolicy::StopWhenDefected) => "stop when defected on".to_string(),
+        Some(ContinuationPolicy::StopAfterRounds(rounds)) => format!("stop after {rounds} rounds"),
+        Some(ContinuationPolicy::StopBelowNet(floor)) => {
+            let sign = if floor.is_negative() { "-" } else { "" };
+            format!("stop below a net of {sign}{} ETH", format_ether(from_core_u256(floor.unsigned_abs())))
+        }
+        None => format!("unknown ({policy})"),
+    }
+}
+
+fn decision(decided: bool, wants: bool) -> &'static str {
+    match (decided, wants) {
+        (false, _) => "undecided",
+        (true, true) => "continue",
+        (true, false) => "stop",
+    }
+}
+
+fn move_name(player_move: Option<Move>) -> &'static str {
+    match player_move {
+        Some(Move::Cooperate) => "cooperate",
+        Some(Move::Defect) => "defect",
+        None => "hidden",
+    }
+}
+
+fn outcome(round: &Round) -> String {
+    format!(
+        "{} vs {} -> {} ETH / {} ETH",
+        executed(round.player1_move, round.player1_intended),
+        executed(round.player2_move, round.player2_intended),
+        format_ether(round.player1_payout),
+        format_ether(round.player2_payout),
+    )
+}
+
+// A move, noting when noise flipped it from the one the player chose
+fn executed(player_move: Option<Move>, intended: Option<Move>) -> String {
+    if intended.is_some() && intended != player_move {
+        format!("{} (flipped from {})", move_name(player_move), move_name(intended))
+    } else {
+        move_name(player_move).to_string()
+    }
+}
+
+fn totals(cell: &Cell) -> (U256, U256) {
+    cell.rounds
+        .iter()
+        .filter(|round| round.is_finished)
+        .fold((U256::zero(), U256::zero()), |(p1, p2), round| {
+            (p1 + round.player1_payout, p2 + round.player2_payout)
+        })
+}
+
+fn round_json(round: &Round) -> Value {
+    json!({
+        "player1_move": round.player1_move.map(|m| move_name(Some(m))),
+        "player2_move": round.player2_move.map(|m| move_name(Some(m))),
+        "player1_intended": round.player1_intended.map(|m| move_name(Some(m))),
+        "player2_intended": round.player2_intended.map(|m| move_name(Some(m))),
+        "player1_payout": round.player1_payout.to_string(),
+        "player2_payout": round.player2_payout.to_string(),
+        "is_finished": round.is_finished,
+        "stake": round.stake.to_string(),
+    })
+}
+
+fn cell_json(cell: &Cell) -> Value {
+    let (total_p1, total_p2) = totals(cell);
+    json!({
+        "cell_id": cell.cell_id.to_string(),
+        "player1": cell.player1,
+        "player2": cell.player2,
+        "stake_amount": cell.stake_amount.to_string(),
+        "total_rounds": cell.total_rounds,
+        "current_round": cell.current_round,
+        "is_complete": cell.is_complete,
+        "status": status(cell),
+        "continuation": {
+            "player1_decided": cell.continuation.player1_decided,
+            "player1_wants": cell.continuation.player1_wants,
+            "player2_decided": cell.continuation.player2_decided,
+            "player2_wants": cell.continuation.player2_wants,
+        },
+        "rounds": cell.rounds.iter().map(round_json).collect::<Vec<_>>(),
+        "player1_total": total_p1.to_string(),
+        "player2_total": total_p2.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{
+        abi::{encode, Token},
+        contract::ContractRevert,
+        utils::{id, parse_ether},
+    };
+    use prisoners_dilemma_client::ContinuationStatus;
+
+    use super::*;
+
+    fn round(p1: Move, p2: Move, payouts: (&str, &str)) -> Round {
+        Round {
+            player1_move: Some(p1),
+            player2_move: Some(p2),
+            player1_intended: Some(p1),
+            player2_intended: Some(p2),
+            player1_payout: parse_ether(payouts.0).unwrap(),
+            player2_payout: parse_ether(payouts.1).unwrap(),
+            is_finished: true,
+            sealed: false,
+            stake: parse_ether("1").unwrap(),
+        }
+    }
+
+    // A two-round cell whose second round is still being played
+    fn cell() -> Cell {
+        let playing = Round {
+            player1_move: None,
+            player2_move: None,
+            player1_intended: None,
+            player2_intended: None,
+            player1_payout: parse_ether("9").unwrap(),
+            player2_payout: U256::zero(),
+            is_finished: false,
+            sealed: false,
+            stake: parse_ether("1").unwrap(),
+        };
+        Cell {
+            cell_id: U256::from(7),
+            player1: Address::repeat_byte(1),
+            player2: Address::repeat_byte(2),
+            stake_amount: parse_ether("1").unwrap(),
+            total_rounds: 2,
+            current_round: 2,
+            is_complete: false,
+            is_cancelled: false,
+            rounds: vec![round(Move::Cooperate, Move::Defect, ("0", "2.5")), playing],
+            continuation: ContinuationStatus { player1_decided: true, player1_wants: true, ..Default::default() },
+            state: CellState::AwaitingMoves,
+            challenge_deadline: U256::zero(),
+            reveal_deadline: U256::zero(),
+        }
+    }
+
+    // Revert data as the contract returns it: the error's selector followed
+    // by its ABI-encoded argument, decoded the way the client does
+    fn decoded(signature: &str, argument: Token) -> Option<RevertError> {
+        let data = [&id(signature)[..], &encode(&[argument])].concat();
+        PrisonersDilemmaErrors::decode_with_selector(&data).map(RevertError)
+    }
+
+    #[test]
+    fn outcomes_note_flipped_moves() {
+        let mut noisy = round(Move::Cooperate, Move::Defect, ("0", "2.5"));
+        assert_eq!(outcome(&noisy), "cooperate vs defect -> 0.000000000000000000 ETH / 2.500000000000000000 ETH");
+
+        noisy.player2_intended = Some(Move::Cooperate);
+        assert!(outcome(&noisy).starts_with("cooperate vs defect (flipped from cooperate) ->"));
+        assert_eq!(executed(None, None), "hidden");
+    }
+
+    #[test]
+    fn text_helpers_name_decisions_states_and_policies() {
+        assert_eq!(decision(false, true), "undecided");
+        assert_eq!(decision(true, true), "continue");
+        assert_eq!(decision(true, false), "stop");
+        assert_eq!(status(&cell()), "awaiting moves");
+        assert_eq!(state_name(CellState::TimedOut as u8), "timed out");
+        assert_eq!(state_name(200), "unknown");
+        assert_eq!(policy_name(3, I256::from(4)), "stop after 4 rounds");
+        assert_eq!(policy_name(4, I256::from(-1_000_000_000_000_000_000i64)), "stop below a net of -1.000000000000000000 ETH");
+        assert_eq!(policy_name(9, I256::zero()), "unknown (9)");
+    }
+
+    #[test]
+    fn totals_skip_the_unfinished_round() {
+        assert_eq!(totals(&cell()), (U256::zero(), parse_ether("2.5").unwrap()));
+    }
+
+    #[test]
+    fn cell_json_carries_ids_as_strings_and_moves_by_name() {
+        let value = cell_json(&cell());
+        assert_eq!(value["cell_id"], "7");
+        assert_eq!(value["stake_amount"], "1000000000000000000");
+        assert_eq!(value["status"], "awaiting moves");
+        assert_eq!(value["continuation"]["player1_wants"], true);
+        assert_eq!(value["continuation"]["player2_decided"], false);
+        assert_eq!(value["rounds"][0]["player2_move"], "defect");
+        assert_eq!(value["rounds"][1]["player1_move"], Value::Null);
+        assert_eq!(value["player2_total"], "2500000000000000000");
+    }
+
+    #[test]
+    fn revert_data_decodes_into_a_named_error() {
+        let revert = decoded("StakeTooLow(uint256)", Token::Uint(U256::from(7))).unwrap();
+        assert!(matches!(revert.0, PrisonersDilemmaErrors::StakeTooLow(_)));
+        assert_eq!(revert.name(), "StakeTooLow");
+        assert_eq!(revert.cell_id(), Some(U256::from(7)));
+        assert_eq!(revert.to_string(), "StakeTooLow (cell #7): stake is below the contract minimum");
+
+        // Errors not tied to a cell carry a zero id, which the message leaves out
+        let revert = decoded("NotOwner(uint256)", Token::Uint(U256::zero())).unwrap();
+        assert_eq!(revert.to_string(), "NotOwner: only the contract owner can do this");
+    }
+
+    #[test]
+    fn revert_strings_keep_their_reason() {
+        let revert = decoded("Error(string)", Token::String("paused".into())).unwrap();
+        assert_eq!((revert.name(), revert.cell_id()), ("RevertString", None));
+        assert_eq!(revert.to_string(), "RevertString: paused");
+    }
+
+    #[test]
+    fn unknown_selectors_do_not_decode() {
+        assert!(decoded("Unknown(uint256)", Token::Uint(U256::from(7))).is_none());
+    }
+}
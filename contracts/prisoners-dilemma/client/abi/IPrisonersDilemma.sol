@@ -0,0 +1,279 @@
+/**
+ * This file was automatically generated by Stylus and represents a Rust program.
+ * For more information, please see [The Stylus SDK](https://github.com/OffchainLabs/stylus-sdk-rs).
+ */
+
+// SPDX-License-Identifier: MIT-OR-APACHE-2.0
+pragma solidity ^0.8.23;
+
+interface IPrisonersDilemma  {
+    function initialize(uint256 min_stake) external;
+
+    function setMaxActiveCells(uint32 max_active_cells) external;
+
+    function createCell(uint8 total_rounds) external payable returns (uint256);
+
+    function createResearchCell(uint8 total_rounds, uint16 noise_bps, uint16 continuation_bps) external payable returns (uint256);
+
+    function createStreamedCell(uint8 total_rounds, uint16 noise_bps, uint16 continuation_bps) external payable returns (uint256);
+
+    function createSealedCell(uint256 nonce, uint8 total_rounds, bytes32 root) external payable returns (uint256);
+
+    function joinCell(uint256 cell_id) external payable;
+
+    function joinSealedCell(uint256 cell_id, bytes32 root) external payable;
+
+    function cancelCell(uint256 cell_id) external;
+
+    function submitMove(uint256 cell_id, uint8 move_choice) external;
+
+    function submitMoveWithContinuation(uint256 cell_id, uint8 move_choice, bool wants_continue) external;
+
+    function commitMove(uint256 cell_id, bytes32 commitment) external;
+
+    function revealMove(uint256 cell_id, uint8 move_choice, bytes32 salt) external;
+
+    function claimRevealTimeout(uint256 cell_id) external;
+
+    function submitContinuationDecision(uint256 cell_id, bool wants_continue) external;
+
+    function setContinuationPolicy(uint256 cell_id, uint8 policy, int256 param) external;
+
+    function settleOffchain(uint256 cell_id, bytes calldata state, bytes calldata sig1, bytes calldata sig2) external;
+
+    function finalizeOffchain(uint256 cell_id) external;
+
+    function revealSealedMoves(uint256 cell_id, bytes calldata reveals) external;
+
+    function proposeRaise(uint256 cell_id, uint256 stake) external payable;
+
+    function acceptRaise(uint256 cell_id) external payable;
+
+    function withdraw() external returns (uint256);
+
+    function offerRematch(uint256 previous_cell_id) external payable returns (uint256);
+
+    function acceptRematch(uint256 previous_cell_id) external payable returns (uint256);
+
+    function createGroupCell(uint8 max_players, uint8 total_rounds, uint32 multiplier_bps) external payable returns (uint256);
+
+    function joinGroupCell(uint256 cell_id) external payable;
+
+    function cancelGroupCell(uint256 cell_id) external;
+
+    function submitContribution(uint256 cell_id, bool contributes) external;
+
+    function submitGroupContinuationDecision(uint256 cell_id, bool wants_continue) external;
+
+    function placeWager(uint256 cell_id, uint8 bucket) external payable;
+
+    function claimWager(uint256 cell_id) external returns (uint256);
+
+    function getCell(uint256 cell_id) external view returns (address, address, uint256, uint8, uint8, bool);
+
+    function getCells(uint256[] memory ids) external view returns ((uint256,address,address,uint256,uint8,uint8,bool,bool,bool,bool,bool,bool,uint16,uint16,uint8,uint256,uint256,uint8)[] memory, (uint8,uint8,uint8,uint8,uint256,uint256,bool,bool,uint256)[] memory);
+
+    function getPlayerCell(address player) external view returns (uint256);
+
+    function getPlayerActiveCells(address player) external view returns (uint256[] memory);
+
+    function getMaxActiveCells() external view returns (uint32);
+
+    function getPlayersCell(address player1, address player2) external view returns (uint256);
+
+    function getPlayerCells(address player, uint256 offset, uint256 limit) external view returns (uint256[] memory);
+
+    function getPlayerCellCount(address player) external view returns (uint256);
+
+    function getPairCells(address a, address b, uint256 offset, uint256 limit) external view returns (uint256[] memory);
+
+    function getPairCellCount(address a, address b) external view returns (uint256);
+
+    function getOpenCells(uint256 offset, uint256 limit, uint256 min_stake, uint256 max_stake) external view returns (uint256[] memory, address[] memory, uint256[] memory, uint8[] memory, uint16[] memory, uint16[] memory);
+
+    function getOpenCellCount() external view returns (uint256);
+
+    function getRematchLinks(uint256 cell_id) external view returns (uint256, uint256);
+
+    function getMinStake() external view returns (uint256);
+
+    function getOwner() external view returns (address);
+
+    function getContinuationStatus(uint256 cell_id) external view returns (bool, bool, bool, bool);
+
+    function getContinuationPolicy(uint256 cell_id, address player) external view returns (uint8, int256);
+
+    function getCellState(uint256 cell_id) external view returns (uint8);
+
+    function previewSettlement(uint256 cell_id) external view returns ((uint256, uint256, uint256, uint256), (uint256, uint256, uint256, uint256));
+
+    function getChannelDigest(bytes calldata state) external view returns (bytes32);
+
+    function getChallengeDeadline(uint256 cell_id) external view returns (uint256);
+
+    function getRevealDeadline(uint256 cell_id) external view returns (uint256);
+
+    function getSealedRoots(uint256 cell_id) external view returns (bytes32, bytes32);
+
+    function getCellSeal(uint256 cell_id) external view returns (bytes32);
+
+    function getWithdrawable(address player) external view returns (uint256);
+
+    function getCellStreamed(uint256 cell_id) external view returns (bool);
+
+    function getPendingRaise(uint256 cell_id) external view returns (uint256, uint256, bool, bool);
+
+    function getCellCounter() external view returns (uint256);
+
+    function getRoundResult(uint256 cell_id, uint8 round_number) external view returns (uint8, uint8, uint256, uint256);
+
+    function getGroupCell(uint256 cell_id) external view returns (address[] memory, uint8, uint256, uint32, uint8, uint8, bool, uint16);
+
+    function getGroupRoundResult(uint256 cell_id, uint8 round_number) external view returns (uint8[] memory, uint256[] memory);
+
+    function getRoundMoves(uint256 cell_id, uint8 round_number) external view returns (uint8, uint8, uint8, uint8);
+
+    function getCellNoise(uint256 cell_id) external view returns (uint16);
+
+    function getContinuationProbability(uint256 cell_id) external view returns (uint16);
+
+    function getContinuationDraws(uint256 cell_id) external view returns (uint16[] memory);
+
+    function getMoveCommitment(uint256 cell_id, address player, uint8 move_choice, bytes32 salt) external view returns (bytes32);
+
+    function getWagerPools(uint256 cell_id) external view returns (uint256[] memory);
+
+    function getWagers(uint256 cell_id, address bettor) external view returns (uint256[] memory);
+
+    function getWagerResult(uint256 cell_id) external view returns (bool, uint8);
+
+    function getCellEscrow(uint256 cell_id) external view returns (uint256, uint256, uint256);
+
+    function getTotalEscrowed() external view returns (uint256);
+
+    function checkSolvency() external view returns (bool, uint256, uint256);
+
+    function setBadgeContract(address badges) external;
+
+    function getBadgeContract() external view returns (address);
+
+    error StakeTooLow(uint256);
+
+    error AlreadyInCell(uint256);
+
+    error CellFull(uint256);
+
+    error WrongStake(uint256);
+
+    error CellIsComplete(uint256);
+
+    error NeedPlayer2(uint256);
+
+    error NotInCell(uint256);
+
+    error NoRoundStarted(uint256);
+
+    error RoundNotReady(uint256);
+
+    error RoundAlreadyFinished(uint256);
+
+    error MaxRoundsReached(uint256);
+
+    error InvalidCellData(uint256);
+
+    error InvalidGroupSize(uint256);
+
+    error InvalidMultiplier(uint256);
+
+    error WaitingForPlayers(uint256);
+
+    error InvalidBucket(uint256);
+
+    error BettingClosed(uint256);
+
+    error PlayerCannotBet(uint256);
+
+    error WagersNotSettled(uint256);
+
+    error NoWager(uint256);
+
+    error TransferFailed(uint256);
+
+    error InvalidProbability(uint256);
+
+    error CommitRequired(uint256);
+
+    error AlreadyCommitted(uint256);
+
+    error NoCommitment(uint256);
+
+    error WaitingForCommitment(uint256);
+
+    error InvalidReveal(uint256);
+
+    error ContinuationIsRandom(uint256);
+
+    error TooManyActiveCells(uint256);
+
+    error NotOwner(uint256);
+
+    error CellNotFound(uint256);
+
+    error CellNotComplete(uint256);
+
+    error RematchUnavailable(uint256);
+
+    error NoRematchOffer(uint256);
+
+    error OpponentJoined(uint256);
+
+    error InvalidTransition(uint256);
+
+    error ChannelUnavailable(uint256);
+
+    error InvalidChannelState(uint256);
+
+    error StaleChannelState(uint256);
+
+    error NoChannelState(uint256);
+
+    error ChannelDisputed(uint256);
+
+    error InvalidSignature(uint256);
+
+    error ChallengeWindowOpen(uint256);
+
+    error ChallengeWindowClosed(uint256);
+
+    error SealedCell(uint256);
+
+    error NotSealed(uint256);
+
+    error InvalidProof(uint256);
+
+    error NothingToWithdraw(uint256);
+
+    error RaiseUnavailable(uint256);
+
+    error InvalidRaise(uint256);
+
+    error RaisePending(uint256);
+
+    error NoRaiseProposed(uint256);
+
+    error TooManyCells(uint256);
+
+    error Reentrancy(uint256);
+
+    error InvalidPolicy(uint256);
+
+    error NoRevealPending(uint256);
+
+    error RevealWindowOpen(uint256);
+
+    error TooManyReveals(uint256);
+
+    error SealUsed(uint256);
+
+    error NotCommitReveal(uint256);
+}
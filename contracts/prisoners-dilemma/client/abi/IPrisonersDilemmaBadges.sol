@@ -0,0 +1,53 @@
+/**
+ * This file was automatically generated by Stylus and represents a Rust program.
+ * For more information, please see [The Stylus SDK](https://github.com/OffchainLabs/stylus-sdk-rs).
+ */
+
+// SPDX-License-Identifier: MIT-OR-APACHE-2.0
+pragma solidity ^0.8.23;
+
+interface IPrisonersDilemmaBadges  {
+    function initialize(address game) external;
+
+    function getGame() external view returns (address);
+
+    function mint(uint256 cell_id, address player, uint8 badge) external;
+
+    function getPlayerBadges(address player) external view returns (uint256[] memory, uint8[] memory);
+
+    function name() external view returns (string memory);
+
+    function symbol() external view returns (string memory);
+
+    function tokenURI(uint256 token_id) external view returns (string memory);
+
+    function balanceOf(address owner) external view returns (uint256);
+
+    function ownerOf(uint256 token_id) external view returns (address);
+
+    function locked(uint256 token_id) external view returns (bool);
+
+    function getApproved(uint256 token_id) external view returns (address);
+
+    function isApprovedForAll(address _owner, address _operator) external view returns (bool);
+
+    function approve(address _to, uint256 token_id) external;
+
+    function setApprovalForAll(address _operator, bool _approved) external;
+
+    function transferFrom(address _from, address _to, uint256 token_id) external;
+
+    function safeTransferFrom(address _from, address _to, uint256 token_id) external;
+
+    function safeTransferFrom(address _from, address _to, uint256 token_id, bytes calldata _data) external;
+
+    function supportsInterface(bytes4 interface_id) external view returns (bool);
+
+    error NotGame(address);
+
+    error InvalidBadge(uint8);
+
+    error BadgeNotFound(uint256);
+
+    error Soulbound(uint256);
+}
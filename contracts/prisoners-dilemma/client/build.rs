@@ -0,0 +1,54 @@
+// Generates the ethers bindings for the contracts from the interfaces printed
+// by `cargo stylus export-abi` (checked in as `abi/IPrisonersDilemma.sol` and
+// `abi/IPrisonersDilemmaBadges.sol`), so the client follows the `#[public]`
+// methods and errors declared in `../src/lib.rs` and `../badges/src/lib.rs`.
+// `tests/abi.rs` fails whenever a checked-in interface goes stale.
+use std::{env, fs, path::PathBuf};
+
+use alloy_json_abi::JsonAbi;
+use ethers_contract_abigen::Abigen;
+
+fn main() {
+    generate("PrisonersDilemma", "abi/IPrisonersDilemma.sol", "../src/lib.rs", "bindings.rs");
+    generate("PrisonersDilemmaBadges", "abi/IPrisonersDilemmaBadges.sol", "../badges/src/lib.rs", "badge_bindings.rs");
+}
+
+fn generate(name: &str, interface: &str, source: &str, output: &str) {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let interface_path = manifest_dir.join(interface);
+    let contract_src = manifest_dir.join(source);
+    println!("cargo:rerun-if-changed={}", interface_path.display());
+    println!("cargo:rerun-if-changed={}", contract_src.display());
+
+    // Functions and errors come straight from the exported interface
+    let interface = fs::read_to_string(&interface_path).expect("exported interface is readable");
+    let mut abi: Vec<String> = interface
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("function ") || line.starts_with("error "))
+        .map(|line| line.trim_end_matches(';').to_string())
+        .collect();
+
+    // Stylus does not export events, so pick them up from the contract's `sol!` block
+    let source = fs::read_to_string(&contract_src).expect("contract source is readable");
+    abi.extend(
+        source
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("event "))
+            .map(|line| line.trim_end_matches(';').to_string()),
+    );
+
+    // ethers' own human-readable parser mangles tuple outputs, so go through alloy's and JSON
+    let abi = JsonAbi::parse(abi.iter().map(String::as_str)).expect("exported ABI parses");
+    let abi = serde_json::to_string(&abi).expect("ABI serializes");
+    let bindings = Abigen::new(name, abi)
+        .expect("ABI JSON parses")
+        .generate()
+        .expect("bindings generate");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    bindings
+        .write_to_file(out_dir.join(output))
+        .expect("bindings are written");
+}
@@ -0,0 +1,744 @@
+// Typed client for the Prisoner's Dilemma Stylus contract
+//
+// The raw ethers bindings are generated at build time from the contract's
+// exported ABI (see `build.rs`). This crate wraps them in an async API that
+// returns structured `Cell`/`Round` values and decodes reverts into the
+// contract's named `PrisonersDilemmaErrors`.
+use std::sync::Arc;
+
+use ethers::{
+    abi::Detokenize,
+    contract::{builders::ContractCall, ContractError, Event},
+    providers::{Middleware, ProviderError},
+    types::{Address, Bytes, TransactionReceipt, TxHash, H256, I256, U256},
+    utils::keccak256,
+};
+
+pub mod bindings {
+    #![allow(clippy::all)]
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+// Bindings for the companion contract holding the achievement badges
+pub mod badge_bindings {
+    #![allow(clippy::all)]
+    include!(concat!(env!("OUT_DIR"), "/badge_bindings.rs"));
+}
+
+// The contract's own move, badge, policy, channel and sealed strategy types,
+// from the shared game core
+pub use prisoners_dilemma_core::{
+    seal_id, sealed_leaf, Badge, CellState, ChannelState, ContinuationPolicy, Move, PayoffMatrix, SealedReveal,
+    SealedStrategy,
+};
+
+pub use bindings::{
+    CellCompleteFilter, CellCreatedFilter, PlayerJoinedFilter, PrisonersDilemma,
+    PrisonersDilemmaErrors, PrisonersDilemmaEvents, RoundCompleteFilter,
+};
+pub use badge_bindings::{BadgeEarnedFilter, PrisonersDilemmaBadges};
+
+// Round state within a cell, mirroring the contract's `Round`
+//
+// Moves are only revealed by the contract once a round is finished, so both
+// are `None` for the round currently being played. The `*_move` fields are the
+// executed moves, which noise cells may have flipped from the intended ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub player1_move: Option<Move>,
+    pub player2_move: Option<Move>,
+    pub player1_intended: Option<Move>,
+    pub player2_intended: Option<Move>,
+    pub player1_payout: U256,
+    pub player2_payout: U256,
+    pub is_finished: bool,
+    // Both moves were revealed from the players' sealed strategies
+    pub sealed: bool,
+    // The stake the round is played at, above the cell's after a raise
+    pub stake: U256,
+}
+
+// Continuation decisions for the current round, as returned by
+// `get_continuation_status`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContinuationStatus {
+    pub player1_decided: bool,
+    pub player1_wants: bool,
+    pub player2_decided: bool,
+    pub player2_wants: bool,
+}
+
+// A raise of the next round's stake waiting on deposits, as returned by
+// `get_pending_raise`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PendingRaise {
+    pub stake: U256,
+    // What each player deposits to take it up
+    pub top_up: U256,
+    pub player1_deposited: bool,
+    pub player2_deposited: bool,
+}
+
+// Cell state, mirroring the contract's `Cell`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub cell_id: U256,
+    pub player1: Address,
+    pub player2: Address,
+    pub stake_amount: U256,
+    pub total_rounds: u8,
+    pub current_round: u8,
+    pub is_complete: bool,
+    // Cancelled by its creator before anyone joined
+    pub is_cancelled: bool,
+    pub rounds: Vec<Round>,
+    pub continuation: ContinuationStatus,
+    pub state: CellState,
+    // When a disputed cell's challenge window closes, as a unix timestamp, or zero
+    pub challenge_deadline: U256,
+    // When the current round's commit or reveal window closes, or zero if nobody is waiting on one
+    pub reveal_deadline: U256,
+}
+
+impl Cell {
+    // What each round is played at before any raise: its share of the stake,
+    // which is also the stake a channel state's totals are computed at
+    pub fn round_stake(&self) -> U256 {
+        self.stake_amount / U256::from(self.total_rounds.max(1))
+    }
+
+    // The state after `rounds` of this cell, with the totals the contract pays,
+    // as both players sign it
+    pub fn channel_state(&self, rounds: Vec<(Move, Move)>) -> ChannelState {
+        ChannelState::new(to_core_u256(self.cell_id), to_core_u256(self.round_stake()), rounds, &PayoffMatrix::CONTRACT)
+    }
+}
+
+// A cell waiting for an opponent, as listed by `get_open_cells`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpenCell {
+    pub cell_id: U256,
+    pub creator: Address,
+    pub stake_amount: U256,
+    pub total_rounds: u8,
+    pub noise_bps: u16,
+    pub continuation_bps: u16,
+}
+
+// A cell's escrow ledger, as returned by `get_cell_escrow`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellEscrow {
+    pub deposited: U256,
+    pub paid: U256,
+    pub outstanding: U256,
+}
+
+// One player's side of `preview_settlement`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SettlementPreview {
+    // Payouts of the rounds finished so far
+    pub accumulated: U256,
+    // What the player would be sent if the cell ended now
+    pub if_ended: U256,
+    // What the player would be sent if every round up to `total_rounds` is played
+    pub best_case: U256,
+    pub worst_case: U256,
+}
+
+// The contract's liabilities against its balance, as returned by `check_solvency`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Solvency {
+    pub solvent: bool,
+    pub liabilities: U256,
+    pub balance: U256,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError<M: Middleware> {
+    #[error("contract reverted: {0:?}")]
+    Revert(PrisonersDilemmaErrors),
+    #[error(transparent)]
+    Contract(ContractError<M>),
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    #[error("transaction {0:?} was dropped from the mempool")]
+    Dropped(TxHash),
+    #[error("transaction receipt has no {0} event")]
+    MissingEvent(&'static str),
+    #[error("no sender is configured to sign with")]
+    NoSender,
+}
+
+impl<M: Middleware> From<ContractError<M>> for ClientError<M> {
+    fn from(err: ContractError<M>) -> Self {
+        match err.decode_contract_revert::<PrisonersDilemmaErrors>() {
+            Some(revert) => ClientError::Revert(revert),
+            None => ClientError::Contract(err),
+        }
+    }
+}
+
+pub type Result<T, M> = std::result::Result<T, ClientError<M>>;
+
+pub struct PrisonersDilemmaClient<M> {
+    contract: PrisonersDilemma<M>,
+}
+
+impl<M: Middleware + 'static> PrisonersDilemmaClient<M> {
+    pub fn new(address: Address, client: Arc<M>) -> Self {
+        Self { contract: PrisonersDilemma::new(address, client) }
+    }
+
+    pub fn address(&self) -> Address {
+        self.contract.address()
+    }
+
+    // Access to the raw generated bindings
+    pub fn contract(&self) -> &PrisonersDilemma<M> {
+        &self.contract
+    }
+
+    pub async fn initialize(&self, min_stake: U256) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.initialize(min_stake)).await
+    }
+
+    // Creates a cell staking `stake` and returns its id from the `CellCreated` event
+    pub async fn create_cell(&self, total_rounds: u8, stake: U256) -> Result<U256, M> {
+        let receipt = self.send(self.contract.create_cell(total_rounds).value(stake)).await?;
+        self.created_cell_id(&receipt)
+    }
+
+    // Creates a cell whose moves are flipped with probability `noise_bps` and
+    // that continues after each round with probability `continuation_bps`
+    pub async fn create_research_cell(
+        &self,
+        total_rounds: u8,
+        noise_bps: u16,
+        continuation_bps: u16,
+        stake: U256,
+    ) -> Result<U256, M> {
+        let call = self
+            .contract
+            .create_research_cell(total_rounds, noise_bps, continuation_bps)
+            .value(stake);
+        let receipt = self.send(call).await?;
+        self.created_cell_id(&receipt)
+    }
+
+    // Creates a cell whose round payouts are credited to withdrawable
+    // balances as each round resolves, with the same settings as a research cell
+    pub async fn create_streamed_cell(
+        &self,
+        total_rounds: u8,
+        noise_bps: u16,
+        continuation_bps: u16,
+        stake: U256,
+    ) -> Result<U256, M> {
+        let call = self
+            .contract
+            .create_streamed_cell(total_rounds, noise_bps, continuation_bps)
+            .value(stake);
+        let receipt = self.send(call).await?;
+        self.created_cell_id(&receipt)
+    }
+
+    // Creates a sealed cell of `strategy.moves.len()` rounds, committing the
+    // strategy's root. The strategy must be sealed with `seal_id` of the
+    // sender and `nonce`, which no earlier sealed cell of theirs may have used
+    pub async fn create_sealed_cell(&self, nonce: U256, strategy: &SealedStrategy, stake: U256) -> Result<U256, M> {
+        let total_rounds = strategy.moves.len() as u8;
+        let call = self.contract.create_sealed_cell(nonce, total_rounds, strategy.root().0).value(stake);
+        let receipt = self.send(call).await?;
+        self.created_cell_id(&receipt)
+    }
+
+    fn created_cell_id(&self, receipt: &TransactionReceipt) -> Result<U256, M> {
+        receipt
+            .logs
+            .iter()
+            .filter(|log| log.address == self.address())
+            .find_map(|log| {
+                ethers::contract::parse_log::<CellCreatedFilter>(log.clone()).ok()
+            })
+            .map(|event| event.cell_id)
+            .ok_or(ClientError::MissingEvent("CellCreated"))
+    }
+
+    fn joined_cell_id(&self, receipt: &TransactionReceipt) -> Result<U256, M> {
+        receipt
+            .logs
+            .iter()
+            .filter(|log| log.address == self.address())
+            .find_map(|log| {
+                ethers::contract::parse_log::<PlayerJoinedFilter>(log.clone()).ok()
+            })
+            .map(|event| event.cell_id)
+            .ok_or(ClientError::MissingEvent("PlayerJoined"))
+    }
+
+    pub async fn join_cell(&self, cell_id: U256, stake: U256) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.join_cell(cell_id).value(stake)).await
+    }
+
+    pub async fn join_sealed_cell(
+        &self,
+        cell_id: U256,
+        strategy: &SealedStrategy,
+        stake: U256,
+    ) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.join_sealed_cell(cell_id, strategy.root().0).value(stake)).await
+    }
+
+    // Withdraws a cell nobody has joined yet and refunds its stake
+    pub async fn cancel_cell(&self, cell_id: U256) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.cancel_cell(cell_id)).await
+    }
+
+    pub async fn submit_move(&self, cell_id: U256, player_move: Move) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.submit_move(cell_id, player_move as u8)).await
+    }
+
+    // Submits a move and this round's continuation vote in one transaction
+    pub async fn submit_move_with_continuation(
+        &self,
+        cell_id: U256,
+        player_move: Move,
+        wants_continue: bool,
+    ) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.submit_move_with_continuation(cell_id, player_move as u8, wants_continue))
+            .await
+    }
+
+    // Commits to a move with the given salt; keep the salt to reveal it later.
+    // The commitment binds the sender, so the client must have one configured
+    pub async fn commit_move(
+        &self,
+        cell_id: U256,
+        player_move: Move,
+        salt: [u8; 32],
+    ) -> Result<TransactionReceipt, M> {
+        let player = self.contract.client().default_sender().ok_or(ClientError::NoSender)?;
+        let commitment = move_commitment(cell_id, player, player_move, salt);
+        self.send(self.contract.commit_move(cell_id, commitment)).await
+    }
+
+    pub async fn reveal_move(
+        &self,
+        cell_id: U256,
+        player_move: Move,
+        salt: [u8; 32],
+    ) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.reveal_move(cell_id, player_move as u8, salt)).await
+    }
+
+    // Ends a round the opponent has not revealed in once the reveal window has passed
+    pub async fn claim_reveal_timeout(&self, cell_id: U256) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.claim_reveal_timeout(cell_id)).await
+    }
+
+    // Reveals sealed moves; rounds resolve once both players' moves are revealed
+    pub async fn reveal_sealed_moves(&self, cell_id: U256, reveals: &[SealedReveal]) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.reveal_sealed_moves(cell_id, SealedReveal::encode_all(reveals).into())).await
+    }
+
+    // Submits a state both players signed off-chain, opening its challenge
+    // window; signatures are 65-byte (r, s, v) signatures over `channel_digest`
+    pub async fn settle_offchain(
+        &self,
+        cell_id: U256,
+        state: &ChannelState,
+        sig1: Bytes,
+        sig2: Bytes,
+    ) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.settle_offchain(cell_id, state.encode().into(), sig1, sig2)).await
+    }
+
+    // Applies a disputed cell's channel state once its challenge window has closed
+    pub async fn finalize_offchain(&self, cell_id: U256) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.finalize_offchain(cell_id)).await
+    }
+
+    // Sends the sender everything streamed cells have credited them
+    pub async fn withdraw(&self) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.withdraw()).await
+    }
+
+    // Proposes raising the next round's stake to `stake`, sending the top-up
+    // over the current stake; only while the round waits on continuation votes
+    pub async fn propose_raise(&self, cell_id: U256, stake: U256, top_up: U256) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.propose_raise(cell_id, stake).value(top_up)).await
+    }
+
+    // Matches the opponent's pending raise with the same top-up
+    pub async fn accept_raise(&self, cell_id: U256, top_up: U256) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.accept_raise(cell_id).value(top_up)).await
+    }
+
+    // Offers the opponent of a completed cell a rematch staking `stake`, and
+    // returns the id of the new cell. Cancel it with `cancel_cell`
+    pub async fn offer_rematch(&self, previous_cell_id: U256, stake: U256) -> Result<U256, M> {
+        let receipt = self.send(self.contract.offer_rematch(previous_cell_id).value(stake)).await?;
+        self.created_cell_id(&receipt)
+    }
+
+    // Accepts a rematch offer by taking the second seat in the offered cell,
+    // and returns that cell's id
+    pub async fn accept_rematch(&self, previous_cell_id: U256, stake: U256) -> Result<U256, M> {
+        let receipt = self.send(self.contract.accept_rematch(previous_cell_id).value(stake)).await?;
+        self.joined_cell_id(&receipt)
+    }
+
+    pub async fn submit_continuation_decision(
+        &self,
+        cell_id: U256,
+        wants_continue: bool,
+    ) -> Result<TransactionReceipt, M> {
+        self.send(self.contract.submit_continuation_decision(cell_id, wants_continue))
+            .await
+    }
+
+    // Sets the standing continuation vote the contract casts for you after each round
+    pub async fn set_continuation_policy(
+        &self,
+        cell_id: U256,
+        policy: ContinuationPolicy,
+    ) -> Result<TransactionReceipt, M> {
+        let param = from_core_i256(policy.param());
+        self.send(self.contract.set_continuation_policy(cell_id, policy.kind(), param)).await
+    }
+
+    // Getters
+
+    // Loads the full cell state, including every round played so far
+    pub async fn get_cell(&self, cell_id: U256) -> Result<Cell, M> {
+        let mut cells = self.get_cells(vec![cell_id]).await?;
+        Ok(cells.remove(0))
+    }
+
+    // Loads up to 50 cells in one call, in the order given
+    pub async fn get_cells(&self, cell_ids: Vec<U256>) -> Result<Vec<Cell>, M> {
+        let (cells, rounds) = self.contract.get_cells(cell_ids).call().await?;
+        Ok(cells_from_views(cells, rounds))
+    }
+
+    pub async fn get_player_cell(&self, player: Address) -> Result<U256, M> {
+        Ok(self.contract.get_player_cell(player).call().await?)
+    }
+
+    pub async fn get_player_active_cells(&self, player: Address) -> Result<Vec<U256>, M> {
+        Ok(self.contract.get_player_active_cells(player).call().await?)
+    }
+
+    pub async fn get_max_active_cells(&self) -> Result<u32, M> {
+        Ok(self.contract.get_max_active_cells().call().await?)
+    }
+
+    // A page of every cell the player has created or joined, oldest first
+    pub async fn get_player_cells(&self, player: Address, offset: U256, limit: U256) -> Result<Vec<U256>, M> {
+        Ok(self.contract.get_player_cells(player, offset, limit).call().await?)
+    }
+
+    pub async fn get_player_cell_count(&self, player: Address) -> Result<U256, M> {
+        Ok(self.contract.get_player_cell_count(player).call().await?)
+    }
+
+    // A page of every cell the two players have played against each other
+    pub async fn get_pair_cells(&self, a: Address, b: Address, offset: U256, limit: U256) -> Result<Vec<U256>, M> {
+        Ok(self.contract.get_pair_cells(a, b, offset, limit).call().await?)
+    }
+
+    pub async fn get_pair_cell_count(&self, a: Address, b: Address) -> Result<U256, M> {
+        Ok(self.contract.get_pair_cell_count(a, b).call().await?)
+    }
+
+    // (previous, next) cell of a series of rematches, zero where there is none
+    pub async fn get_rematch_links(&self, cell_id: U256) -> Result<(U256, U256), M> {
+        Ok(self.contract.get_rematch_links(cell_id).call().await?)
+    }
+
+    // A page of the cells waiting for an opponent with a stake in
+    // [min_stake, max_stake], where a zero `max_stake` means no upper bound
+    pub async fn get_open_cells(
+        &self,
+        offset: U256,
+        limit: U256,
+        min_stake: U256,
+        max_stake: U256,
+    ) -> Result<Vec<OpenCell>, M> {
+        let (ids, creators, stakes, total_rounds, noise, continuation) =
+            self.contract.get_open_cells(offset, limit, min_stake, max_stake).call().await?;
+        Ok(ids
+            .into_iter()
+            .zip(creators)
+            .zip(stakes)
+            .zip(total_rounds)
+            .zip(noise.into_iter().zip(continuation))
+            .map(|((((cell_id, creator), stake_amount), total_rounds), (noise_bps, continuation_bps))| OpenCell {
+                cell_id,
+                creator,
+                stake_amount,
+                total_rounds,
+                noise_bps,
+                continuation_bps,
+            })
+            .collect())
+    }
+
+    pub async fn get_open_cell_count(&self) -> Result<U256, M> {
+        Ok(self.contract.get_open_cell_count().call().await?)
+    }
+
+    pub async fn get_players_cell(&self, player1: Address, player2: Address) -> Result<U256, M> {
+        Ok(self.contract.get_players_cell(player1, player2).call().await?)
+    }
+
+    // The companion contract minting achievement badges, zero if none is set
+    pub async fn get_badge_contract(&self) -> Result<Address, M> {
+        Ok(self.contract.get_badge_contract().call().await?)
+    }
+
+    // Bindings for the badge companion the contract mints through
+    pub async fn badges(&self) -> Result<PrisonersDilemmaBadges<M>, M> {
+        let address = self.get_badge_contract().await?;
+        Ok(PrisonersDilemmaBadges::new(address, self.contract.client()))
+    }
+
+    // Badges held by `player` as (token id, badge), in the order they were earned
+    pub async fn get_player_badges(&self, player: Address) -> Result<Vec<(U256, Badge)>, M> {
+        let (token_ids, kinds) = self.badges().await?.get_player_badges(player).call().await?;
+        Ok(token_ids
+            .into_iter()
+            .zip(kinds)
+            .filter_map(|(token_id, kind)| Badge::from_u8(kind).map(|badge| (token_id, badge)))
+            .collect())
+    }
+
+    // The badge's ERC-721 metadata, a JSON data URI
+    pub async fn token_uri(&self, token_id: U256) -> Result<String, M> {
+        Ok(self.badges().await?.token_uri(token_id).call().await?)
+    }
+
+    pub async fn get_cell_escrow(&self, cell_id: U256) -> Result<CellEscrow, M> {
+        let (deposited, paid, outstanding) = self.contract.get_cell_escrow(cell_id).call().await?;
+        Ok(CellEscrow { deposited, paid, outstanding })
+    }
+
+    // Each player's payouts so far, if the cell ended now, and at best and at
+    // worst by the end of play, as (player1, player2)
+    pub async fn preview_settlement(&self, cell_id: U256) -> Result<(SettlementPreview, SettlementPreview), M> {
+        let (p1, p2) = self.contract.preview_settlement(cell_id).call().await?;
+        let preview = |(accumulated, if_ended, best_case, worst_case)| SettlementPreview {
+            accumulated,
+            if_ended,
+            best_case,
+            worst_case,
+        };
+        Ok((preview(p1), preview(p2)))
+    }
+
+    // The hash each player signs for `state`, bound to this contract and chain
+    pub async fn channel_digest(&self, state: &ChannelState) -> Result<H256, M> {
+        Ok(H256(self.contract.get_channel_digest(state.encode().into()).call().await?))
+    }
+
+    // When a disputed cell's challenge window closes, as a unix timestamp, or zero
+    pub async fn get_challenge_deadline(&self, cell_id: U256) -> Result<U256, M> {
+        Ok(self.contract.get_challenge_deadline(cell_id).call().await?)
+    }
+
+    // When the current round's reveal window closes, as a unix timestamp, or
+    // zero if nobody has revealed in it
+    pub async fn get_reveal_deadline(&self, cell_id: U256) -> Result<U256, M> {
+        Ok(self.contract.get_reveal_deadline(cell_id).call().await?)
+    }
+
+    pub async fn check_solvency(&self) -> Result<Solvency, M> {
+        let (solvent, liabilities, balance) = self.contract.check_solvency().call().await?;
+        Ok(Solvency { solvent, liabilities, balance })
+    }
+
+    pub async fn get_min_stake(&self) -> Result<U256, M> {
+        Ok(self.contract.get_min_stake().call().await?)
+    }
+
+    pub async fn get_owner(&self) -> Result<Address, M> {
+        Ok(self.contract.get_owner().call().await?)
+    }
+
+    pub async fn get_continuation_status(&self, cell_id: U256) -> Result<ContinuationStatus, M> {
+        let (player1_decided, player1_wants, player2_decided, player2_wants) =
+            self.contract.get_continuation_status(cell_id).call().await?;
+        Ok(ContinuationStatus { player1_decided, player1_wants, player2_decided, player2_wants })
+    }
+
+    pub async fn get_continuation_policy(&self, cell_id: U256, player: Address) -> Result<ContinuationPolicy, M> {
+        let (policy, param) = self.contract.get_continuation_policy(cell_id, player).call().await?;
+        Ok(ContinuationPolicy::from_parts(policy, to_core_i256(param)).unwrap_or_default())
+    }
+
+    pub async fn get_cell_state(&self, cell_id: U256) -> Result<CellState, M> {
+        let state = self.contract.get_cell_state(cell_id).call().await?;
+        Ok(CellState::from_u8(state).unwrap_or_default())
+    }
+
+    // What streamed cells have credited `player` and `withdraw` would send
+    pub async fn get_withdrawable(&self, player: Address) -> Result<U256, M> {
+        Ok(self.contract.get_withdrawable(player).call().await?)
+    }
+
+    pub async fn get_cell_streamed(&self, cell_id: U256) -> Result<bool, M> {
+        Ok(self.contract.get_cell_streamed(cell_id).call().await?)
+    }
+
+    // The raise pending on a cell, if any
+    pub async fn get_pending_raise(&self, cell_id: U256) -> Result<Option<PendingRaise>, M> {
+        let (stake, top_up, player1_deposited, player2_deposited) =
+            self.contract.get_pending_raise(cell_id).call().await?;
+        Ok((!stake.is_zero()).then_some(PendingRaise { stake, top_up, player1_deposited, player2_deposited }))
+    }
+
+    // Roots of (player1, player2)'s sealed strategies, zero for unsealed cells
+    pub async fn get_sealed_roots(&self, cell_id: U256) -> Result<([u8; 32], [u8; 32]), M> {
+        Ok(self.contract.get_sealed_roots(cell_id).call().await?)
+    }
+
+    // What both players' sealed leaves are bound to, for building a joiner's strategy
+    pub async fn get_cell_seal(&self, cell_id: U256) -> Result<[u8; 32], M> {
+        Ok(self.contract.get_cell_seal(cell_id).call().await?)
+    }
+
+    pub async fn get_cell_counter(&self) -> Result<U256, M> {
+        Ok(self.contract.get_cell_counter().call().await?)
+    }
+
+    // One round as `get_cells` reports it, with its finished flag, so a round
+    // the exhausted pot paid nothing for still counts as finished. Rounds not
+    // reached yet come back unfinished and empty
+    pub async fn get_round_result(&self, cell_id: U256, round_number: u8) -> Result<Round, M> {
+        let cell = self.get_cell(cell_id).await?;
+        let round = (round_number as usize).checked_sub(1).and_then(|idx| cell.rounds.get(idx).cloned());
+        Ok(round.unwrap_or(Round {
+            player1_move: None,
+            player2_move: None,
+            player1_intended: None,
+            player2_intended: None,
+            player1_payout: U256::zero(),
+            player2_payout: U256::zero(),
+            is_finished: false,
+            sealed: false,
+            stake: U256::zero(),
+        }))
+    }
+
+    // Events
+
+    // All contract events; call `.stream()` or `.query()` on the result
+    pub fn events(&self) -> Event<Arc<M>, M, PrisonersDilemmaEvents> {
+        self.contract.events()
+    }
+
+    // Events for a single cell, filtered on the indexed `cell_id` topic
+    pub fn cell_events(&self, cell_id: U256) -> Event<Arc<M>, M, PrisonersDilemmaEvents> {
+        self.contract.events().topic1(cell_id)
+    }
+
+    async fn send<D: Detokenize>(&self, call: ContractCall<M, D>) -> Result<TransactionReceipt, M> {
+        let pending = call.send().await?;
+        let tx_hash = pending.tx_hash();
+        pending.await?.ok_or(ClientError::Dropped(tx_hash))
+    }
+}
+
+// Raw `get_cells` entries, see the contract's `CellView` and `RoundView`
+type CellView = (U256, Address, Address, U256, u8, u8, bool, bool, bool, bool, bool, bool, u16, u16, u8, U256, U256, u8);
+type RoundView = (u8, u8, u8, u8, U256, U256, bool, bool, U256);
+
+// Rebuilds cells from `get_cells`, which lists every cell's rounds after the
+// cells, as many for each as its view's rounds count
+fn cells_from_views(cells: Vec<CellView>, rounds: Vec<RoundView>) -> Vec<Cell> {
+    let mut rounds = rounds.into_iter().map(
+        |(p1_move, p2_move, p1_intended, p2_intended, player1_payout, player2_payout, is_finished, sealed, stake)| Round {
+            player1_move: is_finished.then(|| Move::from(p1_move)),
+            player2_move: is_finished.then(|| Move::from(p2_move)),
+            player1_intended: is_finished.then(|| Move::from(p1_intended)),
+            player2_intended: is_finished.then(|| Move::from(p2_intended)),
+            player1_payout,
+            player2_payout,
+            is_finished,
+            sealed,
+            stake,
+        },
+    );
+
+    cells
+        .into_iter()
+        .map(|view| {
+            let (cell_id, player1, player2, stake_amount, total_rounds, current_round, is_complete, is_cancelled) =
+                (view.0, view.1, view.2, view.3, view.4, view.5, view.6, view.7);
+            Cell {
+                cell_id,
+                player1,
+                player2,
+                stake_amount,
+                total_rounds,
+                current_round,
+                is_complete,
+                is_cancelled,
+                rounds: rounds.by_ref().take(view.17 as usize).collect(),
+                continuation: ContinuationStatus {
+                    player1_decided: view.8,
+                    player1_wants: view.9,
+                    player2_decided: view.10,
+                    player2_wants: view.11,
+                },
+                state: CellState::from_u8(view.14).unwrap_or_default(),
+                challenge_deadline: view.15,
+                reveal_deadline: view.16,
+            }
+        })
+        .collect()
+}
+
+// The core works in alloy's integers and the bindings in ethers', which share
+// a little-endian limb layout
+pub fn to_core_u256(value: U256) -> alloy_primitives::U256 {
+    alloy_primitives::U256::from_limbs(value.0)
+}
+
+pub fn from_core_u256(value: alloy_primitives::U256) -> U256 {
+    U256(value.into_limbs())
+}
+
+pub fn to_core_i256(value: I256) -> alloy_primitives::I256 {
+    alloy_primitives::I256::from_raw(to_core_u256(value.into_raw()))
+}
+
+pub fn from_core_i256(value: alloy_primitives::I256) -> I256 {
+    I256::from_raw(from_core_u256(value.into_raw()))
+}
+
+pub fn to_core_address(address: Address) -> alloy_primitives::Address {
+    alloy_primitives::Address::from(address.0)
+}
+
+// `player`'s strategy for the sealed cell with `seal`, their salts derived from `seed`
+pub fn sealed_strategy(seal: [u8; 32], player: Address, moves: Vec<Move>, seed: [u8; 32]) -> SealedStrategy {
+    SealedStrategy { seal: seal.into(), player: to_core_address(player), moves, seed: seed.into() }
+}
+
+// The seal of a sealed cell `creator` creates with `nonce`, see `seal_id`
+pub fn creator_seal(creator: Address, nonce: U256) -> [u8; 32] {
+    seal_id(to_core_address(creator), to_core_u256(nonce)).0
+}
+
+// The commitment `commit_move` stores, matching the contract's `get_move_commitment`
+pub fn move_commitment(cell_id: U256, player: Address, player_move: Move, salt: [u8; 32]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(85);
+    let mut id = [0u8; 32];
+    cell_id.to_big_endian(&mut id);
+    data.extend_from_slice(&id);
+    data.extend_from_slice(player.as_bytes());
+    data.push(player_move as u8);
+    data.extend_from_slice(&salt);
+    keccak256(data)
+}
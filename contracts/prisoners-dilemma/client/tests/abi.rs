@@ -0,0 +1,31 @@
+use std::{fmt, marker::PhantomData};
+
+use prisoners_dilemma::PrisonersDilemma;
+use prisoners_dilemma_badges::PrisonersDilemmaBadges;
+use stylus_sdk::abi::export::GenerateAbi;
+
+struct Interface<T>(PhantomData<T>);
+
+impl<T: GenerateAbi> fmt::Display for Interface<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt_abi(f)
+    }
+}
+
+#[test]
+fn checked_in_interface_matches_export_abi() {
+    let checked_in = include_str!("../abi/IPrisonersDilemma.sol");
+    assert!(
+        checked_in.ends_with(&Interface::<PrisonersDilemma>(PhantomData).to_string()),
+        "abi/IPrisonersDilemma.sol is stale, regenerate it with `npm run export-abi:client`"
+    );
+}
+
+#[test]
+fn checked_in_badge_interface_matches_export_abi() {
+    let checked_in = include_str!("../abi/IPrisonersDilemmaBadges.sol");
+    assert!(
+        checked_in.ends_with(&Interface::<PrisonersDilemmaBadges>(PhantomData).to_string()),
+        "abi/IPrisonersDilemmaBadges.sol is stale, regenerate it with `npm run export-abi:badges`"
+    );
+}
@@ -0,0 +1,36 @@
+// Decoding of `get_cells`, served by a mocked `eth_call`
+use std::sync::Arc;
+
+use ethers::abi::{self, Tokenize};
+use ethers::providers::Provider;
+use ethers::types::{Address, Bytes, U256};
+use prisoners_dilemma_client::PrisonersDilemmaClient;
+
+type CellView = (U256, Address, Address, U256, u8, u8, bool, bool, bool, bool, bool, bool, u16, u16, u8, U256, U256, u8);
+type RoundView = (u8, u8, u8, u8, U256, U256, bool, bool, U256);
+
+fn cell_view(cell_id: u64, current_round: u8, rounds_count: u8) -> CellView {
+    let (p1, p2) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let stake = U256::from(100);
+    (cell_id.into(), p1, p2, stake, 5, current_round, false, false, false, false, false, false, 0, 0, 1, U256::zero(), U256::zero(), rounds_count)
+}
+
+fn finished_round(payout: u64) -> RoundView {
+    (0, 0, 0, 0, payout.into(), payout.into(), true, false, U256::from(100))
+}
+
+#[tokio::test]
+async fn rounds_are_split_by_each_cells_rounds_count() {
+    // The first cell lists fewer rounds than its current round
+    let cells = vec![cell_view(1, 3, 2), cell_view(2, 1, 1)];
+    let rounds = vec![finished_round(10), finished_round(11), finished_round(20)];
+
+    let (provider, mock) = Provider::mocked();
+    mock.push::<Bytes, _>(Bytes::from(abi::encode(&(cells, rounds).into_tokens()))).unwrap();
+    let client = PrisonersDilemmaClient::new(Address::repeat_byte(0xcc), Arc::new(provider));
+    let cells = client.get_cells(vec![U256::from(1), U256::from(2)]).await.unwrap();
+
+    let payouts = |cell: &prisoners_dilemma_client::Cell| cell.rounds.iter().map(|round| round.player1_payout.as_u64()).collect::<Vec<_>>();
+    assert_eq!(payouts(&cells[0]), [10, 11]);
+    assert_eq!(payouts(&cells[1]), [20]);
+}
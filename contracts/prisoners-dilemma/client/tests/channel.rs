@@ -0,0 +1,30 @@
+use ethers::types::{Address, I256, U256};
+use prisoners_dilemma_client::{
+    from_core_i256, from_core_u256, to_core_i256, to_core_u256, Cell, CellState, ContinuationStatus, Move,
+};
+
+#[test]
+fn channel_states_are_totalled_at_the_cells_round_stake() {
+    let cell = Cell {
+        cell_id: U256::from(7),
+        player1: Address::repeat_byte(1),
+        player2: Address::repeat_byte(2),
+        stake_amount: U256::from(3_000_000),
+        total_rounds: 3,
+        current_round: 1,
+        is_complete: false,
+        is_cancelled: false,
+        rounds: Vec::new(),
+        continuation: ContinuationStatus::default(),
+        state: CellState::AwaitingMoves,
+        challenge_deadline: U256::zero(),
+        reveal_deadline: U256::zero(),
+    };
+    let state = cell.channel_state(vec![(Move::Cooperate, Move::Defect), (Move::Defect, Move::Defect)]);
+
+    assert_eq!(from_core_u256(state.cell_id), cell.cell_id);
+    assert_eq!(from_core_u256(state.player1_total), U256::from(1_000_000));
+    assert_eq!(from_core_u256(state.player2_total), U256::from(2_000_000));
+    assert_eq!(to_core_u256(U256::MAX), stylus_sdk::alloy_primitives::U256::MAX);
+    assert_eq!(from_core_i256(to_core_i256(I256::from(-5))), I256::from(-5));
+}
@@ -0,0 +1,74 @@
+// Plays a rematch through the contract in a TestVM and hands the logs it
+// emitted to the client in a mocked receipt, so the client decodes the events
+// the contract really emits
+use std::{sync::Arc, time::Duration};
+
+use ethers::providers::Provider;
+use ethers::types::{Address, Block, Bytes, FeeHistory, Log, Transaction, TransactionReceipt, TxHash, H256, U256};
+use prisoners_dilemma::{PrisonersDilemma, PrisonersDilemmaErrors};
+use prisoners_dilemma_client::PrisonersDilemmaClient;
+use stylus_sdk::alloy_primitives::{Address as AlloyAddress, B256, U256 as AlloyU256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const CONTRACT: AlloyAddress = AlloyAddress::repeat_byte(0xcc);
+const P1: AlloyAddress = AlloyAddress::repeat_byte(1);
+const P2: AlloyAddress = AlloyAddress::repeat_byte(2);
+const STAKE: u64 = 1_000_000;
+
+fn call<T>(vm: &TestVM, sender: AlloyAddress, value: u64, f: impl FnOnce(&mut PrisonersDilemma) -> T) -> T {
+    vm.set_sender(sender);
+    vm.set_value(AlloyU256::from(value));
+    vm.set_balance(CONTRACT, vm.balance(CONTRACT) + AlloyU256::from(value));
+    f(&mut PrisonersDilemma::from(vm))
+}
+
+fn ok<T>(result: Result<T, PrisonersDilemmaErrors>) -> T {
+    result.map_err(Vec::<u8>::from).unwrap()
+}
+
+// A receipt for a mined transaction carrying the given contract logs
+fn receipt(tx_hash: TxHash, logs: &[(Vec<B256>, Vec<u8>)]) -> TransactionReceipt {
+    let logs = logs
+        .iter()
+        .map(|(topics, data)| Log {
+            address: Address::from(CONTRACT.into_array()),
+            topics: topics.iter().map(|topic| H256::from(topic.0)).collect(),
+            data: Bytes::from(data.clone()),
+            ..Default::default()
+        })
+        .collect();
+    TransactionReceipt { transaction_hash: tx_hash, block_number: Some(1.into()), status: Some(1.into()), logs, ..Default::default() }
+}
+
+#[tokio::test]
+async fn accepting_a_rematch_returns_the_offered_cell() {
+    let vm = TestVM::new();
+    vm.set_contract_address(CONTRACT);
+    call(&vm, P1, 0, |c| c.initialize(AlloyU256::from(1)));
+    let cell_id = ok(call(&vm, P1, STAKE, |c| c.create_cell(1)));
+    ok(call(&vm, P2, STAKE, |c| c.join_cell(cell_id)));
+    ok(call(&vm, P1, 0, |c| c.submit_move(cell_id, 0)));
+    ok(call(&vm, P2, 0, |c| c.submit_move(cell_id, 0)));
+    let offered = ok(call(&vm, P1, STAKE, |c| c.offer_rematch(cell_id)));
+
+    let emitted = vm.get_emitted_logs().len();
+    let accepted = ok(call(&vm, P2, STAKE, |c| c.accept_rematch(cell_id)));
+    assert_eq!(accepted, offered);
+
+    // Responses are served last in, first out: fee estimation, gas, the
+    // submitted hash, then the mined transaction and its receipt
+    let (provider, mock) = Provider::mocked();
+    let tx_hash = TxHash::repeat_byte(7);
+    mock.push(receipt(tx_hash, &vm.get_emitted_logs()[emitted..])).unwrap();
+    mock.push(Transaction { hash: tx_hash, block_number: Some(1.into()), ..Default::default() }).unwrap();
+    mock.push(tx_hash).unwrap();
+    mock.push(U256::from(100_000)).unwrap();
+    mock.push(FeeHistory { base_fee_per_gas: vec![], gas_used_ratio: vec![], oldest_block: U256::zero(), reward: vec![] }).unwrap();
+    mock.push(Block::<TxHash> { base_fee_per_gas: Some(1.into()), ..Default::default() }).unwrap();
+
+    let provider = provider.with_sender(Address::from(P2.into_array())).interval(Duration::from_millis(1));
+    let client = PrisonersDilemmaClient::new(Address::from(CONTRACT.into_array()), Arc::new(provider));
+    let rematch_id = client.accept_rematch(U256::from(cell_id.to_be_bytes::<32>()), U256::from(STAKE)).await.unwrap();
+    assert_eq!(rematch_id, U256::from(offered.to_be_bytes::<32>()));
+}
@@ -0,0 +1,39 @@
+// Sealed strategies built from ethers types, checked against a cell the
+// contract created in a TestVM
+use ethers::types::{Address, U256};
+use prisoners_dilemma::{PrisonersDilemma, PrisonersDilemmaErrors};
+use prisoners_dilemma_client::{creator_seal, sealed_strategy, Move};
+use stylus_sdk::alloy_primitives::{Address as AlloyAddress, U256 as AlloyU256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const CONTRACT: AlloyAddress = AlloyAddress::repeat_byte(0xcc);
+const P1: AlloyAddress = AlloyAddress::repeat_byte(1);
+
+fn call<T>(vm: &TestVM, sender: AlloyAddress, value: u64, f: impl FnOnce(&mut PrisonersDilemma) -> T) -> T {
+    vm.set_sender(sender);
+    vm.set_value(AlloyU256::from(value));
+    vm.set_balance(CONTRACT, vm.balance(CONTRACT) + AlloyU256::from(value));
+    f(&mut PrisonersDilemma::from(vm))
+}
+
+fn ok<T>(result: Result<T, PrisonersDilemmaErrors>) -> T {
+    result.map_err(Vec::<u8>::from).unwrap()
+}
+
+#[test]
+fn strategies_are_sealed_for_the_seal_the_contract_stores() {
+    let vm = TestVM::new();
+    vm.set_contract_address(CONTRACT);
+    call(&vm, P1, 0, |c| c.initialize(AlloyU256::from(1)));
+
+    let (creator, nonce) = (Address::from(P1.into_array()), U256::from(42));
+    let seal = creator_seal(creator, nonce);
+    let strategy = sealed_strategy(seal, creator, vec![Move::Cooperate, Move::Defect, Move::Defect], [9; 32]);
+    let (root, alloy_nonce) = (strategy.root(), AlloyU256::from(42));
+    let cell_id = ok(call(&vm, P1, 1_000_000, |c| c.create_sealed_cell(alloy_nonce, 3, root)));
+
+    assert_eq!(call(&vm, P1, 0, |c| c.get_cell_seal(cell_id)).0, seal);
+    assert_eq!(call(&vm, P1, 0, |c| c.get_sealed_roots(cell_id)).0, root);
+    assert_eq!(strategy.player, P1);
+}
@@ -0,0 +1,11 @@
+// Feeds arbitrary storage bytes to the cell decoder: it must never panic, and
+// whatever it decodes must survive a round trip unchanged.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use prisoners_dilemma_core::{deserialize_cell, serialize_cell};
+
+fuzz_target!(|data: &[u8]| {
+    let cell = deserialize_cell(data);
+    assert_eq!(deserialize_cell(&serialize_cell(&cell)), cell);
+});
@@ -0,0 +1,125 @@
+// Achievement badges for milestones in two-player cells
+//
+// The rules only report milestones as `Effect::BadgeEarned`, possibly more than
+// once per cell; the caller mints each kind of badge once per player and
+// ignores the repeats. Badges credit the moves players chose, so a move flipped
+// by noise never costs one, while being exploited is judged on the moves played.
+use alloc::{format, string::String, vec::Vec};
+use alloy_primitives::{Address, U256};
+
+use crate::{Cell, Effect, Move};
+
+pub const BADGE_KINDS: usize = 4;
+
+// Consecutive mutual cooperations needed for `Badge::MutualStreak`
+pub const MUTUAL_STREAK: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Badge {
+    // Finished at least one round of a completed cell
+    FirstGame = 0,
+    // Both players cooperated `MUTUAL_STREAK` rounds in a row
+    MutualStreak = 1,
+    // Played every round of a full-length cell without choosing to defect
+    Unbroken = 2,
+    // Cooperated in the round right after being exploited
+    Forgiver = 3,
+}
+
+impl Badge {
+    pub const ALL: [Badge; BADGE_KINDS] = [Badge::FirstGame, Badge::MutualStreak, Badge::Unbroken, Badge::Forgiver];
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.get(value as usize).copied()
+    }
+
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Badge::FirstGame => "First Game",
+            Badge::MutualStreak => "Mutual Streak",
+            Badge::Unbroken => "Unbroken",
+            Badge::Forgiver => "Forgiver",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Badge::FirstGame => "Completed a first Prisoner's Dilemma game",
+            Badge::MutualStreak => "Cooperated with an opponent ten rounds in a row",
+            Badge::Unbroken => "Played a full-length cell without ever choosing to defect",
+            Badge::Forgiver => "Cooperated again right after being exploited",
+        }
+    }
+
+    // ERC-721 metadata for the badge's token, as an inline JSON data URI
+    pub fn token_uri(self, token_id: U256) -> String {
+        format!(
+            "data:application/json;utf8,{{\"name\":\"{} #{}\",\"description\":\"{}\",\"attributes\":[{{\"trait_type\":\"Badge\",\"value\":\"{}\"}}]}}",
+            self.name(),
+            token_id,
+            self.description(),
+            self.name(),
+        )
+    }
+}
+
+// Milestones reached by the round that just resolved
+pub(crate) fn round_badges(cell: &Cell, round_idx: usize, effects: &mut Vec<Effect>) {
+    let streak = cell.rounds[..=round_idx]
+        .iter()
+        .rev()
+        .take_while(|round| {
+            round.is_finished && round.intended_moves() == (Some(Move::Cooperate), Some(Move::Cooperate))
+        })
+        .count();
+    if streak == MUTUAL_STREAK {
+        earn(effects, [cell.player1, cell.player2], Badge::MutualStreak);
+    }
+
+    let Some(previous) = round_idx.checked_sub(1).map(|idx| &cell.rounds[idx]) else {
+        return;
+    };
+    let (p1_now, p2_now) = cell.rounds[round_idx].intended_moves();
+    let exploited = |own: Option<Move>, other: Option<Move>| own == Some(Move::Cooperate) && other == Some(Move::Defect);
+    if exploited(previous.player1_move, previous.player2_move) && p1_now == Some(Move::Cooperate) {
+        earn(effects, [cell.player1], Badge::Forgiver);
+    }
+    if exploited(previous.player2_move, previous.player1_move) && p2_now == Some(Move::Cooperate) {
+        earn(effects, [cell.player2], Badge::Forgiver);
+    }
+}
+
+// Milestones reached by a cell as it completes
+pub(crate) fn completion_badges(cell: &Cell, effects: &mut Vec<Effect>) {
+    let finished = || cell.rounds.iter().filter(|round| round.is_finished);
+    if finished().next().is_none() {
+        return;
+    }
+    earn(effects, [cell.player1, cell.player2], Badge::FirstGame);
+
+    let full_length = cell.current_round >= cell.total_rounds && finished().count() == cell.total_rounds as usize;
+    if full_length {
+        let never_defected = |player1: bool| {
+            finished().all(|round| {
+                let (p1, p2) = round.intended_moves();
+                (if player1 { p1 } else { p2 }) == Some(Move::Cooperate)
+            })
+        };
+        if never_defected(true) {
+            earn(effects, [cell.player1], Badge::Unbroken);
+        }
+        if never_defected(false) {
+            earn(effects, [cell.player2], Badge::Unbroken);
+        }
+    }
+}
+
+fn earn<const N: usize>(effects: &mut Vec<Effect>, players: [Address; N], badge: Badge) {
+    for player in players {
+        effects.push(Effect::BadgeEarned { player, badge });
+    }
+}
@@ -0,0 +1,89 @@
+// Off-chain play of a two-player cell through a state channel
+//
+// Once both players have joined, they can play rounds off-chain: each round
+// they exchange move commitments and reveals, and then both sign the
+// resulting `ChannelState`. Only the revealed moves and running totals are
+// signed, not the commitments and reveals: those only stop a player choosing
+// after seeing the other's move, and once both have signed a round's moves
+// there is nothing left for a reveal to prove. The contract checks the
+// signatures against `channel_digest`; the rules check that the state extends
+// what was played on-chain and that its totals follow the payoff matrix.
+//
+// Encoding: cell id (32 bytes), round count (1 byte), one byte per round
+// holding player 1's move in bit 0 and player 2's in bit 1, then both
+// players' running totals (32 bytes each).
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, Address, B256, U256};
+
+use crate::{Move, PayoffMatrix};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChannelState {
+    pub cell_id: U256,
+    // The executed (player1, player2) moves of every round from the first
+    pub rounds: Vec<(Move, Move)>,
+    pub player1_total: U256,
+    pub player2_total: U256,
+}
+
+impl ChannelState {
+    // Builds the state after `rounds`, with the totals the matrix pays for
+    // rounds played at `stake`, the cell's `round_stake`
+    pub fn new(cell_id: U256, stake: U256, rounds: Vec<(Move, Move)>, payoffs: &PayoffMatrix) -> Self {
+        let (player1_total, player2_total) = channel_totals(stake, &rounds, payoffs);
+        Self { cell_id, rounds, player1_total, player2_total }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(97 + self.rounds.len());
+        data.extend_from_slice(&self.cell_id.to_be_bytes::<32>());
+        data.push(self.rounds.len() as u8);
+        for &(p1_move, p2_move) in &self.rounds {
+            data.push(p1_move as u8 | (p2_move as u8) << 1);
+        }
+        data.extend_from_slice(&self.player1_total.to_be_bytes::<32>());
+        data.extend_from_slice(&self.player2_total.to_be_bytes::<32>());
+        data
+    }
+
+    // `None` unless `data` is exactly one encoded state
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let word = |at: usize| data.get(at..at + 32).map(U256::from_be_slice);
+        let count = *data.get(32)? as usize;
+        if data.len() != 97 + count {
+            return None;
+        }
+        let mut rounds = Vec::with_capacity(count);
+        for &bits in &data[33..33 + count] {
+            if bits > 3 {
+                return None;
+            }
+            rounds.push((Move::from(bits & 1), Move::from(bits >> 1)));
+        }
+        Some(Self {
+            cell_id: word(0)?,
+            rounds,
+            player1_total: word(33 + count)?,
+            player2_total: word(65 + count)?,
+        })
+    }
+}
+
+// What each player signs for `state`: an EIP-191 personal message over the
+// state bound to one contract on one chain, so `eth_sign`-style wallets work
+pub fn channel_digest(contract: Address, chain_id: u64, state: &ChannelState) -> B256 {
+    let mut data = Vec::new();
+    data.extend_from_slice(contract.as_slice());
+    data.extend_from_slice(&chain_id.to_be_bytes());
+    data.extend_from_slice(&state.encode());
+    let hash = keccak256(&data);
+    keccak256([b"\x19Ethereum Signed Message:\n32".as_slice(), hash.as_slice()].concat())
+}
+
+// Sum of each player's payouts over `rounds` played at `stake`
+pub fn channel_totals(stake: U256, rounds: &[(Move, Move)], payoffs: &PayoffMatrix) -> (U256, U256) {
+    rounds.iter().fold((U256::ZERO, U256::ZERO), |(total_p1, total_p2), &(p1_move, p2_move)| {
+        let (p1, p2) = payoffs.payoff(stake, p1_move, p2_move);
+        (total_p1 + p1, total_p2 + p2)
+    })
+}
@@ -0,0 +1,466 @@
+// Byte encoding of a cell, as stored in the contract's `cells` mapping
+//
+// Layout: player1 (20) | player2 (20) | stake (32) | total_rounds | current_round
+// | is_complete | rounds count, then one byte per round followed by both payouts
+// (32 + 32) for finished rounds only, then the continuation flags byte.
+//
+// Optional settings follow as a trailer of tagged records (tag byte, then a
+// fixed-size value). A record is only written when its value is not the
+// default, so cells without them encode exactly as before. Decoding stops at
+// the first unknown tag or truncated record.
+//
+// The encoding is lossy in two well-defined ways:
+// - payouts of unfinished rounds are not written and decode as zero
+// - the rounds count is one byte, so at most 255 rounds are written
+//
+// Decoding never panics. Data shorter than the 76-byte header decodes as the
+// default (empty) cell, decoding stops at the first round whose bytes are not
+// all present, and missing continuation flags decode as zero.
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256, I256, U256};
+
+use crate::{Cell, CellState, ContinuationPolicy, GroupCell, GroupRound, Move, Round};
+
+// Size of the fixed header before the rounds
+pub const CELL_HEADER_LEN: usize = 76;
+
+// Most rounds the one-byte rounds count can describe
+pub const MAX_ENCODED_ROUNDS: usize = u8::MAX as usize;
+
+// Trailer record tags
+const TAG_NOISE: u8 = 1;
+const TAG_CONTINUATION: u8 = 2;
+// Followed by a count byte and that many 2-byte draws
+const TAG_CONTINUATION_DRAWS: u8 = 3;
+// Followed by the policy kind and its 32-byte two's complement parameter
+const TAG_PLAYER1_POLICY: u8 = 4;
+const TAG_PLAYER2_POLICY: u8 = 5;
+// Followed by the state byte; only written when the state differs from the
+// one `CellState::infer` reads off the other fields, as it did for cells
+// stored before states were
+const TAG_STATE: u8 = 6;
+// Followed by the 32-byte root of the player's sealed strategy
+const TAG_PLAYER1_ROOT: u8 = 7;
+const TAG_PLAYER2_ROOT: u8 = 8;
+// Followed by a count byte and one byte per round of revealed sealed moves,
+// with both moves in bits 0-3 as in a round byte
+const TAG_SEALED_MOVES: u8 = 9;
+// No payload: the cell is streamed
+const TAG_STREAMED: u8 = 10;
+// Followed by a count byte and a 32-byte stake per round, zero for rounds
+// played at the cell's stake; only written once a raise has applied
+const TAG_ROUND_STAKES: u8 = 11;
+// Followed by the 32-byte raised stake and the raise flags byte
+const TAG_RAISE: u8 = 12;
+// Followed by the 20-byte address of the player a timed-out cell paid
+const TAG_TIMEOUT_WINNER: u8 = 13;
+// Followed by the 32-byte seal of a sealed cell
+const TAG_SEAL: u8 = 14;
+
+pub fn serialize_cell(cell: &Cell) -> Vec<u8> {
+    let mut data = Vec::with_capacity(128);
+
+    data.extend_from_slice(cell.player1.as_slice());
+    data.extend_from_slice(cell.player2.as_slice());
+    data.extend_from_slice(&cell.stake_amount.to_be_bytes::<32>());
+    data.push(cell.total_rounds);
+    data.push(cell.current_round);
+    data.push(if cell.is_complete { 1 } else { 0 });
+
+    // Rounds count
+    let rounds = &cell.rounds[..cell.rounds.len().min(MAX_ENCODED_ROUNDS)];
+    data.push(rounds.len() as u8);
+
+    // Serialize rounds
+    // Encoding: bits 0-1 = P1 move (0=None, 1=Cooperate, 2=Defect)
+    //           bits 2-3 = P2 move (0=None, 1=Cooperate, 2=Defect)
+    //           bit 4 = is_finished
+    //           bits 5-6 = P1/P2 move flipped by noise
+    //           bit 7 = moves revealed from sealed strategies
+    for round in rounds {
+        let mut round_byte = encode_moves(round.player1_move, round.player2_move);
+        if round.is_finished { round_byte |= 0x10; }
+        if round.player1_flipped { round_byte |= 0x20; }
+        if round.player2_flipped { round_byte |= 0x40; }
+        if round.sealed { round_byte |= 0x80; }
+        data.push(round_byte);
+
+        if round.is_finished {
+            data.extend_from_slice(&round.player1_payout.to_be_bytes::<32>());
+            data.extend_from_slice(&round.player2_payout.to_be_bytes::<32>());
+        }
+    }
+
+    data.push(cell.continuation_flags);
+
+    if cell.noise_bps != 0 {
+        data.push(TAG_NOISE);
+        data.extend_from_slice(&cell.noise_bps.to_be_bytes());
+    }
+    if cell.continuation_bps != 0 {
+        data.push(TAG_CONTINUATION);
+        data.extend_from_slice(&cell.continuation_bps.to_be_bytes());
+    }
+    if !cell.continuation_draws.is_empty() {
+        let draws = &cell.continuation_draws[..cell.continuation_draws.len().min(MAX_ENCODED_ROUNDS)];
+        data.push(TAG_CONTINUATION_DRAWS);
+        data.push(draws.len() as u8);
+        for draw in draws {
+            data.extend_from_slice(&draw.to_be_bytes());
+        }
+    }
+    for (tag, policy) in [(TAG_PLAYER1_POLICY, cell.player1_policy), (TAG_PLAYER2_POLICY, cell.player2_policy)] {
+        if policy != ContinuationPolicy::Manual {
+            data.push(tag);
+            data.push(policy.kind());
+            data.extend_from_slice(&policy.param().to_be_bytes::<32>());
+        }
+    }
+    if cell.state != CellState::infer(cell) {
+        data.push(TAG_STATE);
+        data.push(cell.state as u8);
+    }
+    for (tag, root) in [(TAG_PLAYER1_ROOT, cell.player1_root), (TAG_PLAYER2_ROOT, cell.player2_root)] {
+        if !root.is_zero() {
+            data.push(tag);
+            data.extend_from_slice(root.as_slice());
+        }
+    }
+    if !cell.sealed_moves.is_empty() {
+        let moves = &cell.sealed_moves[..cell.sealed_moves.len().min(MAX_ENCODED_ROUNDS)];
+        data.push(TAG_SEALED_MOVES);
+        data.push(moves.len() as u8);
+        data.extend(moves.iter().map(|&(p1_move, p2_move)| encode_moves(p1_move, p2_move)));
+    }
+    if cell.streamed {
+        data.push(TAG_STREAMED);
+    }
+    if rounds.iter().any(|round| !round.stake.is_zero()) {
+        data.push(TAG_ROUND_STAKES);
+        data.push(rounds.len() as u8);
+        for round in rounds {
+            data.extend_from_slice(&round.stake.to_be_bytes::<32>());
+        }
+    }
+    if !cell.raise_stake.is_zero() || cell.raise_flags != 0 {
+        data.push(TAG_RAISE);
+        data.extend_from_slice(&cell.raise_stake.to_be_bytes::<32>());
+        data.push(cell.raise_flags);
+    }
+    if cell.timeout_winner != Address::ZERO {
+        data.push(TAG_TIMEOUT_WINNER);
+        data.extend_from_slice(cell.timeout_winner.as_slice());
+    }
+    if !cell.seal.is_zero() {
+        data.push(TAG_SEAL);
+        data.extend_from_slice(cell.seal.as_slice());
+    }
+    data
+}
+
+// Bits 0-1 = P1 move and bits 2-3 = P2 move (0=None, 1=Cooperate, 2=Defect)
+fn encode_moves(p1_move: Option<Move>, p2_move: Option<Move>) -> u8 {
+    let bits = |m: Option<Move>| match m {
+        Some(Move::Cooperate) => 1,
+        Some(Move::Defect) => 2,
+        None => 0,
+    };
+    bits(p1_move) | bits(p2_move) << 2
+}
+
+// Inverse of `encode_moves`; the invalid value 3 decodes as no move
+fn decode_moves(byte: u8) -> (Option<Move>, Option<Move>) {
+    let decode = |bits: u8| match bits {
+        1 => Some(Move::Cooperate),
+        2 => Some(Move::Defect),
+        _ => None,
+    };
+    (decode(byte & 0x03), decode((byte >> 2) & 0x03))
+}
+
+pub fn deserialize_cell(data: &[u8]) -> Cell {
+    if data.len() < CELL_HEADER_LEN {
+        // Return a default cell
+        return Cell::default();
+    }
+
+    let player1 = Address::from_slice(&data[0..20]);
+    let player2 = Address::from_slice(&data[20..40]);
+    let stake_amount = read_u256(data, 40).unwrap_or_default();
+    let total_rounds = data[72];
+    let current_round = data[73];
+    let is_complete = data[74] != 0;
+    let rounds_count = data[75] as usize;
+
+    let mut rounds = Vec::with_capacity(rounds_count);
+    let mut pos = CELL_HEADER_LEN;
+
+    for _ in 0..rounds_count {
+        let Some(&round_byte) = data.get(pos) else { break; };
+
+        let (player1_move, player2_move) = decode_moves(round_byte);
+        let is_finished = (round_byte & 0x10) != 0;
+        let player1_flipped = (round_byte & 0x20) != 0;
+        let player2_flipped = (round_byte & 0x40) != 0;
+        let sealed = (round_byte & 0x80) != 0;
+
+        let (player1_payout, player2_payout) = if is_finished {
+            // A finished round without both payouts is truncated
+            match (read_u256(data, pos + 1), read_u256(data, pos + 33)) {
+                (Some(p1_payout), Some(p2_payout)) => (p1_payout, p2_payout),
+                _ => break,
+            }
+        } else {
+            (U256::ZERO, U256::ZERO)
+        };
+        pos += if is_finished { 65 } else { 1 };
+
+        rounds.push(Round {
+            player1_move,
+            player2_move,
+            player1_payout,
+            player2_payout,
+            is_finished,
+            player1_flipped,
+            player2_flipped,
+            sealed,
+            stake: U256::ZERO,
+        });
+    }
+
+    let continuation_flags = if rounds.len() == rounds_count { data.get(pos).copied().unwrap_or(0) } else { 0 };
+
+    let mut noise_bps = 0;
+    let mut continuation_bps = 0;
+    let mut continuation_draws = Vec::new();
+    let mut policies = [ContinuationPolicy::Manual; 2];
+    let mut state = None;
+    let mut roots = [B256::ZERO; 2];
+    let mut sealed_moves = Vec::new();
+    let mut streamed = false;
+    let mut raise = (U256::ZERO, 0);
+    let mut timeout_winner = Address::ZERO;
+    let mut seal = B256::ZERO;
+    if rounds.len() == rounds_count {
+        let mut pos = pos + 1;
+        while let Some(&tag) = data.get(pos) {
+            match tag {
+                TAG_NOISE | TAG_CONTINUATION => {
+                    let Some(&[hi, lo]) = data.get(pos + 1..pos + 3) else { break; };
+                    let value = u16::from_be_bytes([hi, lo]);
+                    if tag == TAG_NOISE { noise_bps = value; } else { continuation_bps = value; }
+                    pos += 3;
+                }
+                TAG_CONTINUATION_DRAWS => {
+                    let Some(&count) = data.get(pos + 1) else { break; };
+                    let end = pos + 2 + 2 * count as usize;
+                    let Some(bytes) = data.get(pos + 2..end) else { break; };
+                    continuation_draws = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+                    pos = end;
+                }
+                TAG_PLAYER1_POLICY | TAG_PLAYER2_POLICY => {
+                    let (Some(&kind), Some(param)) = (data.get(pos + 1), read_u256(data, pos + 2)) else { break; };
+                    // An unknown kind reads as voting by hand
+                    let policy = ContinuationPolicy::from_parts(kind, I256::from_raw(param)).unwrap_or_default();
+                    policies[(tag - TAG_PLAYER1_POLICY) as usize] = policy;
+                    pos += 34;
+                }
+                TAG_STATE => {
+                    let Some(value) = data.get(pos + 1).copied().and_then(CellState::from_u8) else { break; };
+                    state = Some(value);
+                    pos += 2;
+                }
+                TAG_PLAYER1_ROOT | TAG_PLAYER2_ROOT => {
+                    let Some(root) = data.get(pos + 1..pos + 33) else { break; };
+                    roots[(tag - TAG_PLAYER1_ROOT) as usize] = B256::from_slice(root);
+                    pos += 33;
+                }
+                TAG_SEALED_MOVES => {
+                    let Some(&count) = data.get(pos + 1) else { break; };
+                    let end = pos + 2 + count as usize;
+                    let Some(bytes) = data.get(pos + 2..end) else { break; };
+                    sealed_moves = bytes.iter().map(|&byte| decode_moves(byte)).collect();
+                    pos = end;
+                }
+                TAG_STREAMED => {
+                    streamed = true;
+                    pos += 1;
+                }
+                TAG_ROUND_STAKES => {
+                    let Some(&count) = data.get(pos + 1) else { break; };
+                    let end = pos + 2 + 32 * count as usize;
+                    let Some(bytes) = data.get(pos + 2..end) else { break; };
+                    for (round, stake) in rounds.iter_mut().zip(bytes.chunks_exact(32)) {
+                        round.stake = U256::from_be_slice(stake);
+                    }
+                    pos = end;
+                }
+                TAG_RAISE => {
+                    let (Some(stake), Some(&flags)) = (read_u256(data, pos + 1), data.get(pos + 33)) else { break; };
+                    raise = (stake, flags);
+                    pos += 34;
+                }
+                TAG_TIMEOUT_WINNER => {
+                    let Some(winner) = data.get(pos + 1..pos + 21) else { break; };
+                    timeout_winner = Address::from_slice(winner);
+                    pos += 21;
+                }
+                TAG_SEAL => {
+                    let Some(bytes) = data.get(pos + 1..pos + 33) else { break; };
+                    seal = B256::from_slice(bytes);
+                    pos += 33;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    let mut cell = Cell {
+        player1,
+        player2,
+        stake_amount,
+        total_rounds,
+        current_round,
+        is_complete,
+        rounds,
+        continuation_flags,
+        noise_bps,
+        continuation_bps,
+        continuation_draws,
+        player1_policy: policies[0],
+        player2_policy: policies[1],
+        state: CellState::Open,
+        player1_root: roots[0],
+        player2_root: roots[1],
+        seal,
+        sealed_moves,
+        streamed,
+        raise_stake: raise.0,
+        raise_flags: raise.1,
+        timeout_winner,
+    };
+    cell.state = state.unwrap_or_else(|| CellState::infer(&cell));
+    cell
+}
+
+// Group cells use their own layout, in the contract's `group_cells` mapping:
+//
+// max_players | stake (32) | multiplier (4) | total_rounds | current_round
+// | is_complete | continue votes (2) | players count, then 20 bytes per player,
+// then the rounds count and for each round a finished byte, one move byte per
+// player (0=None, 1=Cooperate, 2=Defect) and, for finished rounds only, one
+// 32-byte payout per player.
+//
+// The same decoding rules apply: a short header decodes as the default group
+// cell and decoding stops at the first incomplete player or round.
+pub const GROUP_CELL_HEADER_LEN: usize = 43;
+
+pub fn serialize_group_cell(cell: &GroupCell) -> Vec<u8> {
+    let players = &cell.players[..cell.players.len().min(u8::MAX as usize)];
+    let rounds = &cell.rounds[..cell.rounds.len().min(MAX_ENCODED_ROUNDS)];
+    let mut data = Vec::with_capacity(GROUP_CELL_HEADER_LEN + players.len() * 20 + 1);
+
+    data.push(cell.max_players);
+    data.extend_from_slice(&cell.stake_amount.to_be_bytes::<32>());
+    data.extend_from_slice(&cell.multiplier_bps.to_be_bytes());
+    data.push(cell.total_rounds);
+    data.push(cell.current_round);
+    data.push(if cell.is_complete { 1 } else { 0 });
+    data.extend_from_slice(&cell.continue_votes.to_be_bytes());
+
+    data.push(players.len() as u8);
+    for player in players {
+        data.extend_from_slice(player.as_slice());
+    }
+
+    data.push(rounds.len() as u8);
+    for round in rounds {
+        data.push(if round.is_finished { 1 } else { 0 });
+        for i in 0..players.len() {
+            data.push(match round.moves.get(i).copied().flatten() {
+                Some(Move::Cooperate) => 1,
+                Some(Move::Defect) => 2,
+                None => 0,
+            });
+        }
+        if round.is_finished {
+            for i in 0..players.len() {
+                let payout = round.payouts.get(i).copied().unwrap_or_default();
+                data.extend_from_slice(&payout.to_be_bytes::<32>());
+            }
+        }
+    }
+    data
+}
+
+pub fn deserialize_group_cell(data: &[u8]) -> GroupCell {
+    if data.len() < GROUP_CELL_HEADER_LEN {
+        return GroupCell::default();
+    }
+
+    let max_players = data[0];
+    let stake_amount = read_u256(data, 1).unwrap_or_default();
+    let multiplier_bps = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+    let total_rounds = data[37];
+    let current_round = data[38];
+    let is_complete = data[39] != 0;
+    let continue_votes = u16::from_be_bytes([data[40], data[41]]);
+    let players_count = data[42] as usize;
+
+    let mut pos = GROUP_CELL_HEADER_LEN;
+    let mut players = Vec::with_capacity(players_count);
+    while players.len() < players_count {
+        let Some(bytes) = data.get(pos..pos + 20) else { break; };
+        players.push(Address::from_slice(bytes));
+        pos += 20;
+    }
+
+    let rounds_count = if players.len() == players_count { data.get(pos).copied().unwrap_or(0) as usize } else { 0 };
+    pos += 1;
+
+    let n = players.len();
+    let mut rounds = Vec::with_capacity(rounds_count);
+    for _ in 0..rounds_count {
+        let (Some(&finished), Some(move_bytes)) = (data.get(pos), data.get(pos + 1..pos + 1 + n)) else { break; };
+        let is_finished = finished != 0;
+        let moves = move_bytes
+            .iter()
+            .map(|byte| match byte {
+                1 => Some(Move::Cooperate),
+                2 => Some(Move::Defect),
+                _ => None,
+            })
+            .collect();
+        pos += 1 + n;
+
+        let mut payouts = Vec::with_capacity(n);
+        if is_finished {
+            payouts.extend((0..n).map_while(|i| read_u256(data, pos + i * 32)));
+            if payouts.len() < n {
+                break;
+            }
+            pos += n * 32;
+        } else {
+            payouts.resize(n, U256::ZERO);
+        }
+
+        rounds.push(GroupRound { moves, payouts, is_finished });
+    }
+
+    GroupCell {
+        players,
+        max_players,
+        stake_amount,
+        multiplier_bps,
+        total_rounds,
+        current_round,
+        is_complete,
+        rounds,
+        continue_votes,
+    }
+}
+
+fn read_u256(data: &[u8], pos: usize) -> Option<U256> {
+    let bytes: [u8; 32] = data.get(pos..pos + 32)?.try_into().ok()?;
+    Some(U256::from_be_bytes(bytes))
+}
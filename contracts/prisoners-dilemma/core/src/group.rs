@@ -0,0 +1,272 @@
+// N-player public goods cells
+//
+// Each round every player either contributes their stake to a common pot
+// (`Move::Cooperate`) or free-rides (`Move::Defect`). The pot is multiplied and
+// split equally between all players, so a contributor receives their share of
+// the pot while a free-rider keeps their stake on top of it. With a multiplier
+// between 1x and Nx, contributing is best for the group but free-riding is
+// best for the individual: the N-player social dilemma.
+//
+// Each player's stake funds every round up front: a round is played at
+// `round_stake`, the share of the stake that still covers the multiplied pot
+// if everyone contributes in every round, so the cell never pays out more
+// than was deposited. Rounds that are never played are refunded. What the
+// multiplier would have added for contributions nobody made stays behind.
+//
+// Continuation works like two-player cells except that a single exit vote
+// ends the game for everyone. Until the last seat is taken the creator can
+// cancel the cell, refunding everyone who joined.
+use alloc::{vec, vec::Vec};
+use alloy_primitives::{Address, U256};
+
+use crate::{Context, Effect, Error, Move, PayoffMatrix};
+
+pub const MIN_GROUP_PLAYERS: u8 = 3;
+pub const MAX_GROUP_PLAYERS: u8 = 10;
+
+// Round state within a group cell, one entry per player in join order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupRound {
+    pub moves: Vec<Option<Move>>,
+    pub payouts: Vec<U256>,
+    pub is_finished: bool,
+}
+
+// A multi-round public goods game between `max_players` players
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupCell {
+    pub players: Vec<Address>,
+    pub max_players: u8,
+    pub stake_amount: U256,
+    // Pot multiplier in basis points of the contributions
+    pub multiplier_bps: u32,
+    pub total_rounds: u8,
+    pub current_round: u8,
+    pub is_complete: bool,
+    pub rounds: Vec<GroupRound>,
+    // Bit i set: player i voted to continue after the current round
+    pub continue_votes: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GroupAction {
+    Create { player: Address, stake: U256, max_players: u8, total_rounds: u8, multiplier_bps: u32 },
+    Join { player: Address, stake: U256 },
+    SubmitMove { player: Address, player_move: Move },
+    ContinuationDecision { player: Address, wants_continue: bool },
+    Cancel { player: Address },
+}
+
+impl GroupCell {
+    pub fn player_index(&self, player: Address) -> Option<usize> {
+        self.players.iter().position(|&p| p == player)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.players.len() >= self.max_players as usize
+    }
+
+    // Sum of each player's payouts over every finished round
+    pub fn total_payouts(&self) -> Vec<U256> {
+        let mut totals = vec![U256::ZERO; self.players.len()];
+        for round in self.rounds.iter().filter(|round| round.is_finished) {
+            for (total, payout) in totals.iter_mut().zip(&round.payouts) {
+                *total += *payout;
+            }
+        }
+        totals
+    }
+
+    // Every player's stake, which is all the cell can pay out
+    pub fn pot(&self) -> U256 {
+        self.stake_amount * U256::from(self.players.len())
+    }
+
+    // What each player stakes on a round: their stake spread over every round
+    // and divided by the multiplier, so that a round in which everyone
+    // contributes pays out exactly what it was funded with
+    pub fn round_stake(&self) -> U256 {
+        let funded = U256::from(self.total_rounds.max(1)) * U256::from(self.multiplier_bps);
+        self.stake_amount * U256::from(PayoffMatrix::BASIS_POINTS) / funded
+    }
+
+    // What each player is paid on completion: their total payouts, plus
+    // their stake back for every round that was never played
+    pub fn settlement(&self) -> Vec<U256> {
+        let played = self.rounds.iter().filter(|round| round.is_finished).count();
+        let unplayed = U256::from((self.total_rounds as usize).saturating_sub(played));
+        let refund = self.stake_amount * unplayed / U256::from(self.total_rounds.max(1));
+        self.total_payouts().into_iter().map(|total| total + refund).collect()
+    }
+
+    fn new_round(&self) -> GroupRound {
+        GroupRound {
+            moves: vec![None; self.players.len()],
+            payouts: vec![U256::ZERO; self.players.len()],
+            is_finished: false,
+        }
+    }
+}
+
+// Per-player payouts for one round of the public goods game
+pub fn group_payoff(stake: U256, multiplier_bps: u32, moves: &[Move]) -> Vec<U256> {
+    let contributors = moves.iter().filter(|&&m| m == Move::Cooperate).count();
+    let pot = stake * U256::from(contributors) * U256::from(multiplier_bps) / U256::from(PayoffMatrix::BASIS_POINTS);
+    let share = pot / U256::from(moves.len().max(1));
+
+    moves
+        .iter()
+        .map(|m| match m {
+            Move::Cooperate => share,
+            Move::Defect => stake + share,
+        })
+        .collect()
+}
+
+// Applies `action` to a group cell, with the same contract as `apply`
+pub fn apply_group(mut cell: GroupCell, action: GroupAction, ctx: &Context) -> Result<(GroupCell, Vec<Effect>), Error> {
+    let mut effects = Vec::new();
+
+    match action {
+        GroupAction::Create { player, stake, max_players, total_rounds, multiplier_bps } => {
+            if stake < ctx.min_stake {
+                return Err(Error::StakeTooLow);
+            }
+            if !(MIN_GROUP_PLAYERS..=MAX_GROUP_PLAYERS).contains(&max_players) {
+                return Err(Error::InvalidGroupSize);
+            }
+            // The pot must pay more than it costs, but less than free-riding on everyone else
+            let max_multiplier = u32::from(max_players) * PayoffMatrix::BASIS_POINTS;
+            if multiplier_bps <= PayoffMatrix::BASIS_POINTS || multiplier_bps >= max_multiplier {
+                return Err(Error::InvalidMultiplier);
+            }
+            cell = GroupCell {
+                players: vec![player],
+                max_players,
+                stake_amount: stake,
+                multiplier_bps,
+                total_rounds,
+                ..GroupCell::default()
+            };
+            effects.push(Effect::CellCreated { player1: player, stake });
+        }
+        GroupAction::Join { player, stake } => {
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            if cell.is_full() {
+                return Err(Error::CellFull);
+            }
+            if cell.player_index(player).is_some() {
+                return Err(Error::AlreadyInCell);
+            }
+            if stake != cell.stake_amount {
+                return Err(Error::WrongStake);
+            }
+
+            cell.players.push(player);
+            effects.push(Effect::GroupPlayerJoined { player });
+
+            // The game starts once the last seat is taken
+            if cell.is_full() {
+                cell.current_round = 1;
+                let round = cell.new_round();
+                cell.rounds.push(round);
+            }
+        }
+        GroupAction::SubmitMove { player, player_move } => {
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            let idx = cell.player_index(player).ok_or(Error::NotInCell)?;
+            if !cell.is_full() || cell.current_round == 0 {
+                return Err(Error::WaitingForPlayers);
+            }
+            let round_idx = (cell.current_round - 1) as usize;
+            let round = cell.rounds.get_mut(round_idx).ok_or(Error::RoundNotReady)?;
+            if round.is_finished || round.moves[idx].is_some() {
+                return Err(Error::RoundAlreadyFinished);
+            }
+            round.moves[idx] = Some(player_move);
+
+            // Resolve once everyone has moved
+            let moves: Option<Vec<Move>> = round.moves.iter().copied().collect();
+            if let Some(moves) = moves {
+                let stake = cell.round_stake();
+                let round = &mut cell.rounds[round_idx];
+                round.payouts = group_payoff(stake, cell.multiplier_bps, &moves);
+                round.is_finished = true;
+                effects.push(Effect::RoundComplete { round_num: cell.current_round });
+
+                if cell.current_round >= cell.total_rounds {
+                    complete_group_cell(&mut cell, &mut effects);
+                } else {
+                    cell.continue_votes = 0;
+                }
+            }
+        }
+        GroupAction::ContinuationDecision { player, wants_continue } => {
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            let idx = cell.player_index(player).ok_or(Error::NotInCell)?;
+            if cell.current_round >= cell.total_rounds {
+                return Err(Error::MaxRoundsReached);
+            }
+            let round_finished = cell
+                .rounds
+                .get((cell.current_round as usize).wrapping_sub(1))
+                .is_some_and(|round| round.is_finished);
+            if !round_finished {
+                return Err(Error::RoundNotReady);
+            }
+
+            if !wants_continue {
+                // Any exit ends the game for everyone
+                complete_group_cell(&mut cell, &mut effects);
+            } else {
+                cell.continue_votes |= 1 << idx;
+                let everyone = (1u16 << cell.players.len()) - 1;
+                if cell.continue_votes == everyone {
+                    cell.current_round += 1;
+                    let round = cell.new_round();
+                    cell.rounds.push(round);
+                    cell.continue_votes = 0;
+                }
+            }
+        }
+        GroupAction::Cancel { player } => {
+            if cell.players.is_empty() {
+                return Err(Error::CellNotFound);
+            }
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            if player != cell.players[0] {
+                return Err(Error::NotInCell);
+            }
+            if cell.is_full() {
+                return Err(Error::CellFull);
+            }
+
+            cell.is_complete = true;
+            for &player in &cell.players {
+                effects.push(Effect::Payout { player, amount: cell.stake_amount });
+            }
+            effects.push(Effect::CellComplete);
+        }
+    }
+
+    Ok((cell, effects))
+}
+
+fn complete_group_cell(cell: &mut GroupCell, effects: &mut Vec<Effect>) {
+    cell.is_complete = true;
+
+    for (&player, amount) in cell.players.iter().zip(cell.settlement()) {
+        if amount > U256::ZERO {
+            effects.push(Effect::Payout { player, amount });
+        }
+    }
+    effects.push(Effect::CellComplete);
+}
@@ -0,0 +1,231 @@
+// Prisoner's Dilemma Game Core
+//
+// The rules of the iterated Prisoner's Dilemma as a pure state machine, with
+// no dependency on the Stylus VM or contract storage. The contract entry points
+// translate calls into `Action`s, run them through `apply`, and then carry out
+// the returned `Effect`s (events, payouts, index updates). Off-chain tools such
+// as simulations and tests use the exact same code.
+//
+// Note: this code is a template-only and has not been audited.
+#![no_std]
+
+extern crate alloc;
+
+mod badges;
+mod channel;
+mod codec;
+mod group;
+mod policy;
+mod rules;
+mod sealed;
+mod settlement;
+mod state;
+mod wager;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256, U256};
+
+pub use badges::{Badge, BADGE_KINDS, MUTUAL_STREAK};
+pub use channel::{channel_digest, channel_totals, ChannelState};
+pub use codec::{
+    deserialize_cell, deserialize_group_cell, serialize_cell, serialize_group_cell, CELL_HEADER_LEN,
+    GROUP_CELL_HEADER_LEN, MAX_ENCODED_ROUNDS,
+};
+pub use policy::ContinuationPolicy;
+pub use group::{apply_group, group_payoff, GroupAction, GroupCell, GroupRound, MAX_GROUP_PLAYERS, MIN_GROUP_PLAYERS};
+pub use sealed::{merkle_proof, merkle_root, seal_id, sealed_leaf, verify_proof, SealedReveal, SealedStrategy};
+pub use settlement::{preview_settlement, settlement, SettlementPreview};
+pub use state::CellState;
+pub use rules::{apply, payoff, Action, Context, Effect, Error, PayoffMatrix, MAX_PROBABILITY_BPS};
+pub use wager::{market_payout, winning_buckets, Bucket, Market, WAGER_BUCKETS};
+
+// Game move options
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    Cooperate = 0,
+    Defect = 1,
+}
+
+impl Move {
+    pub fn opposite(self) -> Self {
+        match self {
+            Move::Cooperate => Move::Defect,
+            Move::Defect => Move::Cooperate,
+        }
+    }
+}
+
+impl From<u8> for Move {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Move::Cooperate,
+            _ => Move::Defect,
+        }
+    }
+}
+
+// Round state within a cell
+//
+// The moves are the executed ones. In noise cells a move may have been
+// flipped from the one the player chose, which the `*_flipped` flags record.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Round {
+    pub player1_move: Option<Move>,
+    pub player2_move: Option<Move>,
+    pub player1_payout: U256,
+    pub player2_payout: U256,
+    pub is_finished: bool,
+    pub player1_flipped: bool,
+    pub player2_flipped: bool,
+    // Both moves were revealed from the players' sealed strategies
+    pub sealed: bool,
+    // The stake the round is played at after a raise; zero for the cell's stake
+    pub stake: U256,
+}
+
+impl Round {
+    // The moves the players chose, before any noise was applied
+    pub fn intended_moves(&self) -> (Option<Move>, Option<Move>) {
+        let intended = |m: Option<Move>, flipped: bool| if flipped { m.map(Move::opposite) } else { m };
+        (intended(self.player1_move, self.player1_flipped), intended(self.player2_move, self.player2_flipped))
+    }
+}
+
+// Cell represents a multi-round game between two players
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cell {
+    pub player1: Address,
+    pub player2: Address,
+    pub stake_amount: U256,
+    pub total_rounds: u8,
+    pub current_round: u8,
+    pub is_complete: bool,
+    pub rounds: Vec<Round>,
+    pub continuation_flags: u8,
+    // Probability, in basis points, that a move is flipped before the round resolves
+    pub noise_bps: u16,
+    // Probability δ, in basis points, that the game continues after each round.
+    // Zero means players vote on continuation instead
+    pub continuation_bps: u16,
+    // The random draws (0-9999) made after each round of a δ cell
+    pub continuation_draws: Vec<u16>,
+    // Standing continuation votes, applied when a round resolves
+    pub player1_policy: ContinuationPolicy,
+    pub player2_policy: ContinuationPolicy,
+    // Where the cell is in its lifecycle, changed only by the rules
+    pub state: CellState,
+    // Roots of the players' sealed strategies; zero unless the cell is sealed
+    pub player1_root: B256,
+    pub player2_root: B256,
+    // What the leaves of both strategies are bound to, see `seal_id`
+    pub seal: B256,
+    // Moves revealed from the sealed strategies for rounds not yet played,
+    // indexed by round - 1 as (player1, player2)
+    pub sealed_moves: Vec<(Option<Move>, Option<Move>)>,
+    // Each round's payouts are credited to the players as the round resolves,
+    // and the stake no round used when the cell completes, instead of being
+    // paid out together on completion
+    pub streamed: bool,
+    // A raise of the stake for the next round, proposed during the
+    // continuation phase; zero when none is pending
+    pub raise_stake: U256,
+    // Which players have deposited the raise's top-up
+    pub raise_flags: u8,
+    // The player a timed-out cell paid the rest of its pot to; zero otherwise
+    pub timeout_winner: Address,
+}
+
+// Continuation flag bit positions:
+// Bit 0 (value 1): Player 1 wants to continue
+// Bit 1 (value 2): Player 2 wants to continue
+// Bit 2 (value 4): Player 1 has decided
+// Bit 3 (value 8): Player 2 has decided
+pub const P1_WANTS_CONTINUE: u8 = 1;
+pub const P2_WANTS_CONTINUE: u8 = 2;
+pub const P1_DECIDED: u8 = 4;
+pub const P2_DECIDED: u8 = 8;
+
+// Raise flag bit positions: the player has deposited the top-up
+pub const P1_RAISED: u8 = 1;
+pub const P2_RAISED: u8 = 2;
+
+impl Cell {
+    // Noise and δ draws need entropy from both players, which only reveals provide
+    pub fn requires_commit(&self) -> bool {
+        self.noise_bps > 0 || self.continuation_bps > 0
+    }
+
+    // Sealed cells are created with a strategy root, so player 1's marks them
+    pub fn is_sealed(&self) -> bool {
+        !self.player1_root.is_zero()
+    }
+
+    // Cancelled before anyone joined; every other cell completes with two players
+    pub fn is_cancelled(&self) -> bool {
+        self.state == CellState::Cancelled
+    }
+
+    pub fn is_player(&self, player: Address) -> bool {
+        player == self.player1 || player == self.player2
+    }
+
+    // Returns (player1_decided, player1_wants, player2_decided, player2_wants)
+    pub fn continuation_status(&self) -> (bool, bool, bool, bool) {
+        let flags = self.continuation_flags;
+        (
+            flags & P1_DECIDED != 0,
+            flags & P1_WANTS_CONTINUE != 0,
+            flags & P2_DECIDED != 0,
+            flags & P2_WANTS_CONTINUE != 0,
+        )
+    }
+
+    // The stake in force when `round` was opened
+    pub fn stake_of(&self, round: &Round) -> U256 {
+        if round.stake.is_zero() { self.stake_amount } else { round.stake }
+    }
+
+    // What `round` is played at: its share of the stake in force. Each round
+    // pays out at most twice its share, so the deposits fund every round up
+    // front and no settlement has to be scaled down to fit the pot
+    pub fn round_stake(&self, round: &Round) -> U256 {
+        self.stake_of(round) / U256::from(self.total_rounds.max(1))
+    }
+
+    // What is left of each player's stake once every finished round has
+    // drawn its share; refunded when the cell completes
+    pub fn unspent_stake(&self) -> U256 {
+        let spent = self.rounds.iter().filter(|round| round.is_finished).map(|round| self.round_stake(round));
+        self.current_stake().saturating_sub(spent.fold(U256::ZERO, |total, share| total + share))
+    }
+
+    // The stake of the latest round, which later rounds keep unless raised
+    pub fn current_stake(&self) -> U256 {
+        self.rounds.last().map_or(self.stake_amount, |round| self.stake_of(round))
+    }
+
+    // Everything the two stakes have put into the cell, topped up by every
+    // raise taken so far. The cell never pays out more than this
+    pub fn pot(&self) -> U256 {
+        self.current_stake() * U256::from(2)
+    }
+
+    // What each player deposits to take up the pending raise
+    pub fn raise_top_up(&self) -> U256 {
+        self.raise_stake.saturating_sub(self.current_stake())
+    }
+
+    // Sum of the payouts of every finished round
+    pub fn total_payouts(&self) -> (U256, U256) {
+        let mut total_p1 = U256::ZERO;
+        let mut total_p2 = U256::ZERO;
+
+        for round in &self.rounds {
+            if round.is_finished {
+                total_p1 += round.player1_payout;
+                total_p2 += round.player2_payout;
+            }
+        }
+        (total_p1, total_p2)
+    }
+}
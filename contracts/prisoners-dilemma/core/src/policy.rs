@@ -0,0 +1,83 @@
+// Standing continuation policies for voted two-player cells
+//
+// A policy decides a player's continuation vote automatically when a round
+// resolves, so players who know their plan do not need a transaction per
+// round. An explicit `Action::ContinuationDecision` made before the round
+// resolves overrides the policy for that round.
+use alloy_primitives::{I256, U256};
+
+use crate::{Cell, Move};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContinuationPolicy {
+    // Vote every round with `ContinuationDecision`
+    #[default]
+    Manual,
+    Always,
+    // Stop after a round in which the opponent's move was a defection
+    StopWhenDefected,
+    // Continue until this many rounds have been played
+    StopAfterRounds(u8),
+    // Stop once the player's net payout, what they were paid beyond the share
+    // of their stake each round played was played at, falls below this amount
+    StopBelowNet(I256),
+}
+
+impl ContinuationPolicy {
+    pub fn kind(self) -> u8 {
+        match self {
+            ContinuationPolicy::Manual => 0,
+            ContinuationPolicy::Always => 1,
+            ContinuationPolicy::StopWhenDefected => 2,
+            ContinuationPolicy::StopAfterRounds(_) => 3,
+            ContinuationPolicy::StopBelowNet(_) => 4,
+        }
+    }
+
+    // The round count or net payout floor, zero for policies without one
+    pub fn param(self) -> I256 {
+        match self {
+            ContinuationPolicy::StopAfterRounds(rounds) => I256::from_raw(U256::from(rounds)),
+            ContinuationPolicy::StopBelowNet(floor) => floor,
+            _ => I256::ZERO,
+        }
+    }
+
+    // Inverse of `kind` and `param`; `None` for an unknown kind or a round
+    // count that does not fit a cell
+    pub fn from_parts(kind: u8, param: I256) -> Option<Self> {
+        match kind {
+            0 => Some(ContinuationPolicy::Manual),
+            1 => Some(ContinuationPolicy::Always),
+            2 => Some(ContinuationPolicy::StopWhenDefected),
+            3 => u8::try_from(param).ok().map(ContinuationPolicy::StopAfterRounds),
+            4 => Some(ContinuationPolicy::StopBelowNet(param)),
+            _ => None,
+        }
+    }
+
+    // The vote this policy casts for a player once the current round is
+    // finished, or `None` if the player votes by hand
+    pub fn decide(self, cell: &Cell, player1: bool) -> Option<bool> {
+        let finished = || cell.rounds.iter().filter(|round| round.is_finished);
+        match self {
+            ContinuationPolicy::Manual => None,
+            ContinuationPolicy::Always => Some(true),
+            ContinuationPolicy::StopWhenDefected => {
+                let last = cell.rounds.get((cell.current_round as usize).checked_sub(1)?)?;
+                let opponent = if player1 { last.player2_move } else { last.player1_move };
+                Some(opponent != Some(Move::Defect))
+            }
+            ContinuationPolicy::StopAfterRounds(rounds) => Some(finished().count() < rounds as usize),
+            ContinuationPolicy::StopBelowNet(floor) => {
+                let (paid, staked) = finished().fold((U256::ZERO, U256::ZERO), |(paid, staked), round| {
+                    let payout = if player1 { round.player1_payout } else { round.player2_payout };
+                    (paid + payout, staked + cell.round_stake(round))
+                });
+                let signed = |amount: U256| I256::try_from(amount).unwrap_or(I256::MAX);
+                let net = signed(paid).saturating_sub(signed(staked));
+                Some(net >= floor)
+            }
+        }
+    }
+}
@@ -0,0 +1,849 @@
+// Game rules as a pure state machine
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256, U256};
+
+use crate::channel::channel_totals;
+use crate::sealed::{seal_id, sealed_leaf, verify_proof};
+use crate::settlement::{credited, round_credits, round_payouts, settlement};
+use crate::{badges, Badge, Cell, CellState, ChannelState, ContinuationPolicy, Move, Round, SealedReveal, P1_DECIDED, P1_RAISED, P1_WANTS_CONTINUE, P2_DECIDED, P2_RAISED, P2_WANTS_CONTINUE};
+
+// Something a player does to a cell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Create { player: Address, stake: U256, total_rounds: u8, noise_bps: u16, continuation_bps: u16 },
+    Join { player: Address, stake: U256 },
+    // A cell whose round payouts are credited as each round resolves
+    CreateStreamed { player: Address, stake: U256, total_rounds: u8, noise_bps: u16, continuation_bps: u16 },
+    // Sealed cells: each player commits the Merkle root of their moves for
+    // every round when creating or joining, then reveals them with proofs.
+    // Rounds resolve as soon as both moves are revealed and always continue.
+    // The creator's `nonce` makes the cell's seal, which every leaf is bound to
+    CreateSealed { player: Address, stake: U256, total_rounds: u8, nonce: U256, root: B256 },
+    JoinSealed { player: Address, stake: U256, root: B256 },
+    RevealSealed { player: Address, reveals: Vec<SealedReveal> },
+    SubmitMove { player: Address, player_move: Move },
+    // A move together with the player's continuation vote for the same round,
+    // so the next round can open as soon as the round resolves
+    SubmitMoveWithContinuation { player: Address, player_move: Move, wants_continue: bool },
+    // Commit-reveal moves, only for cells that draw noise or δ: committing only
+    // checks that the player may move now, and the second reveal of a round
+    // carries the entropy of both players' salts
+    CommitMove { player: Address },
+    RevealMove { player: Address, player_move: Move, entropy: Option<B256> },
+    // Ends a round `player` is left waiting in once the caller's commit or
+    // reveal window has passed: the cell times out and the opponent, who
+    // withheld their commitment or move, forfeits everything the cell has not
+    // already credited them. The caller tracks commitments, so it names the
+    // player; the rules only check that the opponent has not moved
+    ClaimRevealTimeout { player: Address },
+    ContinuationDecision { player: Address, wants_continue: bool },
+    // Sets the player's standing continuation vote for the rest of the cell
+    SetContinuationPolicy { player: Address, policy: ContinuationPolicy },
+    // Stake escalation: while a round waits on continuation votes, a player
+    // proposes the stake for the next round with a deposit of the top-up over
+    // the current stake. The raise applies only if the opponent deposits the
+    // same top-up before the next round opens; otherwise deposits are refunded
+    ProposeRaise { player: Address, stake: U256, deposit: U256 },
+    AcceptRaise { player: Address, deposit: U256 },
+    // Withdraws a cell nobody has joined yet, refunding its creator
+    Cancel { player: Address },
+    // Replaces the rounds played so far with a longer state both players
+    // signed off-chain, opening a challenge window. The caller checks the
+    // signatures and that the state is for this cell
+    SubmitChannelState { state: ChannelState },
+    // Closes the challenge window, completing the cell if every round was
+    // played and otherwise resuming on-chain play with the next round
+    FinalizeChannel,
+}
+
+// Environment the rules are evaluated in, supplied by the caller
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Context {
+    pub min_stake: U256,
+    pub payoffs: PayoffMatrix,
+}
+
+// Per-round payouts as a multiple of the stake, in basis points (10000 = 1x).
+// Named after the classic matrix: R (both cooperate), S (cooperate against a
+// defector), T (defect against a cooperator) and P (both defect).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PayoffMatrix {
+    pub reward: u32,
+    pub sucker: u32,
+    pub temptation: u32,
+    pub punishment: u32,
+}
+
+impl PayoffMatrix {
+    pub const BASIS_POINTS: u32 = 10_000;
+
+    // The matrix the contract pays out with
+    pub const CONTRACT: PayoffMatrix = PayoffMatrix {
+        reward: 10_000,
+        sucker: 5_000,
+        temptation: 15_000,
+        punishment: 5_000,
+    };
+
+    // Per-round payouts for a pair of moves at the given stake
+    pub fn payoff(&self, stake: U256, p1_move: Move, p2_move: Move) -> (U256, U256) {
+        let scale = |bps: u32| stake * U256::from(bps) / U256::from(Self::BASIS_POINTS);
+        match (p1_move, p2_move) {
+            (Move::Cooperate, Move::Cooperate) => (scale(self.reward), scale(self.reward)),
+            (Move::Defect, Move::Defect) => (scale(self.punishment), scale(self.punishment)),
+            (Move::Cooperate, Move::Defect) => (scale(self.sucker), scale(self.temptation)),
+            (Move::Defect, Move::Cooperate) => (scale(self.temptation), scale(self.sucker)),
+        }
+    }
+}
+
+impl Default for PayoffMatrix {
+    fn default() -> Self {
+        Self::CONTRACT
+    }
+}
+
+// Side effects the caller must carry out, in order, after applying an action
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+    CellCreated { player1: Address, stake: U256 },
+    PlayerJoined { player2: Address },
+    GroupPlayerJoined { player: Address },
+    RoundComplete { round_num: u8 },
+    ContinuationDrawn { round_num: u8, draw: u16, continues: bool },
+    ContinuationPolicySet { player: Address, policy: ContinuationPolicy },
+    StateChanged { from: CellState, to: CellState },
+    ChannelStateSubmitted { rounds: u8 },
+    StrategySealed { player: Address, root: B256 },
+    SealedMovesRevealed { player: Address, count: u8 },
+    RaiseProposed { player: Address, stake: U256 },
+    RaiseAccepted { player: Address, stake: U256 },
+    // A top-up deposited for a raise that lapsed, owed back to the player
+    RaiseRefunded { player: Address, amount: U256 },
+    Payout { player: Address, amount: U256 },
+    // A streamed cell's payout for one round, owed to the player from then on
+    Credit { player: Address, round_num: u8, amount: U256 },
+    BadgeEarned { player: Address, badge: Badge },
+    CellComplete,
+}
+
+// Rule violations, mapped one-to-one onto the contract's errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    StakeTooLow,
+    AlreadyInCell,
+    CellFull,
+    WrongStake,
+    CellIsComplete,
+    NeedPlayer2,
+    NotInCell,
+    NoRoundStarted,
+    RoundNotReady,
+    RoundAlreadyFinished,
+    MaxRoundsReached,
+    InvalidGroupSize,
+    InvalidMultiplier,
+    WaitingForPlayers,
+    InvalidProbability,
+    CommitRequired,
+    // Commitments and reveals are only for cells that draw noise or δ
+    NotCommitReveal,
+    ContinuationIsRandom,
+    CellNotFound,
+    OpponentJoined,
+    // The action would move the cell along an edge the transition table lacks
+    InvalidTransition,
+    // Noise and δ cells need on-chain randomness, so they cannot use a channel
+    ChannelUnavailable,
+    // The channel state's totals do not follow its moves, it has more rounds
+    // than the cell, or it disagrees with rounds already played
+    InvalidChannelState,
+    // The channel state has no rounds beyond those the cell already holds
+    StaleChannelState,
+    // Only a disputed cell has a channel state to finalize
+    NoChannelState,
+    // On-chain play waits while a channel state is being challenged
+    ChannelDisputed,
+    // Sealed cells are joined and played only through their sealed actions
+    SealedCell,
+    // A sealed action on a cell without sealed strategies, or a zero root
+    NotSealed,
+    // A revealed move is not in the player's sealed strategy
+    InvalidProof,
+    // More sealed moves revealed at once than the cell has rounds
+    TooManyReveals,
+    // Raises are made only while a voted cell waits on continuation votes
+    // with rounds left to play
+    RaiseUnavailable,
+    // The raised stake is not above the current one, or the deposit is not its top-up
+    InvalidRaise,
+    // A raise is already proposed, or the player has already deposited for it
+    RaisePending,
+    NoRaiseProposed,
+    // A reveal timeout needs an unfinished round the opponent has not moved in
+    NoRevealPending,
+}
+
+// Noise and δ are probabilities, so at most 10000 basis points
+pub const MAX_PROBABILITY_BPS: u16 = PayoffMatrix::BASIS_POINTS as u16;
+
+// Applies `action` to `cell`, returning the updated cell and the effects to run.
+// On error nothing has happened and the stored cell must be left as it was.
+pub fn apply(mut cell: Cell, action: Action, ctx: &Context) -> Result<(Cell, Vec<Effect>), Error> {
+    let mut effects = Vec::new();
+
+    match action {
+        Action::Create { player, stake, total_rounds, noise_bps, continuation_bps } => {
+            if stake < ctx.min_stake {
+                return Err(Error::StakeTooLow);
+            }
+            if noise_bps > MAX_PROBABILITY_BPS || continuation_bps > MAX_PROBABILITY_BPS {
+                return Err(Error::InvalidProbability);
+            }
+            cell = Cell {
+                player1: player,
+                stake_amount: stake,
+                total_rounds,
+                noise_bps,
+                continuation_bps,
+                ..Cell::default()
+            };
+            effects.push(Effect::CellCreated { player1: player, stake });
+        }
+        Action::Join { player, stake } => {
+            if cell.is_sealed() {
+                return Err(Error::SealedCell);
+            }
+            join(&mut cell, player, stake, &mut effects)?;
+        }
+        Action::CreateStreamed { player, stake, total_rounds, noise_bps, continuation_bps } => {
+            let create = Action::Create { player, stake, total_rounds, noise_bps, continuation_bps };
+            (cell, effects) = apply(cell, create, ctx)?;
+            cell.streamed = true;
+        }
+        Action::CreateSealed { player, stake, total_rounds, nonce, root } => {
+            if root.is_zero() {
+                return Err(Error::NotSealed);
+            }
+            let create = Action::Create { player, stake, total_rounds, noise_bps: 0, continuation_bps: 0 };
+            (cell, effects) = apply(cell, create, ctx)?;
+            cell.player1_root = root;
+            cell.seal = seal_id(player, nonce);
+            effects.push(Effect::StrategySealed { player, root });
+        }
+        Action::JoinSealed { player, stake, root } => {
+            if cell.player1 != Address::ZERO && !cell.is_sealed() || root.is_zero() {
+                return Err(Error::NotSealed);
+            }
+            join(&mut cell, player, stake, &mut effects)?;
+            cell.player2_root = root;
+            effects.push(Effect::StrategySealed { player, root });
+        }
+        Action::RevealSealed { player, reveals } => {
+            if cell.player1 == Address::ZERO {
+                return Err(Error::CellNotFound);
+            }
+            if !cell.is_sealed() {
+                return Err(Error::NotSealed);
+            }
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            if cell.player2 == Address::ZERO {
+                return Err(Error::NeedPlayer2);
+            }
+            if !cell.is_player(player) {
+                return Err(Error::NotInCell);
+            }
+            if reveals.len() > cell.total_rounds as usize {
+                return Err(Error::TooManyReveals);
+            }
+
+            let player1 = player == cell.player1;
+            let root = if player1 { cell.player1_root } else { cell.player2_root };
+            for reveal in &reveals {
+                let idx = (reveal.round as usize).wrapping_sub(1);
+                let leaf = sealed_leaf(cell.seal, player, reveal.round, reveal.player_move, reveal.salt);
+                if !verify_proof(root, leaf, idx, cell.total_rounds as usize, &reveal.proof) {
+                    return Err(Error::InvalidProof);
+                }
+                // Sealed rounds resolve as soon as both moves are in, so only earlier ones are finished
+                if reveal.round < cell.current_round {
+                    return Err(Error::RoundAlreadyFinished);
+                }
+                if cell.sealed_moves.len() <= idx {
+                    cell.sealed_moves.resize(idx + 1, (None, None));
+                }
+                let slot = &mut cell.sealed_moves[idx];
+                if player1 { slot.0 = Some(reveal.player_move) } else { slot.1 = Some(reveal.player_move) }
+            }
+            effects.push(Effect::SealedMovesRevealed { player, count: reveals.len() as u8 });
+            play_sealed_rounds(&mut cell, &ctx.payoffs, &mut effects)?;
+        }
+        Action::SubmitMove { player, player_move } => {
+            let round_idx = check_move(&cell, player)?;
+            if cell.requires_commit() {
+                return Err(Error::CommitRequired);
+            }
+            record_move(&mut cell, round_idx, player, player_move, None, &ctx.payoffs, &mut effects)?;
+        }
+        Action::SubmitMoveWithContinuation { player, player_move, wants_continue } => {
+            let round_idx = check_move(&cell, player)?;
+            if cell.requires_commit() {
+                return Err(Error::CommitRequired);
+            }
+            // After the last round there is nothing to vote on
+            if cell.current_round < cell.total_rounds {
+                let player1 = player == cell.player1;
+                decide(&mut cell, player1, wants_continue);
+            }
+            record_move(&mut cell, round_idx, player, player_move, None, &ctx.payoffs, &mut effects)?;
+        }
+        Action::CommitMove { player } => {
+            check_move(&cell, player)?;
+            if !cell.requires_commit() {
+                return Err(Error::NotCommitReveal);
+            }
+        }
+        Action::RevealMove { player, player_move, entropy } => {
+            let round_idx = check_move(&cell, player)?;
+            if !cell.requires_commit() {
+                return Err(Error::NotCommitReveal);
+            }
+            record_move(&mut cell, round_idx, player, player_move, entropy, &ctx.payoffs, &mut effects)?;
+        }
+        Action::ClaimRevealTimeout { player } => {
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            if !cell.is_player(player) {
+                return Err(Error::NotInCell);
+            }
+            let round_idx = (cell.current_round as usize).wrapping_sub(1);
+            let round = cell.rounds.get(round_idx).filter(|round| !round.is_finished);
+            // A sealed move counts once revealed, though its round waits on the other
+            let (sealed_p1, sealed_p2) = cell.sealed_moves.get(round_idx).copied().unwrap_or((None, None));
+            let opponent_moved = round.is_none_or(|round| {
+                if player == cell.player1 {
+                    round.player2_move.or(sealed_p2).is_some()
+                } else {
+                    round.player1_move.or(sealed_p1).is_some()
+                }
+            });
+            if opponent_moved {
+                return Err(Error::NoRevealPending);
+            }
+
+            set_state(&mut cell, CellState::TimedOut, &mut effects)?;
+            cell.is_complete = true;
+            cell.timeout_winner = player;
+            let (owed_p1, owed_p2) = settlement(&cell);
+            let (credited_p1, credited_p2) = credited(&cell);
+            for (player, amount) in [(cell.player1, owed_p1 - credited_p1), (cell.player2, owed_p2 - credited_p2)] {
+                if amount > U256::ZERO {
+                    effects.push(Effect::Payout { player, amount });
+                }
+            }
+            effects.push(Effect::CellComplete);
+        }
+        Action::Cancel { player } => {
+            if cell.player1 == Address::ZERO {
+                return Err(Error::CellNotFound);
+            }
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            if player != cell.player1 {
+                return Err(Error::NotInCell);
+            }
+            if cell.player2 != Address::ZERO {
+                return Err(Error::OpponentJoined);
+            }
+
+            set_state(&mut cell, CellState::Cancelled, &mut effects)?;
+            cell.is_complete = true;
+            effects.push(Effect::Payout { player, amount: cell.stake_amount });
+            effects.push(Effect::CellComplete);
+        }
+        Action::SubmitChannelState { state } => {
+            if cell.player1 == Address::ZERO {
+                return Err(Error::CellNotFound);
+            }
+            if cell.is_complete {
+                return Err(Error::CellIsComplete);
+            }
+            if cell.player2 == Address::ZERO {
+                return Err(Error::NeedPlayer2);
+            }
+            // Streamed cells credit rounds as they resolve on-chain, which channel
+            // rounds never do, and channel rounds are all played at the cell's stake
+            if cell.requires_commit() || cell.continuation_bps > 0 || cell.is_sealed() || cell.streamed {
+                return Err(Error::ChannelUnavailable);
+            }
+            if cell.current_stake() != cell.stake_amount || !cell.raise_stake.is_zero() {
+                return Err(Error::ChannelUnavailable);
+            }
+            // Channel rounds are played at the same share of the stake as on-chain ones
+            let stake = cell.round_stake(&Round::default());
+            let totals = channel_totals(stake, &state.rounds, &ctx.payoffs);
+            if state.rounds.len() > cell.total_rounds as usize || totals != (state.player1_total, state.player2_total) {
+                return Err(Error::InvalidChannelState);
+            }
+            let finished: Vec<&Round> = cell.rounds.iter().filter(|round| round.is_finished).collect();
+            if state.rounds.len() <= finished.len() {
+                return Err(Error::StaleChannelState);
+            }
+            let agrees = finished
+                .iter()
+                .zip(&state.rounds)
+                .all(|(round, &(p1_move, p2_move))| (round.player1_move, round.player2_move) == (Some(p1_move), Some(p2_move)));
+            if !agrees {
+                return Err(Error::InvalidChannelState);
+            }
+
+            cell.rounds = state
+                .rounds
+                .iter()
+                .map(|&(p1_move, p2_move)| {
+                    let (player1_payout, player2_payout) = round_payouts(&ctx.payoffs, stake, p1_move, p2_move);
+                    Round {
+                        player1_move: Some(p1_move),
+                        player2_move: Some(p2_move),
+                        player1_payout,
+                        player2_payout,
+                        is_finished: true,
+                        ..Round::default()
+                    }
+                })
+                .collect();
+            cell.current_round = state.rounds.len() as u8;
+            cell.continuation_flags = 0;
+            effects.push(Effect::ChannelStateSubmitted { rounds: cell.current_round });
+            set_state(&mut cell, CellState::Disputed, &mut effects)?;
+        }
+        Action::FinalizeChannel => {
+            if cell.state != CellState::Disputed {
+                return Err(Error::NoChannelState);
+            }
+            for round_idx in 0..cell.rounds.len() {
+                badges::round_badges(&cell, round_idx, &mut effects);
+            }
+            if cell.current_round >= cell.total_rounds {
+                complete_cell(&mut cell, &mut effects)?;
+            } else {
+                // Rounds are left, so continuing is voted on as after any
+                // round played on-chain
+                apply_policies(&mut cell);
+                settle_continuation(&mut cell, &mut effects)?;
+            }
+        }
+        Action::ContinuationDecision { player, wants_continue } => {
+            check_continuation(&cell, player)?;
+            if cell.current_round >= cell.total_rounds {
+                return Err(Error::MaxRoundsReached);
+            }
+
+            // Made while the round is still being played, the decision waits
+            // for it to resolve and overrides the player's policy
+            let player1 = player == cell.player1;
+            decide(&mut cell, player1, wants_continue);
+            if round_is_finished(&cell) {
+                settle_continuation(&mut cell, &mut effects)?;
+            }
+        }
+        Action::SetContinuationPolicy { player, policy } => {
+            check_continuation(&cell, player)?;
+
+            if player == cell.player1 {
+                cell.player1_policy = policy;
+            } else {
+                cell.player2_policy = policy;
+            }
+            effects.push(Effect::ContinuationPolicySet { player, policy });
+
+            // A round already waiting on votes takes the new policy right away
+            if round_is_finished(&cell) && cell.current_round < cell.total_rounds {
+                apply_policies(&mut cell);
+                settle_continuation(&mut cell, &mut effects)?;
+            }
+        }
+        Action::ProposeRaise { player, stake, deposit } => {
+            check_raise(&cell, player)?;
+            if !cell.raise_stake.is_zero() {
+                return Err(Error::RaisePending);
+            }
+            let current = cell.current_stake();
+            if stake <= current || deposit != stake - current {
+                return Err(Error::InvalidRaise);
+            }
+
+            cell.raise_stake = stake;
+            cell.raise_flags = raise_flag(&cell, player);
+            effects.push(Effect::RaiseProposed { player, stake });
+        }
+        Action::AcceptRaise { player, deposit } => {
+            check_raise(&cell, player)?;
+            if cell.raise_stake.is_zero() {
+                return Err(Error::NoRaiseProposed);
+            }
+            let flag = raise_flag(&cell, player);
+            if cell.raise_flags & flag != 0 {
+                return Err(Error::RaisePending);
+            }
+            if deposit != cell.raise_top_up() {
+                return Err(Error::InvalidRaise);
+            }
+
+            cell.raise_flags |= flag;
+            effects.push(Effect::RaiseAccepted { player, stake: cell.raise_stake });
+        }
+    }
+
+    Ok((cell, effects))
+}
+
+// Seats `player` as player 2 and opens the first round
+fn join(cell: &mut Cell, player: Address, stake: U256, effects: &mut Vec<Effect>) -> Result<(), Error> {
+    if cell.player1 == Address::ZERO {
+        return Err(Error::CellNotFound);
+    }
+    if cell.is_complete {
+        return Err(Error::CellIsComplete);
+    }
+    if cell.player2 != Address::ZERO {
+        return Err(Error::CellFull);
+    }
+    if player == cell.player1 {
+        return Err(Error::AlreadyInCell);
+    }
+    if stake != cell.stake_amount {
+        return Err(Error::WrongStake);
+    }
+
+    cell.player2 = player;
+    // Initialize first round
+    open_round(cell, cell.stake_amount);
+    effects.push(Effect::PlayerJoined { player2: player });
+    set_state(cell, CellState::AwaitingMoves, effects)
+}
+
+// Plays every round, from the current one, whose sealed moves have both been
+// revealed, consuming them; unconsumed reveals for later rounds stay behind
+fn play_sealed_rounds(cell: &mut Cell, payoffs: &PayoffMatrix, effects: &mut Vec<Effect>) -> Result<(), Error> {
+    while !cell.is_complete {
+        let round_idx = cell.current_round as usize - 1;
+        let Some(&(Some(p1_move), Some(p2_move))) = cell.sealed_moves.get(round_idx) else { break };
+        cell.sealed_moves[round_idx] = (None, None);
+        let round = &mut cell.rounds[round_idx];
+        round.player1_move = Some(p1_move);
+        round.player2_move = Some(p2_move);
+        round.sealed = true;
+        resolve_round(cell, round_idx, payoffs, None, effects)?;
+    }
+    while cell.sealed_moves.last() == Some(&(None, None)) {
+        cell.sealed_moves.pop();
+    }
+    Ok(())
+}
+
+// Per-round payouts for a pair of moves at the given stake, using the contract's matrix
+pub fn payoff(stake: U256, p1_move: Move, p2_move: Move) -> (U256, U256) {
+    PayoffMatrix::CONTRACT.payoff(stake, p1_move, p2_move)
+}
+
+// Checks that `player` may vote on continuing this voted cell
+fn check_continuation(cell: &Cell, player: Address) -> Result<(), Error> {
+    if cell.is_complete {
+        return Err(Error::CellIsComplete);
+    }
+    if !cell.is_player(player) {
+        return Err(Error::NotInCell);
+    }
+    if cell.continuation_bps > 0 {
+        return Err(Error::ContinuationIsRandom);
+    }
+    if cell.state == CellState::Disputed {
+        return Err(Error::ChannelDisputed);
+    }
+    if cell.is_sealed() {
+        return Err(Error::SealedCell);
+    }
+    Ok(())
+}
+
+// Checks that `player` may propose or accept a raise of the next round's stake
+fn check_raise(cell: &Cell, player: Address) -> Result<(), Error> {
+    check_continuation(cell, player)?;
+    if cell.state != CellState::AwaitingContinuation || cell.current_round >= cell.total_rounds {
+        return Err(Error::RaiseUnavailable);
+    }
+    Ok(())
+}
+
+fn raise_flag(cell: &Cell, player: Address) -> u8 {
+    if player == cell.player1 { P1_RAISED } else { P2_RAISED }
+}
+
+// Clears the pending raise. It takes effect if the next round `opens` and both
+// players deposited, returning that round's stake; otherwise whoever
+// deposited is refunded
+fn settle_raise(cell: &mut Cell, opens: bool, effects: &mut Vec<Effect>) -> Option<U256> {
+    let (stake, flags) = (cell.raise_stake, cell.raise_flags);
+    cell.raise_stake = U256::ZERO;
+    cell.raise_flags = 0;
+    if stake.is_zero() {
+        return None;
+    }
+    if opens && flags == P1_RAISED | P2_RAISED {
+        return Some(stake);
+    }
+    let amount = stake - cell.current_stake();
+    for (flag, player) in [(P1_RAISED, cell.player1), (P2_RAISED, cell.player2)] {
+        if flags & flag != 0 {
+            effects.push(Effect::RaiseRefunded { player, amount });
+        }
+    }
+    None
+}
+
+// Opens the next round, played at `stake`
+fn open_round(cell: &mut Cell, stake: U256) {
+    cell.current_round += 1;
+    let stake = if stake == cell.stake_amount { U256::ZERO } else { stake };
+    cell.rounds.push(Round { stake, ..Round::default() });
+}
+
+// Whether the current round has been played and now waits on continuation votes
+fn round_is_finished(cell: &Cell) -> bool {
+    let round_idx = (cell.current_round as usize).checked_sub(1);
+    round_idx.and_then(|idx| cell.rounds.get(idx)).is_some_and(|round| round.is_finished)
+}
+
+// Records a player's continuation vote for the current round
+fn decide(cell: &mut Cell, player1: bool, wants_continue: bool) {
+    let (wants, decided) = if player1 { (P1_WANTS_CONTINUE, P1_DECIDED) } else { (P2_WANTS_CONTINUE, P2_DECIDED) };
+    if wants_continue {
+        cell.continuation_flags |= wants;
+    } else {
+        cell.continuation_flags &= !wants;
+    }
+    cell.continuation_flags |= decided;
+}
+
+// Votes for each player who has not decided this round, using their policy
+fn apply_policies(cell: &mut Cell) {
+    let (p1_decided, _, p2_decided, _) = cell.continuation_status();
+    if !p1_decided {
+        if let Some(wants_continue) = cell.player1_policy.decide(cell, true) {
+            decide(cell, true, wants_continue);
+        }
+    }
+    if !p2_decided {
+        if let Some(wants_continue) = cell.player2_policy.decide(cell, false) {
+            decide(cell, false, wants_continue);
+        }
+    }
+}
+
+// Opens the next round or completes the cell once both players have voted,
+// and otherwise waits for the missing votes
+fn settle_continuation(cell: &mut Cell, effects: &mut Vec<Effect>) -> Result<(), Error> {
+    let (p1_decided, p1_wants, p2_decided, p2_wants) = cell.continuation_status();
+    if !(p1_decided && p2_decided) {
+        return set_state(cell, CellState::AwaitingContinuation, effects);
+    }
+    if p1_wants && p2_wants && cell.current_round < cell.total_rounds {
+        // Both want to continue - create next round, raised if both deposited
+        let raised = settle_raise(cell, true, effects);
+        let stake = raised.unwrap_or_else(|| cell.current_stake());
+        open_round(cell, stake);
+        cell.continuation_flags = 0;
+        set_state(cell, CellState::AwaitingMoves, effects)
+    } else {
+        // At least one doesn't want to continue or max rounds reached - end
+        // cell, refunding any raise since no round is played at it
+        settle_raise(cell, false, effects);
+        complete_cell(cell, effects)
+    }
+}
+
+// The only place a cell changes state, following `CellState::can_become`
+fn set_state(cell: &mut Cell, to: CellState, effects: &mut Vec<Effect>) -> Result<(), Error> {
+    let from = cell.state;
+    if from == to {
+        return Ok(());
+    }
+    if !from.can_become(to) {
+        return Err(Error::InvalidTransition);
+    }
+    cell.state = to;
+    effects.push(Effect::StateChanged { from, to });
+    Ok(())
+}
+
+// Checks that `player` may move in the current round, returning its index
+fn check_move(cell: &Cell, player: Address) -> Result<usize, Error> {
+    if cell.is_complete {
+        return Err(Error::CellIsComplete);
+    }
+    if cell.player2 == Address::ZERO {
+        return Err(Error::NeedPlayer2);
+    }
+    if !cell.is_player(player) {
+        return Err(Error::NotInCell);
+    }
+    if cell.current_round == 0 {
+        return Err(Error::NoRoundStarted);
+    }
+    if cell.state == CellState::Disputed {
+        return Err(Error::ChannelDisputed);
+    }
+    if cell.is_sealed() {
+        return Err(Error::SealedCell);
+    }
+    let round_idx = (cell.current_round - 1) as usize;
+    let Some(round) = cell.rounds.get(round_idx) else {
+        return Err(Error::RoundNotReady);
+    };
+    if round.is_finished {
+        return Err(Error::RoundAlreadyFinished);
+    }
+    let slot = if player == cell.player1 { round.player1_move } else { round.player2_move };
+    if slot.is_some() {
+        return Err(Error::RoundAlreadyFinished);
+    }
+    Ok(round_idx)
+}
+
+fn record_move(
+    cell: &mut Cell,
+    round_idx: usize,
+    player: Address,
+    player_move: Move,
+    entropy: Option<B256>,
+    payoffs: &PayoffMatrix,
+    effects: &mut Vec<Effect>,
+) -> Result<(), Error> {
+    let round = &mut cell.rounds[round_idx];
+    if player == cell.player1 {
+        round.player1_move = Some(player_move);
+    } else {
+        round.player2_move = Some(player_move);
+    }
+
+    // Check if round is complete
+    if round.player1_move.is_some() && round.player2_move.is_some() {
+        if let Some(entropy) = entropy {
+            tremble(cell, round_idx, entropy);
+        }
+        resolve_round(cell, round_idx, payoffs, entropy, effects)?;
+    }
+    Ok(())
+}
+
+// A uniform draw in 0..10000 from four bytes of the entropy, so each use of
+// the same entropy (noise per player, continuation) takes its own bytes
+fn draw_bps(entropy: B256, offset: usize) -> u16 {
+    let bytes = [entropy[offset], entropy[offset + 1], entropy[offset + 2], entropy[offset + 3]];
+    (u32::from_be_bytes(bytes) % PayoffMatrix::BASIS_POINTS) as u16
+}
+
+// Flips each move with probability `noise_bps`
+fn tremble(cell: &mut Cell, round_idx: usize, entropy: B256) {
+    let noise = cell.noise_bps;
+    if noise == 0 {
+        return;
+    }
+
+    let round = &mut cell.rounds[round_idx];
+    if draw_bps(entropy, 0) < noise {
+        round.player1_move = round.player1_move.map(Move::opposite);
+        round.player1_flipped = true;
+    }
+    if draw_bps(entropy, 4) < noise {
+        round.player2_move = round.player2_move.map(Move::opposite);
+        round.player2_flipped = true;
+    }
+}
+
+fn resolve_round(
+    cell: &mut Cell,
+    round_idx: usize,
+    payoffs: &PayoffMatrix,
+    entropy: Option<B256>,
+    effects: &mut Vec<Effect>,
+) -> Result<(), Error> {
+    let stake = cell.round_stake(&cell.rounds[round_idx]);
+    let round = &mut cell.rounds[round_idx];
+    let (Some(p1_move), Some(p2_move)) = (round.player1_move, round.player2_move) else {
+        return Ok(());
+    };
+
+    let (p1_payout, p2_payout) = round_payouts(payoffs, stake, p1_move, p2_move);
+    round.player1_payout = p1_payout;
+    round.player2_payout = p2_payout;
+    round.is_finished = true;
+
+    effects.push(Effect::RoundComplete { round_num: cell.current_round });
+    if cell.streamed {
+        // This round is the latest finished one
+        let round_num = cell.current_round;
+        let (p1_credit, p2_credit) = round_credits(cell).last().unwrap_or_default();
+        for (player, amount) in [(cell.player1, p1_credit), (cell.player2, p2_credit)] {
+            if amount > U256::ZERO {
+                effects.push(Effect::Credit { player, round_num, amount });
+            }
+        }
+    }
+    badges::round_badges(cell, round_idx, effects);
+
+    // Check if we've completed all rounds
+    if cell.current_round >= cell.total_rounds {
+        complete_cell(cell, effects)
+    } else if let (true, Some(entropy)) = (cell.continuation_bps > 0, entropy) {
+        // Shadow of the future: continue with probability δ, no votes needed
+        let draw = draw_bps(entropy, 8);
+        let continues = draw < cell.continuation_bps;
+        cell.continuation_draws.push(draw);
+        effects.push(Effect::ContinuationDrawn { round_num: cell.current_round, draw, continues });
+        if continues {
+            open_round(cell, cell.current_stake());
+            Ok(())
+        } else {
+            complete_cell(cell, effects)
+        }
+    } else if cell.is_sealed() {
+        // Sealed strategies cover every round, so there is nothing to vote on
+        open_round(cell, cell.current_stake());
+        Ok(())
+    } else {
+        // Votes made during the round stand; policies cover the rest, and
+        // anyone still undecided is waited for
+        apply_policies(cell);
+        settle_continuation(cell, effects)
+    }
+}
+
+fn complete_cell(cell: &mut Cell, effects: &mut Vec<Effect>) -> Result<(), Error> {
+    set_state(cell, CellState::Completed, effects)?;
+    cell.is_complete = true;
+    badges::completion_badges(cell, effects);
+
+    // Streamed cells were credited round by round, and are credited the stake
+    // no round used along with the last round
+    let unspent = cell.unspent_stake();
+    if cell.streamed && unspent > U256::ZERO {
+        for player in [cell.player1, cell.player2] {
+            effects.push(Effect::Credit { player, round_num: cell.current_round, amount: unspent });
+        }
+    }
+    let (total_p1, total_p2) = if cell.streamed { (U256::ZERO, U256::ZERO) } else { settlement(cell) };
+    if total_p1 > U256::ZERO {
+        effects.push(Effect::Payout { player: cell.player1, amount: total_p1 });
+    }
+    if total_p2 > U256::ZERO {
+        effects.push(Effect::Payout { player: cell.player2, amount: total_p2 });
+    }
+    effects.push(Effect::CellComplete);
+    Ok(())
+}
@@ -0,0 +1,165 @@
+// Sealed whole-game strategies
+//
+// In a sealed cell each player commits, when creating or joining it, the root
+// of a Merkle tree over their move for every round. They later reveal moves
+// with proofs, one round at a time or all at once, and a round resolves as
+// soon as both of its moves are revealed, so a cell can finish in two reveal
+// transactions.
+//
+// A leaf is keccak256(seal | player | round | move | salt), binding each move
+// to its cell, player and round, so a strategy sealed for one cell proves
+// nothing in another. The seal is `seal_id` of the creator and a nonce they
+// choose, rather than the cell id, so the creator can build their tree before
+// the cell exists without predicting which id it will get; the contract takes
+// each seal only once. The tree has exactly one leaf per round, in round order;
+// inner nodes hash their left and right children and an unpaired last node
+// moves up a level as is. Proofs are checked against the leaf's position in a
+// tree of `total_rounds` leaves, so each round has exactly one provable move.
+//
+// Salts are derived from a per-cell seed, so a player only needs to keep the
+// seed and their moves to reveal.
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, Address, B256, U256};
+
+use crate::Move;
+
+// A revealed move of a sealed strategy with its proof against the player's root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealedReveal {
+    pub round: u8,
+    pub player_move: Move,
+    pub salt: B256,
+    pub proof: Vec<B256>,
+}
+
+impl SealedReveal {
+    // Reveals packed one after another, each as its round, move, salt, proof
+    // length and proof nodes, as `reveal_sealed_moves` takes them
+    pub fn encode_all(reveals: &[SealedReveal]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for reveal in reveals {
+            data.extend_from_slice(&[reveal.round, reveal.player_move as u8]);
+            data.extend_from_slice(reveal.salt.as_slice());
+            data.push(reveal.proof.len() as u8);
+            reveal.proof.iter().for_each(|node| data.extend_from_slice(node.as_slice()));
+        }
+        data
+    }
+
+    // `None` unless `data` is exactly a sequence of encoded reveals
+    pub fn decode_all(data: &[u8]) -> Option<Vec<SealedReveal>> {
+        let word = |at: usize| data.get(at..at + 32).map(B256::from_slice);
+        let (mut reveals, mut at) = (Vec::new(), 0);
+        while at < data.len() {
+            let (round, player_move, count) = (data[at], *data.get(at + 1)?, *data.get(at + 34)? as usize);
+            if player_move > 1 {
+                return None;
+            }
+            let proof = (0..count).map(|i| word(at + 35 + 32 * i)).collect::<Option<Vec<_>>>()?;
+            reveals.push(SealedReveal { round, player_move: Move::from(player_move), salt: word(at + 2)?, proof });
+            at += 35 + 32 * count;
+        }
+        Some(reveals)
+    }
+}
+
+// A player's sealed moves for every round of a cell, as kept off-chain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealedStrategy {
+    // The seal of the cell the moves are for
+    pub seal: B256,
+    pub player: Address,
+    pub moves: Vec<Move>,
+    pub seed: B256,
+}
+
+impl SealedStrategy {
+    pub fn salt(&self, round: u8) -> B256 {
+        keccak256([self.seed.as_slice(), &[round]].concat())
+    }
+
+    pub fn leaves(&self) -> Vec<B256> {
+        (1..=self.moves.len() as u8)
+            .zip(&self.moves)
+            .map(|(round, &player_move)| sealed_leaf(self.seal, self.player, round, player_move, self.salt(round)))
+            .collect()
+    }
+
+    pub fn root(&self) -> B256 {
+        merkle_root(&self.leaves())
+    }
+
+    // The reveal of round `round` (from 1), or `None` past the last round
+    pub fn reveal(&self, round: u8) -> Option<SealedReveal> {
+        let idx = (round as usize).checked_sub(1)?;
+        let player_move = *self.moves.get(idx)?;
+        Some(SealedReveal { round, player_move, salt: self.salt(round), proof: merkle_proof(&self.leaves(), idx) })
+    }
+}
+
+// What the leaves of a sealed cell created by `creator` with `nonce` are bound to
+pub fn seal_id(creator: Address, nonce: U256) -> B256 {
+    let mut data = Vec::with_capacity(52);
+    data.extend_from_slice(creator.as_slice());
+    data.extend_from_slice(&nonce.to_be_bytes::<32>());
+    keccak256(&data)
+}
+
+pub fn sealed_leaf(seal: B256, player: Address, round: u8, player_move: Move, salt: B256) -> B256 {
+    let mut data = Vec::with_capacity(86);
+    data.extend_from_slice(seal.as_slice());
+    data.extend_from_slice(player.as_slice());
+    data.push(round);
+    data.push(player_move as u8);
+    data.extend_from_slice(salt.as_slice());
+    keccak256(&data)
+}
+
+fn hash_pair(left: B256, right: B256) -> B256 {
+    keccak256([left.as_slice(), right.as_slice()].concat())
+}
+
+// The next level up: pairs hashed together, an unpaired last node carried as is
+fn parent_level(level: &[B256]) -> Vec<B256> {
+    level.chunks(2).map(|pair| if let [a, b] = pair { hash_pair(*a, *b) } else { pair[0] }).collect()
+}
+
+// Zero for no leaves
+pub fn merkle_root(leaves: &[B256]) -> B256 {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level.first().copied().unwrap_or_default()
+}
+
+pub fn merkle_proof(leaves: &[B256], mut idx: usize) -> Vec<B256> {
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        if let Some(&sibling) = level.get(idx ^ 1) {
+            proof.push(sibling);
+        }
+        level = parent_level(&level);
+        idx /= 2;
+    }
+    proof
+}
+
+// Whether `proof` leads from `leaf`, at `idx` of `leaf_count` leaves, to `root`
+pub fn verify_proof(root: B256, leaf: B256, mut idx: usize, leaf_count: usize, proof: &[B256]) -> bool {
+    if idx >= leaf_count {
+        return false;
+    }
+    let (mut node, mut width, mut siblings) = (leaf, leaf_count, proof.iter());
+    while width > 1 {
+        let sibling = idx ^ 1;
+        if sibling < width {
+            let Some(&hash) = siblings.next() else { return false };
+            node = if idx % 2 == 0 { hash_pair(node, hash) } else { hash_pair(hash, node) };
+        }
+        idx /= 2;
+        width = width.div_ceil(2);
+    }
+    siblings.next().is_none() && node == root
+}
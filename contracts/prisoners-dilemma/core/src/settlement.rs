@@ -0,0 +1,132 @@
+// What a two-player cell pays out, now and at the end of play
+//
+// `settlement` is the amount `complete_cell` pays each player, or what a
+// streamed cell credits them round by round and when it completes; the
+// preview reuses it so what players are shown is exactly what they would be
+// sent, and both kinds of cell settle the same moves to the same amounts.
+// The contract takes no fee. Each round is played at its share of the stake
+// and pays at most twice that share, so the deposits fund every round up
+// front: a settlement is the sum of the finished rounds' payouts plus the
+// share of the stake no round used, and a cell is only ever paid from its
+// own pot. A cell that timed out waiting on a commitment or reveal pays the rest of
+// its pot to the player who was left waiting.
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+use core::iter;
+
+use crate::{Cell, CellState, Move, PayoffMatrix, Round};
+
+// One player's side of `preview_settlement`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SettlementPreview {
+    // Payouts of the rounds finished so far
+    pub accumulated: U256,
+    // What the player would be sent if the cell ended now
+    pub if_ended: U256,
+    // What the player would be sent if every round up to `total_rounds` is
+    // played, with all remaining rounds going as well or as badly as they can
+    pub best_case: U256,
+    pub worst_case: U256,
+}
+
+// Amounts (player1, player2) receive from the cell: paid when it completes,
+// or credited as it is played if it is streamed
+pub fn settlement(cell: &Cell) -> (U256, U256) {
+    let (credited_p1, credited_p2) = credited(cell);
+    if cell.state == CellState::TimedOut {
+        // Withholding a commitment or reveal forfeits everything not already credited
+        let rest = cell.pot().saturating_sub(credited_p1 + credited_p2);
+        return if cell.timeout_winner == cell.player1 { (credited_p1 + rest, credited_p2) } else { (credited_p1, credited_p2 + rest) };
+    }
+    let (total_p1, total_p2) = cell.total_payouts();
+    let unspent = cell.unspent_stake();
+    (total_p1 + unspent, total_p2 + unspent)
+}
+
+// What a streamed cell has credited (player1, player2) so far, nothing for other cells
+pub(crate) fn credited(cell: &Cell) -> (U256, U256) {
+    if !cell.streamed {
+        return (U256::ZERO, U256::ZERO);
+    }
+    round_credits(cell).fold((U256::ZERO, U256::ZERO), |(p1, p2), (c1, c2)| (p1 + c1, p2 + c2))
+}
+
+// What a streamed cell credits for each finished round in turn: the round's
+// payouts, which its own share of the deposits always covers
+pub(crate) fn round_credits(cell: &Cell) -> impl Iterator<Item = (U256, U256)> + '_ {
+    cell.rounds.iter().filter(|round| round.is_finished).map(|round| (round.player1_payout, round.player2_payout))
+}
+
+// What a round played at `stake` pays for the moves. A matrix paying more
+// than the two shares the deposits set aside for the round is scaled down to
+// them; the contract's never is
+pub(crate) fn round_payouts(payoffs: &PayoffMatrix, stake: U256, p1_move: Move, p2_move: Move) -> (U256, U256) {
+    cap_to_pot(payoffs.payoff(stake, p1_move, p2_move), stake * U256::from(2))
+}
+
+// Scales `(p1, p2)` down in proportion so that together they fit in `pot`,
+// rounding each share down
+pub(crate) fn cap_to_pot((p1, p2): (U256, U256), pot: U256) -> (U256, U256) {
+    let total = p1 + p2;
+    if total <= pot {
+        return (p1, p2);
+    }
+    (p1 * pot / total, p2 * pot / total)
+}
+
+pub fn preview_settlement(cell: &Cell, payoffs: &PayoffMatrix) -> (SettlementPreview, SettlementPreview) {
+    let (accumulated_p1, accumulated_p2) = cell.total_payouts();
+    // Before anyone joins, the only way out is cancelling, which refunds the creator
+    let (ended_p1, ended_p2) = match cell.state {
+        CellState::Open | CellState::Cancelled => (cell.stake_amount, U256::ZERO),
+        _ => settlement(cell),
+    };
+    if cell.state.is_terminal() {
+        let settled = |accumulated, ended| SettlementPreview {
+            accumulated,
+            if_ended: ended,
+            best_case: ended,
+            worst_case: ended,
+        };
+        return (settled(accumulated_p1, ended_p1), settled(accumulated_p2, ended_p2));
+    }
+
+    // The current round counts as remaining until it is finished, and
+    // remaining rounds are played at the current stake. Each outcome is
+    // settled as if every remaining round went that way
+    let finished: Vec<Round> = cell.rounds.iter().filter(|round| round.is_finished).cloned().collect();
+    let remaining = (cell.total_rounds as usize).saturating_sub(finished.len());
+    let stake = cell.current_stake();
+    let outcomes: Vec<(U256, U256)> = [Move::Cooperate, Move::Defect]
+        .into_iter()
+        .flat_map(|p1_move| [Move::Cooperate, Move::Defect].map(|p2_move| (p1_move, p2_move)))
+        .map(|(p1_move, p2_move)| {
+            let mut round = Round {
+                player1_move: Some(p1_move),
+                player2_move: Some(p2_move),
+                is_finished: true,
+                stake: if stake == cell.stake_amount { U256::ZERO } else { stake },
+                ..Round::default()
+            };
+            let (player1_payout, player2_payout) = round_payouts(payoffs, cell.round_stake(&round), p1_move, p2_move);
+            (round.player1_payout, round.player2_payout) = (player1_payout, player2_payout);
+            let mut played = cell.clone();
+            played.rounds = finished.iter().cloned().chain(iter::repeat_n(round, remaining)).collect();
+            settlement(&played)
+        })
+        .collect();
+    let range = |pick: fn(&(U256, U256)) -> U256| {
+        let amounts = outcomes.iter().map(pick);
+        (amounts.clone().max().unwrap_or_default(), amounts.min().unwrap_or_default())
+    };
+    let preview = |accumulated: U256, ended, (best_case, worst_case): (U256, U256)| SettlementPreview {
+        accumulated,
+        if_ended: ended,
+        best_case,
+        worst_case,
+    };
+    (
+        preview(accumulated_p1, ended_p1, range(|&(p1, _)| p1)),
+        preview(accumulated_p2, ended_p2, range(|&(_, p2)| p2)),
+    )
+}
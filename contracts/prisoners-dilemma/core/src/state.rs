@@ -0,0 +1,86 @@
+// Lifecycle of a two-player cell
+//
+// The state is stored with the cell and only ever changed through
+// `set_state` in the rules, which rejects any move the transition table does
+// not allow. A cell is `Disputed` while a channel state submitted from
+// off-chain play can still be challenged, and `TimedOut` once a player has
+// let their reveal window pass with their opponent's move already in.
+use crate::Cell;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CellState {
+    // Created and waiting for an opponent
+    #[default]
+    Open = 0,
+    // The current round is waiting for one or both moves
+    AwaitingMoves = 1,
+    // The current round is finished and waiting for continuation votes
+    AwaitingContinuation = 2,
+    Completed = 3,
+    // Withdrawn by its creator before anyone joined
+    Cancelled = 4,
+    // Ended because a player withheld their move past the reveal window
+    TimedOut = 5,
+    // Holding an off-chain channel state until its challenge window closes
+    Disputed = 6,
+}
+
+impl CellState {
+    pub const ALL: [CellState; 7] = [
+        CellState::Open,
+        CellState::AwaitingMoves,
+        CellState::AwaitingContinuation,
+        CellState::Completed,
+        CellState::Cancelled,
+        CellState::TimedOut,
+        CellState::Disputed,
+    ];
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.get(value as usize).copied()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CellState::Open => "open",
+            CellState::AwaitingMoves => "awaiting moves",
+            CellState::AwaitingContinuation => "awaiting continuation decisions",
+            CellState::Completed => "complete",
+            CellState::Cancelled => "cancelled",
+            CellState::TimedOut => "timed out",
+            CellState::Disputed => "disputed",
+        }
+    }
+
+    pub fn is_terminal(self) -> bool {
+        matches!(self, CellState::Completed | CellState::Cancelled | CellState::TimedOut)
+    }
+
+    // The transition table
+    pub fn can_become(self, next: CellState) -> bool {
+        use CellState::*;
+        matches!(
+            (self, next),
+            (Open, AwaitingMoves | Cancelled)
+                | (AwaitingMoves, AwaitingContinuation | Completed | TimedOut | Disputed)
+                | (AwaitingContinuation, AwaitingMoves | Completed | Disputed)
+                | (Disputed, AwaitingMoves | AwaitingContinuation | Completed)
+        )
+    }
+
+    // The state a cell stored before states were recorded is in, from the
+    // fields that used to imply it
+    pub fn infer(cell: &Cell) -> Self {
+        let round_finished = (cell.current_round as usize)
+            .checked_sub(1)
+            .and_then(|idx| cell.rounds.get(idx))
+            .is_some_and(|round| round.is_finished);
+        match (cell.is_complete, cell.player2.is_zero()) {
+            (true, true) => CellState::Cancelled,
+            (true, false) => CellState::Completed,
+            (false, true) => CellState::Open,
+            (false, false) if round_finished => CellState::AwaitingContinuation,
+            (false, false) => CellState::AwaitingMoves,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Spectator side-markets on two-player cells
+//
+// Spectators back outcome buckets while a cell waits for its second player.
+// The buckets form two independent parimutuel markets: whether every round
+// ended in mutual cooperation, and which player is sent more over the game. When
+// the cell completes, each market's whole pool is shared between the stakes on
+// its winning bucket; a market with no winning stakes, or a cell that finished
+// no rounds, refunds its bettors instead.
+use alloy_primitives::U256;
+
+use crate::{settlement, Cell, Move};
+
+pub const WAGER_BUCKETS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bucket {
+    MutualCooperation = 0,
+    AnyDefection = 1,
+    Player1Ahead = 2,
+    Player2Ahead = 3,
+    Even = 4,
+}
+
+impl Bucket {
+    pub const ALL: [Bucket; WAGER_BUCKETS] =
+        [Bucket::MutualCooperation, Bucket::AnyDefection, Bucket::Player1Ahead, Bucket::Player2Ahead, Bucket::Even];
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.get(value as usize).copied()
+    }
+
+    // Buckets in the same market share a pool
+    pub fn market(self) -> Market {
+        match self {
+            Bucket::MutualCooperation | Bucket::AnyDefection => Market::Cooperation,
+            Bucket::Player1Ahead | Bucket::Player2Ahead | Bucket::Even => Market::Winner,
+        }
+    }
+
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Market {
+    Cooperation,
+    Winner,
+}
+
+// Bitmask of the winning buckets of a completed cell, by `Bucket::mask`.
+// Zero when no round was finished, which voids both markets.
+pub fn winning_buckets(cell: &Cell) -> u8 {
+    let finished = || cell.rounds.iter().filter(|round| round.is_finished);
+    if finished().next().is_none() {
+        return 0;
+    }
+
+    let mutual = finished()
+        .all(|round| round.player1_move == Some(Move::Cooperate) && round.player2_move == Some(Move::Cooperate));
+    let cooperation = if mutual { Bucket::MutualCooperation } else { Bucket::AnyDefection };
+
+    // Ahead means sent more, refunds of unplayed rounds included
+    let (total_p1, total_p2) = settlement(cell);
+    let winner = match total_p1.cmp(&total_p2) {
+        core::cmp::Ordering::Greater => Bucket::Player1Ahead,
+        core::cmp::Ordering::Less => Bucket::Player2Ahead,
+        core::cmp::Ordering::Equal => Bucket::Even,
+    };
+
+    cooperation.mask() | winner.mask()
+}
+
+// What a bettor receives from one market, given their stakes and the pool
+// totals per bucket (both indexed by `Bucket`) and the winning buckets
+pub fn market_payout(market: Market, stakes: &[U256; WAGER_BUCKETS], pools: &[U256; WAGER_BUCKETS], winners: u8) -> U256 {
+    let in_market = || Bucket::ALL.into_iter().filter(move |bucket| bucket.market() == market);
+    let market_pool: U256 = in_market().map(|bucket| pools[bucket as usize]).sum();
+    let own_stake: U256 = in_market().map(|bucket| stakes[bucket as usize]).sum();
+
+    let winner = in_market().find(|bucket| winners & bucket.mask() != 0);
+    match winner {
+        Some(bucket) if pools[bucket as usize] > U256::ZERO => {
+            stakes[bucket as usize] * market_pool / pools[bucket as usize]
+        }
+        // Void market: everyone gets their stake back
+        _ => own_stake,
+    }
+}
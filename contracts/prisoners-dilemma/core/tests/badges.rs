@@ -0,0 +1,69 @@
+mod common;
+
+use alloy_primitives::{Address, U256};
+use common::{ctx, started_cell, P1, P2};
+use prisoners_dilemma_core::{apply, Action, Badge, Effect, Move, MUTUAL_STREAK};
+
+// Plays the given rounds, voting to continue between them, and returns every badge earned
+fn play(total_rounds: u8, rounds: &[(Move, Move)]) -> Vec<(Address, Badge)> {
+    let mut cell = started_cell(total_rounds);
+    let mut earned = Vec::new();
+    for (idx, &(p1_move, p2_move)) in rounds.iter().enumerate() {
+        if idx > 0 {
+            for player in [P1, P2] {
+                let action = Action::ContinuationDecision { player, wants_continue: true };
+                cell = apply(cell, action, &ctx()).unwrap().0;
+            }
+        }
+        for (player, player_move) in [(P1, p1_move), (P2, p2_move)] {
+            let (next, effects) = apply(cell, Action::SubmitMove { player, player_move }, &ctx()).unwrap();
+            cell = next;
+            earned.extend(effects.into_iter().filter_map(|effect| match effect {
+                Effect::BadgeEarned { player, badge } => Some((player, badge)),
+                _ => None,
+            }));
+        }
+    }
+    earned
+}
+
+const CC: (Move, Move) = (Move::Cooperate, Move::Cooperate);
+const CD: (Move, Move) = (Move::Cooperate, Move::Defect);
+const DD: (Move, Move) = (Move::Defect, Move::Defect);
+
+#[test]
+fn completing_a_cell_earns_first_game_and_unbroken_for_cooperators() {
+    let earned = play(2, &[CD, CD]);
+    assert_eq!(
+        earned,
+        vec![(P1, Badge::Forgiver), (P1, Badge::FirstGame), (P2, Badge::FirstGame), (P1, Badge::Unbroken)]
+    );
+}
+
+#[test]
+fn mutual_streak_is_earned_once_the_streak_reaches_its_length() {
+    let rounds = vec![CC; MUTUAL_STREAK];
+    let streaks = |rounds: &[(Move, Move)]| {
+        play(rounds.len() as u8, rounds).into_iter().filter(|&(_, badge)| badge == Badge::MutualStreak).count()
+    };
+
+    assert_eq!(streaks(&rounds), 2);
+    assert_eq!(streaks(&rounds[1..]), 0);
+    let broken: Vec<_> = rounds.iter().copied().chain([DD]).chain(rounds.iter().copied()).collect();
+    assert_eq!(streaks(&broken), 4);
+}
+
+#[test]
+fn defecting_back_is_not_forgiving() {
+    let earned = play(3, &[CD, DD, CC]);
+    assert!(!earned.contains(&(P1, Badge::Forgiver)));
+    assert!(!earned.iter().any(|&(_, badge)| badge == Badge::Unbroken));
+}
+
+#[test]
+fn token_uri_is_json_metadata() {
+    let uri = Badge::Forgiver.token_uri(U256::from(7));
+    let json = uri.strip_prefix("data:application/json;utf8,").unwrap();
+    assert!(json.starts_with("{\"name\":\"Forgiver #7\""));
+    assert!(json.ends_with("\"value\":\"Forgiver\"}]}"));
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use alloy_primitives::U256;
+use common::{ctx, started_cell, P1, P2};
+use prisoners_dilemma_core::{
+    apply, Action, Cell, CellState, ChannelState, Effect, Error, Move, PayoffMatrix,
+};
+
+const C: Move = Move::Cooperate;
+const D: Move = Move::Defect;
+
+// A state for the three-round cells below, whose rounds are played at a third of the stake
+fn channel(rounds: &[(Move, Move)]) -> ChannelState {
+    ChannelState::new(U256::from(1), U256::from(33), rounds.to_vec(), &PayoffMatrix::CONTRACT)
+}
+
+fn submit(cell: Cell, rounds: &[(Move, Move)]) -> Result<(Cell, Vec<Effect>), Error> {
+    apply(cell, Action::SubmitChannelState { state: channel(rounds) }, &ctx())
+}
+
+#[test]
+fn states_round_trip_through_their_encoding() {
+    let state = channel(&[(C, C), (C, D), (D, C), (D, D)]);
+    assert_eq!(ChannelState::decode(&state.encode()), Some(state.clone()));
+
+    let encoded = state.encode();
+    assert_eq!(ChannelState::decode(&encoded[..encoded.len() - 1]), None);
+    let mut bad_move = encoded;
+    bad_move[33] = 4;
+    assert_eq!(ChannelState::decode(&bad_move), None);
+}
+
+#[test]
+fn a_full_channel_state_completes_the_cell_once_finalized() {
+    let (cell, _) = submit(started_cell(3), &[(C, C), (C, D), (D, D)]).unwrap();
+    assert_eq!((cell.state, cell.current_round), (CellState::Disputed, 3));
+
+    let (cell, effects) = apply(cell, Action::FinalizeChannel, &ctx()).unwrap();
+    assert!(cell.is_complete);
+    // The rounds pay 65 and 98, and the 1 of each stake no round used is refunded
+    assert!(effects.contains(&Effect::Payout { player: P1, amount: U256::from(66) }));
+    assert!(effects.contains(&Effect::Payout { player: P2, amount: U256::from(99) }));
+}
+
+#[test]
+fn a_newer_state_overrides_one_being_challenged() {
+    let (cell, _) = submit(started_cell(3), &[(C, C)]).unwrap();
+    assert_eq!(submit(cell.clone(), &[(C, C)]).unwrap_err(), Error::StaleChannelState);
+
+    let (cell, _) = submit(cell, &[(C, C), (D, C)]).unwrap();
+    assert_eq!(cell.rounds.len(), 2);
+    assert_eq!(cell.state, CellState::Disputed);
+}
+
+#[test]
+fn a_partial_state_falls_back_to_on_chain_play() {
+    let cell = started_cell(3);
+    let cell = apply(cell, Action::SubmitMove { player: P1, player_move: C }, &ctx()).unwrap().0;
+    let (cell, _) = submit(cell, &[(D, C)]).unwrap();
+
+    let moving = Action::SubmitMove { player: P2, player_move: C };
+    assert_eq!(apply(cell.clone(), moving.clone(), &ctx()).unwrap_err(), Error::ChannelDisputed);
+
+    // The usual continuation vote decides whether play resumes on-chain
+    let (mut cell, _) = apply(cell, Action::FinalizeChannel, &ctx()).unwrap();
+    assert_eq!((cell.state, cell.current_round), (CellState::AwaitingContinuation, 1));
+    assert_eq!(apply(cell.clone(), moving.clone(), &ctx()).unwrap_err(), Error::RoundAlreadyFinished);
+    for player in [P1, P2] {
+        cell = apply(cell, Action::ContinuationDecision { player, wants_continue: true }, &ctx()).unwrap().0;
+    }
+    assert_eq!((cell.state, cell.current_round), (CellState::AwaitingMoves, 2));
+    apply(cell, moving, &ctx()).unwrap();
+}
+
+#[test]
+fn a_partial_state_ends_the_cell_if_a_player_stops() {
+    let (cell, _) = submit(started_cell(3), &[(C, C)]).unwrap();
+    let (cell, _) = apply(cell, Action::FinalizeChannel, &ctx()).unwrap();
+    let (cell, _) = apply(cell, Action::ContinuationDecision { player: P2, wants_continue: false }, &ctx()).unwrap();
+    let (cell, effects) = apply(cell, Action::ContinuationDecision { player: P1, wants_continue: true }, &ctx()).unwrap();
+    assert_eq!(cell.state, CellState::Completed);
+    assert!(effects.contains(&Effect::StateChanged { from: CellState::AwaitingContinuation, to: CellState::Completed }));
+}
+
+#[test]
+fn states_must_agree_with_the_matrix_and_the_chain() {
+    let mut inflated = channel(&[(C, C)]);
+    inflated.player1_total += U256::from(1);
+    let action = Action::SubmitChannelState { state: inflated };
+    assert_eq!(apply(started_cell(3), action, &ctx()).unwrap_err(), Error::InvalidChannelState);
+
+    let mut cell = started_cell(3);
+    for player in [P1, P2] {
+        cell = apply(cell, Action::SubmitMove { player, player_move: D }, &ctx()).unwrap().0;
+    }
+    assert_eq!(submit(cell, &[(C, C), (C, C)]).unwrap_err(), Error::InvalidChannelState);
+}
@@ -0,0 +1,336 @@
+use alloy_primitives::{Address, B256, I256, U256};
+use prisoners_dilemma_core::{
+    deserialize_cell, deserialize_group_cell, serialize_cell, serialize_group_cell, Cell, CellState, ContinuationPolicy, GroupCell,
+    GroupRound, Move, Round, CELL_HEADER_LEN, MAX_ENCODED_ROUNDS,
+};
+use proptest::prelude::*;
+
+fn arb_address() -> impl Strategy<Value = Address> {
+    any::<[u8; 20]>().prop_map(Address::from)
+}
+
+fn arb_u256() -> impl Strategy<Value = U256> {
+    any::<[u8; 32]>().prop_map(U256::from_be_bytes)
+}
+
+fn arb_move() -> impl Strategy<Value = Option<Move>> {
+    prop_oneof![Just(None), Just(Some(Move::Cooperate)), Just(Some(Move::Defect))]
+}
+
+fn arb_round() -> impl Strategy<Value = Round> {
+    (
+        (arb_move(), arb_move(), arb_u256(), arb_u256()),
+        (any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>()),
+        prop_oneof![Just(U256::ZERO), arb_u256()],
+    )
+        .prop_map(
+            |((player1_move, player2_move, player1_payout, player2_payout), (is_finished, player1_flipped, player2_flipped, sealed), stake)| {
+                Round {
+                    player1_move,
+                    player2_move,
+                    player1_payout,
+                    player2_payout,
+                    is_finished,
+                    player1_flipped,
+                    player2_flipped,
+                    sealed,
+                    stake,
+                }
+            },
+        )
+}
+
+fn arb_policy() -> impl Strategy<Value = ContinuationPolicy> {
+    prop_oneof![
+        Just(ContinuationPolicy::Manual),
+        Just(ContinuationPolicy::Always),
+        Just(ContinuationPolicy::StopWhenDefected),
+        any::<u8>().prop_map(ContinuationPolicy::StopAfterRounds),
+        arb_u256().prop_map(|raw| ContinuationPolicy::StopBelowNet(I256::from_raw(raw))),
+    ]
+}
+
+fn arb_cell(max_rounds: usize) -> impl Strategy<Value = Cell> {
+    (
+        arb_address(),
+        arb_address(),
+        arb_u256(),
+        any::<u8>(),
+        any::<u8>(),
+        any::<bool>(),
+        prop::collection::vec(arb_round(), 0..=max_rounds),
+        any::<u8>(),
+        prop_oneof![Just(0u16), any::<u16>()],
+        prop_oneof![Just(0u16), any::<u16>()],
+        prop::collection::vec(any::<u16>(), 0..=max_rounds),
+        (
+            (arb_policy(), arb_policy(), (0..CellState::ALL.len()).prop_map(|idx| CellState::ALL[idx])),
+            (
+                prop_oneof![Just(B256::ZERO), any::<[u8; 32]>().prop_map(B256::from)],
+                prop_oneof![Just(B256::ZERO), any::<[u8; 32]>().prop_map(B256::from)],
+                prop_oneof![Just(B256::ZERO), any::<[u8; 32]>().prop_map(B256::from)],
+                prop::collection::vec((arb_move(), arb_move()), 0..=max_rounds),
+                any::<bool>(),
+            ),
+            (prop_oneof![Just(U256::ZERO), arb_u256()], any::<u8>(), prop_oneof![Just(Address::ZERO), arb_address()]),
+        ),
+    )
+        .prop_map(
+            |(
+                player1,
+                player2,
+                stake_amount,
+                total_rounds,
+                current_round,
+                is_complete,
+                rounds,
+                continuation_flags,
+                noise_bps,
+                continuation_bps,
+                continuation_draws,
+                ((player1_policy, player2_policy, state), (player1_root, player2_root, seal, sealed_moves, streamed), (raise_stake, raise_flags, timeout_winner)),
+            )| {
+                Cell {
+                    player1,
+                    player2,
+                    stake_amount,
+                    total_rounds,
+                    current_round,
+                    is_complete,
+                    rounds,
+                    continuation_flags,
+                    noise_bps,
+                    continuation_bps,
+                    continuation_draws,
+                    player1_policy,
+                    player2_policy,
+                    state,
+                    player1_root,
+                    player2_root,
+                    seal,
+                    sealed_moves,
+                    streamed,
+                    raise_stake,
+                    raise_flags,
+                    timeout_winner,
+                }
+            },
+        )
+}
+
+// What a cell looks like after a round trip: unfinished payouts are dropped
+// and only the first 255 rounds are kept
+fn canonical(mut cell: Cell) -> Cell {
+    cell.rounds.truncate(MAX_ENCODED_ROUNDS);
+    cell.sealed_moves.truncate(MAX_ENCODED_ROUNDS);
+    for round in cell.rounds.iter_mut().filter(|round| !round.is_finished) {
+        round.player1_payout = U256::ZERO;
+        round.player2_payout = U256::ZERO;
+    }
+    cell
+}
+
+fn arb_group_round(players: usize) -> impl Strategy<Value = GroupRound> {
+    (prop::collection::vec(arb_move(), players), prop::collection::vec(arb_u256(), players), any::<bool>()).prop_map(
+        |(moves, payouts, is_finished)| GroupRound {
+            moves,
+            payouts: if is_finished { payouts } else { vec![U256::ZERO; payouts.len()] },
+            is_finished,
+        },
+    )
+}
+
+// Group cells whose rounds hold one move and payout per player, as `apply_group` keeps them
+fn arb_group_cell() -> impl Strategy<Value = GroupCell> {
+    prop::collection::vec(arb_address(), 0..=10).prop_flat_map(|players| {
+        let n = players.len();
+        (
+            Just(players),
+            any::<u8>(),
+            arb_u256(),
+            any::<u32>(),
+            any::<u8>(),
+            any::<u8>(),
+            any::<bool>(),
+            prop::collection::vec(arb_group_round(n), 0..=8),
+            any::<u16>(),
+        )
+            .prop_map(
+                |(players, max_players, stake_amount, multiplier_bps, total_rounds, current_round, is_complete, rounds, continue_votes)| {
+                    GroupCell {
+                        players,
+                        max_players,
+                        stake_amount,
+                        multiplier_bps,
+                        total_rounds,
+                        current_round,
+                        is_complete,
+                        rounds,
+                        continue_votes,
+                    }
+                },
+            )
+    })
+}
+
+proptest! {
+    #[test]
+    fn round_trips_canonical_cells(cell in arb_cell(32).prop_map(canonical)) {
+        prop_assert_eq!(deserialize_cell(&serialize_cell(&cell)), cell);
+    }
+
+    #[test]
+    fn round_trip_drops_unfinished_payouts(cell in arb_cell(32)) {
+        let decoded = deserialize_cell(&serialize_cell(&cell));
+        prop_assert_eq!(decoded, canonical(cell));
+    }
+
+    #[test]
+    fn decoding_arbitrary_bytes_is_stable(data in prop::collection::vec(any::<u8>(), 0..512)) {
+        let cell = deserialize_cell(&data);
+        prop_assert_eq!(deserialize_cell(&serialize_cell(&cell)), cell);
+    }
+
+    #[test]
+    fn every_prefix_decodes_the_complete_rounds(cell in arb_cell(8).prop_map(canonical)) {
+        let data = serialize_cell(&cell);
+
+        for len in 0..=data.len() {
+            let decoded = deserialize_cell(&data[..len]);
+            if len < CELL_HEADER_LEN {
+                prop_assert_eq!(&decoded, &Cell::default());
+                continue;
+            }
+
+            // Rounds are kept only if every one of their bytes is present
+            let mut end = CELL_HEADER_LEN;
+            let complete = cell
+                .rounds
+                .iter()
+                .take_while(|round| {
+                    end += if round.is_finished { 65 } else { 1 };
+                    end <= len
+                })
+                .count();
+            // Round stakes are in the trailer, so they are only compared once it is all present
+            let without_stakes = |rounds: &[Round]| {
+                rounds.iter().map(|round| Round { stake: U256::ZERO, ..round.clone() }).collect::<Vec<_>>()
+            };
+            prop_assert_eq!(without_stakes(&decoded.rounds), without_stakes(&cell.rounds[..complete]));
+
+            // The flags byte follows the rounds, and the trailer is only read in full
+            let flags_pos = CELL_HEADER_LEN
+                + cell.rounds.iter().map(|round| if round.is_finished { 65 } else { 1 }).sum::<usize>();
+            let flags = if len > flags_pos { cell.continuation_flags } else { 0 };
+            prop_assert_eq!(decoded.continuation_flags, flags);
+            // Each trailer record decodes in full or not at all
+            if len == data.len() {
+                prop_assert_eq!(decoded.noise_bps, cell.noise_bps);
+                prop_assert_eq!(decoded.continuation_bps, cell.continuation_bps);
+                prop_assert_eq!(&decoded.continuation_draws, &cell.continuation_draws);
+                prop_assert_eq!(&decoded.rounds, &cell.rounds);
+            }
+            prop_assert!(decoded.noise_bps == 0 || decoded.noise_bps == cell.noise_bps);
+            prop_assert!(decoded.continuation_bps == 0 || decoded.continuation_bps == cell.continuation_bps);
+            prop_assert!(decoded.continuation_draws.is_empty() || decoded.continuation_draws == cell.continuation_draws);
+            prop_assert_eq!(decoded.player1, cell.player1);
+            prop_assert_eq!(decoded.stake_amount, cell.stake_amount);
+        }
+    }
+
+    #[test]
+    fn round_trips_group_cells(cell in arb_group_cell()) {
+        prop_assert_eq!(deserialize_group_cell(&serialize_group_cell(&cell)), cell);
+    }
+
+    #[test]
+    fn decoding_arbitrary_bytes_as_group_cell_is_stable(data in prop::collection::vec(any::<u8>(), 0..512)) {
+        let cell = deserialize_group_cell(&data);
+        prop_assert_eq!(deserialize_group_cell(&serialize_group_cell(&cell)), cell);
+    }
+
+    #[test]
+    fn group_cell_prefixes_never_gain_rounds(cell in arb_group_cell()) {
+        let data = serialize_group_cell(&cell);
+        for len in 0..=data.len() {
+            let decoded = deserialize_group_cell(&data[..len]);
+            prop_assert!(cell.rounds.starts_with(&decoded.rounds));
+            prop_assert!(cell.players.starts_with(&decoded.players));
+        }
+    }
+}
+
+#[test]
+fn rounds_beyond_the_count_byte_are_not_written() {
+    let round = Round { player1_move: Some(Move::Defect), is_finished: false, ..Round::default() };
+    let cell = Cell { rounds: vec![round; MAX_ENCODED_ROUNDS + 10], ..Cell::default() };
+
+    let data = serialize_cell(&cell);
+    assert_eq!(data[75] as usize, MAX_ENCODED_ROUNDS);
+    assert_eq!(data.len(), CELL_HEADER_LEN + MAX_ENCODED_ROUNDS + 1);
+    assert_eq!(deserialize_cell(&data), canonical(cell));
+}
+
+#[test]
+fn empty_storage_decodes_as_default_cell() {
+    assert_eq!(deserialize_cell(&[]), Cell::default());
+    assert_eq!(deserialize_cell(&[0xff; CELL_HEADER_LEN - 1]), Cell::default());
+}
+
+#[test]
+fn finished_round_missing_payouts_is_dropped() {
+    let round = Round {
+        player1_move: Some(Move::Cooperate),
+        player2_move: Some(Move::Cooperate),
+        player1_payout: U256::from(100),
+        player2_payout: U256::from(100),
+        is_finished: true,
+        ..Round::default()
+    };
+    let cell = Cell { rounds: vec![round.clone(), round], continuation_flags: 5, ..Cell::default() };
+    let data = serialize_cell(&cell);
+
+    // Cut the second round's payouts short
+    let decoded = deserialize_cell(&data[..CELL_HEADER_LEN + 65 + 40]);
+    assert_eq!(decoded.rounds, cell.rounds[..1]);
+    assert_eq!(decoded.continuation_flags, 0);
+}
+
+#[test]
+fn cells_without_settings_have_no_trailer() {
+    let cell = Cell { rounds: vec![Round::default()], continuation_flags: 3, ..Cell::default() };
+    assert_eq!(serialize_cell(&cell).len(), CELL_HEADER_LEN + 2);
+
+    let noisy = Cell { noise_bps: 500, ..cell };
+    let data = serialize_cell(&noisy);
+    assert_eq!(data.len(), CELL_HEADER_LEN + 5);
+    assert_eq!(deserialize_cell(&data), noisy);
+}
+
+#[test]
+fn cells_without_a_state_record_infer_it() {
+    let finished = Round { is_finished: true, ..Round::default() };
+    let cell = Cell {
+        player2: Address::repeat_byte(2),
+        current_round: 1,
+        rounds: vec![finished],
+        state: CellState::AwaitingContinuation,
+        ..Cell::default()
+    };
+    let data = serialize_cell(&cell);
+    assert_eq!(data.len(), CELL_HEADER_LEN + 65 + 1);
+    assert_eq!(deserialize_cell(&data).state, CellState::AwaitingContinuation);
+
+    let disputed = Cell { state: CellState::Disputed, ..cell };
+    assert_eq!(deserialize_cell(&serialize_cell(&disputed)).state, CellState::Disputed);
+}
+
+#[test]
+fn invalid_move_bits_decode_as_no_move() {
+    let mut data = serialize_cell(&Cell { rounds: vec![Round::default()], ..Cell::default() });
+    data[CELL_HEADER_LEN] = 0x0f;
+
+    let decoded = deserialize_cell(&data);
+    assert_eq!(decoded.rounds[0].player1_move, None);
+    assert_eq!(decoded.rounds[0].player2_move, None);
+}
@@ -0,0 +1,123 @@
+// Commit-reveal moves: noise drawn from the second reveal's entropy, and the
+// timeout that ends a round one player withholds their commitment or move in
+mod common;
+
+use alloy_primitives::{Address, B256, U256};
+use common::{ctx, entropy, research_cell, reveal_round, started_cell, vote, P1, P2};
+use prisoners_dilemma_core::{apply, settlement, Action, Cell, CellState, Effect, Error, Move};
+
+const C: Move = Move::Cooperate;
+const D: Move = Move::Defect;
+
+fn reveal(cell: Cell, player: Address, player_move: Move, entropy: Option<B256>) -> Result<(Cell, Vec<Effect>), Error> {
+    apply(cell, Action::RevealMove { player, player_move, entropy }, &ctx())
+}
+
+#[test]
+fn the_first_reveal_only_records_the_move() {
+    let (cell, effects) = reveal(research_cell(1, 5000, 0), P1, D, None).unwrap();
+    assert!(effects.is_empty());
+    assert_eq!(cell.rounds[0].player1_move, Some(D));
+    assert!(!cell.rounds[0].is_finished);
+
+    let err = reveal(cell, P1, C, None).unwrap_err();
+    assert_eq!(err, Error::RoundAlreadyFinished);
+}
+
+#[test]
+fn noise_flips_each_move_whose_draw_falls_below_it() {
+    let (cell, _) = reveal_round(research_cell(1, 5000, 0), C, C, entropy(4999, 5000, 0));
+    let round = &cell.rounds[0];
+    assert!(round.player1_flipped && !round.player2_flipped);
+    assert_eq!((round.player1_move, round.player2_move), (Some(D), Some(C)));
+    assert_eq!(round.intended_moves(), (Some(C), Some(C)));
+    // Payouts follow the executed moves
+    assert_eq!((round.player1_payout, round.player2_payout), (U256::from(150), U256::from(50)));
+}
+
+#[test]
+fn draws_are_the_entropy_words_modulo_ten_thousand() {
+    // 0xffffffff % 10000 = 7295
+    let (cell, _) = reveal_round(research_cell(1, 7296, 0), C, D, entropy(u32::MAX, u32::MAX, 0));
+    assert!(cell.rounds[0].player1_flipped && cell.rounds[0].player2_flipped);
+    assert_eq!((cell.rounds[0].player1_move, cell.rounds[0].player2_move), (Some(D), Some(C)));
+
+    let (cell, _) = reveal_round(research_cell(1, 7295, 0), C, D, entropy(u32::MAX, u32::MAX, 0));
+    assert!(!cell.rounds[0].player1_flipped && !cell.rounds[0].player2_flipped);
+}
+
+#[test]
+fn cells_without_noise_never_flip() {
+    let (cell, _) = reveal_round(research_cell(2, 0, 10000), C, D, entropy(0, 0, 0));
+    assert!(!cell.rounds[0].player1_flipped && !cell.rounds[0].player2_flipped);
+    assert_eq!(cell.rounds[0].intended_moves(), (Some(C), Some(D)));
+}
+
+#[test]
+fn cells_without_noise_or_continuation_take_moves_directly() {
+    let err = apply(started_cell(3), Action::CommitMove { player: P1 }, &ctx()).unwrap_err();
+    assert_eq!(err, Error::NotCommitReveal);
+    let err = reveal(started_cell(3), P1, C, None).unwrap_err();
+    assert_eq!(err, Error::NotCommitReveal);
+}
+
+#[test]
+fn a_withheld_reveal_forfeits_the_pot_to_the_waiting_player() {
+    let cell = reveal(research_cell(3, 5000, 0), P2, D, None).unwrap().0;
+    let err = apply(cell.clone(), Action::ClaimRevealTimeout { player: P1 }, &ctx()).unwrap_err();
+    assert_eq!(err, Error::NoRevealPending);
+
+    let (cell, effects) = apply(cell, Action::ClaimRevealTimeout { player: P2 }, &ctx()).unwrap();
+    assert_eq!(cell.state, CellState::TimedOut);
+    assert!(cell.is_complete);
+    assert_eq!(
+        effects,
+        [
+            Effect::StateChanged { from: CellState::AwaitingMoves, to: CellState::TimedOut },
+            Effect::Payout { player: P2, amount: U256::from(200) },
+            Effect::CellComplete,
+        ]
+    );
+    assert_eq!(settlement(&cell), (U256::ZERO, U256::from(200)));
+
+    let err = apply(cell, Action::ClaimRevealTimeout { player: P2 }, &ctx()).unwrap_err();
+    assert_eq!(err, Error::CellIsComplete);
+}
+
+#[test]
+fn a_withheld_commitment_forfeits_the_pot_before_anyone_reveals() {
+    // Commitments live with the caller, so the round has no moves yet
+    let (cell, effects) = apply(research_cell(3, 5000, 0), Action::ClaimRevealTimeout { player: P1 }, &ctx()).unwrap();
+    assert_eq!(cell.state, CellState::TimedOut);
+    assert_eq!(cell.timeout_winner, P1);
+    assert!(effects.contains(&Effect::Payout { player: P1, amount: U256::from(200) }));
+    assert_eq!(settlement(&cell), (U256::from(200), U256::ZERO));
+
+    let err = apply(research_cell(3, 5000, 0), Action::ClaimRevealTimeout { player: Address::ZERO }, &ctx()).unwrap_err();
+    assert_eq!(err, Error::NotInCell);
+}
+
+#[test]
+fn a_finished_round_has_no_reveal_to_time_out() {
+    let (cell, _) = reveal_round(research_cell(3, 5000, 0), C, C, entropy(9999, 9999, 0));
+    assert_eq!(cell.state, CellState::AwaitingContinuation);
+    let err = apply(cell, Action::ClaimRevealTimeout { player: P1 }, &ctx()).unwrap_err();
+    assert_eq!(err, Error::NoRevealPending);
+}
+
+#[test]
+fn a_timed_out_streamed_cell_keeps_what_it_credited() {
+    let create = Action::CreateStreamed { player: P1, stake: U256::from(100), total_rounds: 2, noise_bps: 1, continuation_bps: 0 };
+    let cell = apply(Cell::default(), create, &ctx()).unwrap().0;
+    let cell = apply(cell, Action::Join { player: P2, stake: U256::from(100) }, &ctx()).unwrap().0;
+    // Round 1 is played at half the stake and credits 25 each, leaving 150 in the pot
+    let (cell, _) = reveal_round(cell, D, D, entropy(9999, 9999, 0));
+    let (cell, _) = vote(cell, P1, true);
+    let (cell, _) = vote(cell, P2, true);
+
+    let cell = reveal(cell, P1, C, None).unwrap().0;
+    let (cell, effects) = apply(cell, Action::ClaimRevealTimeout { player: P1 }, &ctx()).unwrap();
+    assert!(effects.contains(&Effect::Payout { player: P1, amount: U256::from(150) }));
+    assert!(!effects.iter().any(|effect| matches!(effect, Effect::Payout { player: P2, .. })));
+    assert_eq!(settlement(&cell), (U256::from(175), U256::from(25)));
+}
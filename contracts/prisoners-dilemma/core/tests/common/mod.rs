@@ -0,0 +1,56 @@
+// Players, context and cell fixtures shared by the core tests
+#![allow(dead_code)]
+
+use alloy_primitives::{Address, B256, U256};
+use prisoners_dilemma_core::{apply, Action, Cell, Context, Effect, Move, PayoffMatrix};
+
+pub const P1: Address = Address::repeat_byte(1);
+pub const P2: Address = Address::repeat_byte(2);
+
+pub fn ctx() -> Context {
+    Context { min_stake: U256::from(1), payoffs: PayoffMatrix::CONTRACT }
+}
+
+// A voted cell of stake 100 waiting on its second player
+pub fn open_cell(total_rounds: u8) -> Cell {
+    let create = Action::Create { player: P1, stake: U256::from(100), total_rounds, noise_bps: 0, continuation_bps: 0 };
+    apply(Cell::default(), create, &ctx()).unwrap().0
+}
+
+// The same cell once player 2 has matched the stake
+pub fn started_cell(total_rounds: u8) -> Cell {
+    apply(open_cell(total_rounds), Action::Join { player: P2, stake: U256::from(100) }, &ctx()).unwrap().0
+}
+
+// Both players' moves for the current round
+pub fn play(cell: Cell, p1_move: Move, p2_move: Move) -> (Cell, Vec<Effect>) {
+    let cell = apply(cell, Action::SubmitMove { player: P1, player_move: p1_move }, &ctx()).unwrap().0;
+    apply(cell, Action::SubmitMove { player: P2, player_move: p2_move }, &ctx()).unwrap()
+}
+
+pub fn vote(cell: Cell, player: Address, wants_continue: bool) -> (Cell, Vec<Effect>) {
+    apply(cell, Action::ContinuationDecision { player, wants_continue }, &ctx()).unwrap()
+}
+
+// A started research cell of stake 100
+pub fn research_cell(total_rounds: u8, noise_bps: u16, continuation_bps: u16) -> Cell {
+    let create = Action::Create { player: P1, stake: U256::from(100), total_rounds, noise_bps, continuation_bps };
+    let cell = apply(Cell::default(), create, &ctx()).unwrap().0;
+    apply(cell, Action::Join { player: P2, stake: U256::from(100) }, &ctx()).unwrap().0
+}
+
+// Entropy whose player 1 noise, player 2 noise and continuation draws read as the given words
+pub fn entropy(p1_noise: u32, p2_noise: u32, continuation: u32) -> B256 {
+    let mut bytes = [0u8; 32];
+    bytes[0..4].copy_from_slice(&p1_noise.to_be_bytes());
+    bytes[4..8].copy_from_slice(&p2_noise.to_be_bytes());
+    bytes[8..12].copy_from_slice(&continuation.to_be_bytes());
+    B256::from(bytes)
+}
+
+// Player 1 reveals first, then player 2 with the combined entropy
+pub fn reveal_round(cell: Cell, p1_move: Move, p2_move: Move, entropy: B256) -> (Cell, Vec<Effect>) {
+    let reveal = |cell, player, player_move, entropy| apply(cell, Action::RevealMove { player, player_move, entropy }, &ctx()).unwrap();
+    let cell = reveal(cell, P1, p1_move, None).0;
+    reveal(cell, P2, p2_move, Some(entropy))
+}
@@ -0,0 +1,50 @@
+// Shadow of the future: research cells continue with probability δ, drawn
+// from the entropy of each round's second reveal instead of voted on
+mod common;
+
+use alloy_primitives::U256;
+use common::{ctx, entropy, research_cell, reveal_round, P1, P2};
+use prisoners_dilemma_core::{apply, Action, CellState, ContinuationPolicy, Effect, Error, Move};
+
+const C: Move = Move::Cooperate;
+
+#[test]
+fn a_draw_below_delta_opens_the_next_round() {
+    let (cell, effects) = reveal_round(research_cell(3, 0, 5000), C, C, entropy(0, 0, 4999));
+    assert!(effects.contains(&Effect::ContinuationDrawn { round_num: 1, draw: 4999, continues: true }));
+    assert_eq!(cell.continuation_draws, [4999]);
+    assert_eq!(cell.current_round, 2);
+    assert_eq!(cell.state, CellState::AwaitingMoves);
+    assert!(!effects.contains(&Effect::CellComplete));
+}
+
+#[test]
+fn a_draw_at_or_above_delta_completes_the_cell() {
+    let (cell, effects) = reveal_round(research_cell(3, 0, 5000), C, C, entropy(0, 0, 5000));
+    assert!(effects.contains(&Effect::ContinuationDrawn { round_num: 1, draw: 5000, continues: false }));
+    assert_eq!(cell.continuation_draws, [5000]);
+    assert_eq!(cell.state, CellState::Completed);
+    assert!(effects.contains(&Effect::Payout { player: P1, amount: U256::from(100) }));
+    assert!(effects.contains(&Effect::Payout { player: P2, amount: U256::from(100) }));
+    assert_eq!(effects.last(), Some(&Effect::CellComplete));
+}
+
+#[test]
+fn draws_are_recorded_for_every_round_but_the_last() {
+    let (cell, _) = reveal_round(research_cell(3, 0, 10000), C, C, entropy(0, 0, 1));
+    let (cell, _) = reveal_round(cell, C, C, entropy(0, 0, 2));
+    let (cell, effects) = reveal_round(cell, C, C, entropy(0, 0, 3));
+    // The last round ends the cell without drawing
+    assert!(!effects.iter().any(|effect| matches!(effect, Effect::ContinuationDrawn { .. })));
+    assert_eq!(cell.continuation_draws, [1, 2]);
+    assert_eq!(cell.state, CellState::Completed);
+}
+
+#[test]
+fn votes_are_rejected_while_delta_decides() {
+    let (cell, _) = reveal_round(research_cell(3, 0, 10000), C, C, entropy(0, 0, 0));
+    let vote = Action::ContinuationDecision { player: P1, wants_continue: false };
+    assert_eq!(apply(cell.clone(), vote, &ctx()).unwrap_err(), Error::ContinuationIsRandom);
+    let policy = Action::SetContinuationPolicy { player: P2, policy: ContinuationPolicy::Always };
+    assert_eq!(apply(cell, policy, &ctx()).unwrap_err(), Error::ContinuationIsRandom);
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use alloy_primitives::{Address, U256};
+use common::ctx;
+use prisoners_dilemma_core::{apply_group, group_payoff, Effect, Error, GroupAction, GroupCell, Move};
+
+const C: Move = Move::Cooperate;
+const D: Move = Move::Defect;
+
+fn player(idx: u8) -> Address {
+    Address::repeat_byte(idx + 1)
+}
+
+fn create(max_players: u8, total_rounds: u8, multiplier_bps: u32) -> Result<GroupCell, Error> {
+    let action = GroupAction::Create { player: player(0), stake: U256::from(100), max_players, total_rounds, multiplier_bps };
+    apply_group(GroupCell::default(), action, &ctx()).map(|(cell, _)| cell)
+}
+
+fn join(cell: GroupCell, idx: u8) -> Result<(GroupCell, Vec<Effect>), Error> {
+    apply_group(cell, GroupAction::Join { player: player(idx), stake: U256::from(100) }, &ctx())
+}
+
+// A three-player cell with every seat taken
+fn full_cell(total_rounds: u8) -> GroupCell {
+    let cell = create(3, total_rounds, 20_000).unwrap();
+    let cell = join(cell, 1).unwrap().0;
+    join(cell, 2).unwrap().0
+}
+
+fn play(mut cell: GroupCell, moves: &[Move]) -> (GroupCell, Vec<Effect>) {
+    let mut effects = Vec::new();
+    for (idx, &player_move) in moves.iter().enumerate() {
+        let action = GroupAction::SubmitMove { player: player(idx as u8), player_move };
+        (cell, effects) = apply_group(cell, action, &ctx()).unwrap();
+    }
+    (cell, effects)
+}
+
+fn payouts(effects: &[Effect]) -> Vec<(Address, U256)> {
+    effects
+        .iter()
+        .filter_map(|effect| match *effect {
+            Effect::Payout { player, amount } => Some((player, amount)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn contributions_are_multiplied_and_shared_while_free_riders_keep_their_stake() {
+    // Two contributions of 100 doubled into 400, split three ways
+    let stake = U256::from(100);
+    assert_eq!(group_payoff(stake, 20_000, &[C, C, D]), vec![U256::from(133), U256::from(133), U256::from(233)]);
+    assert_eq!(group_payoff(stake, 20_000, &[D, D, D]), vec![stake; 3]);
+    assert_eq!(group_payoff(stake, 15_000, &[C, C, C, C]), vec![U256::from(150); 4]);
+}
+
+#[test]
+fn sizes_and_multipliers_are_checked_on_creation() {
+    assert_eq!(create(2, 1, 20_000).unwrap_err(), Error::InvalidGroupSize);
+    assert_eq!(create(11, 1, 20_000).unwrap_err(), Error::InvalidGroupSize);
+    // The multiplier lies strictly between 1x and max_players x
+    assert_eq!(create(3, 1, 10_000).unwrap_err(), Error::InvalidMultiplier);
+    assert_eq!(create(3, 1, 30_000).unwrap_err(), Error::InvalidMultiplier);
+    assert!(create(3, 1, 29_999).is_ok());
+}
+
+#[test]
+fn the_first_round_starts_once_every_seat_is_taken() {
+    let cell = create(3, 1, 20_000).unwrap();
+    let cell = join(cell, 1).unwrap().0;
+    assert_eq!(join(cell.clone(), 1).unwrap_err(), Error::AlreadyInCell);
+    let wrong_stake = GroupAction::Join { player: player(2), stake: U256::from(99) };
+    assert_eq!(apply_group(cell.clone(), wrong_stake, &ctx()).unwrap_err(), Error::WrongStake);
+    let early = GroupAction::SubmitMove { player: player(0), player_move: C };
+    assert_eq!(apply_group(cell.clone(), early, &ctx()).unwrap_err(), Error::WaitingForPlayers);
+
+    let cell = join(cell, 2).unwrap().0;
+    assert_eq!((cell.current_round, cell.rounds.len()), (1, 1));
+    assert_eq!(join(cell, 3).unwrap_err(), Error::CellFull);
+}
+
+// Plays `rounds` in turn, every player voting to continue between them
+fn play_rounds(mut cell: GroupCell, rounds: &[&[Move]]) -> (GroupCell, Vec<Effect>) {
+    let mut effects = Vec::new();
+    for (idx, moves) in rounds.iter().enumerate() {
+        if idx > 0 {
+            cell = (0..cell.players.len() as u8).fold(cell, |cell, idx| {
+                let vote = GroupAction::ContinuationDecision { player: player(idx), wants_continue: true };
+                apply_group(cell, vote, &ctx()).unwrap().0
+            });
+        }
+        (cell, effects) = play(cell, moves);
+    }
+    (cell, effects)
+}
+
+#[test]
+fn rounds_are_played_at_a_stake_the_pot_covers() {
+    let (cell, effects) = play_rounds(full_cell(2), &[&[C, C, D], &[C, D, D]]);
+    assert!(cell.is_complete);
+
+    // The 100 stakes fund two fully contributed rounds at 25 each. The rounds
+    // pay (33, 33, 58) and (16, 41, 41), well within the pot of 300
+    assert_eq!(cell.round_stake(), U256::from(25));
+    let paid = payouts(&effects);
+    assert_eq!(paid, vec![(player(0), U256::from(49)), (player(1), U256::from(74)), (player(2), U256::from(99))]);
+    assert!(paid.iter().fold(U256::ZERO, |sum, &(_, amount)| sum + amount) <= cell.pot());
+}
+
+#[test]
+fn contributing_and_free_riding_settle_differently_over_several_rounds() {
+    let settle = |moves: &[Move]| {
+        let (cell, effects) = play_rounds(full_cell(3), &[moves, moves, moves]);
+        assert!(cell.is_complete);
+        payouts(&effects).into_iter().map(|(_, amount)| amount).collect::<Vec<_>>()
+    };
+
+    // Rounds at 16 each: contributing pays 32 a round, free-riding returns 16
+    assert_eq!(settle(&[C, C, C]), vec![U256::from(96); 3]);
+    assert_eq!(settle(&[D, D, D]), vec![U256::from(48); 3]);
+    // A lone free-rider among contributors does better than any of them
+    assert_eq!(settle(&[C, C, D]), vec![U256::from(63), U256::from(63), U256::from(111)]);
+}
+
+#[test]
+fn an_exit_vote_ends_the_game_for_everyone() {
+    let (cell, _) = play(full_cell(3), &[D, D, D]);
+    let exit = GroupAction::ContinuationDecision { player: player(1), wants_continue: false };
+    let (cell, effects) = apply_group(cell, exit, &ctx()).unwrap();
+    assert!(cell.is_complete);
+    // The free-ridden round returns its stake of 16, and the two rounds never
+    // played refund two thirds of each stake
+    assert_eq!(payouts(&effects), (0..3).map(|idx| (player(idx), U256::from(82))).collect::<Vec<_>>());
+    assert_eq!(effects.last(), Some(&Effect::CellComplete));
+}
+
+#[test]
+fn the_creator_can_cancel_a_cell_that_never_fills() {
+    let cell = join(create(4, 1, 20_000).unwrap(), 1).unwrap().0;
+    let cancel = |cell, idx| apply_group(cell, GroupAction::Cancel { player: player(idx) }, &ctx());
+    assert_eq!(cancel(cell.clone(), 1).unwrap_err(), Error::NotInCell);
+    assert_eq!(cancel(GroupCell::default(), 0).unwrap_err(), Error::CellNotFound);
+
+    let (cancelled, effects) = cancel(cell.clone(), 0).unwrap();
+    assert!(cancelled.is_complete);
+    assert_eq!(payouts(&effects), vec![(player(0), U256::from(100)), (player(1), U256::from(100))]);
+    assert_eq!(join(cancelled, 2).unwrap_err(), Error::CellIsComplete);
+
+    // Once every seat is taken the game is on
+    let cell = join(join(cell, 2).unwrap().0, 3).unwrap().0;
+    assert_eq!(cancel(cell, 0).unwrap_err(), Error::CellFull);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use alloy_primitives::I256;
+use common::{ctx, P1, P2};
+use prisoners_dilemma_core::{apply, Action, Cell, ContinuationPolicy, Move};
+
+fn started_cell(total_rounds: u8, p1: ContinuationPolicy, p2: ContinuationPolicy) -> Cell {
+    let mut cell = common::started_cell(total_rounds);
+    for (player, policy) in [(P1, p1), (P2, p2)] {
+        cell = apply(cell, Action::SetContinuationPolicy { player, policy }, &ctx()).unwrap().0;
+    }
+    cell
+}
+
+fn play(cell: Cell, p1_move: Move, p2_move: Move) -> Cell {
+    common::play(cell, p1_move, p2_move).0
+}
+
+#[test]
+fn policies_open_the_next_round_without_votes() {
+    let mut cell = started_cell(3, ContinuationPolicy::Always, ContinuationPolicy::StopAfterRounds(2));
+    cell = play(cell, Move::Cooperate, Move::Cooperate);
+    assert_eq!((cell.current_round, cell.continuation_flags, cell.is_complete), (2, 0, false));
+
+    cell = play(cell, Move::Cooperate, Move::Cooperate);
+    assert!(cell.is_complete);
+}
+
+#[test]
+fn stop_when_defected_ends_the_cell_after_a_defection() {
+    let mut cell = started_cell(5, ContinuationPolicy::StopWhenDefected, ContinuationPolicy::Always);
+    cell = play(cell, Move::Defect, Move::Cooperate);
+    assert_eq!(cell.current_round, 2);
+
+    cell = play(cell, Move::Cooperate, Move::Defect);
+    assert!(cell.is_complete);
+    assert_eq!(cell.rounds.len(), 2);
+}
+
+#[test]
+fn stop_below_net_compares_payouts_with_stakes() {
+    // Each of the five rounds is played at 20, and a sucker's payoff loses
+    // half of that, so a floor of -10 survives one and not two
+    let floor = ContinuationPolicy::StopBelowNet(I256::try_from(-10).unwrap());
+    let mut cell = started_cell(5, floor, ContinuationPolicy::Always);
+    cell = play(cell, Move::Cooperate, Move::Defect);
+    assert_eq!(cell.current_round, 2);
+
+    cell = play(cell, Move::Cooperate, Move::Defect);
+    assert!(cell.is_complete);
+}
+
+#[test]
+fn an_explicit_decision_overrides_the_policy() {
+    let mut cell = started_cell(5, ContinuationPolicy::Always, ContinuationPolicy::Always);
+    let stop = Action::ContinuationDecision { player: P2, wants_continue: false };
+    cell = apply(cell, stop, &ctx()).unwrap().0;
+    assert_eq!(cell.current_round, 1);
+
+    cell = play(cell, Move::Cooperate, Move::Cooperate);
+    assert!(cell.is_complete);
+}
+
+#[test]
+fn manual_players_are_still_waited_for() {
+    let mut cell = started_cell(5, ContinuationPolicy::Always, ContinuationPolicy::Manual);
+    cell = play(cell, Move::Cooperate, Move::Cooperate);
+    assert_eq!(cell.continuation_status(), (true, true, false, false));
+
+    // Choosing a policy while the vote is pending casts it straight away
+    let policy = Action::SetContinuationPolicy { player: P2, policy: ContinuationPolicy::Always };
+    cell = apply(cell, policy, &ctx()).unwrap().0;
+    assert_eq!((cell.current_round, cell.continuation_flags), (2, 0));
+}
+
+#[test]
+fn moves_with_continuation_open_the_next_round_in_the_same_call() {
+    let mut cell = started_cell(2, ContinuationPolicy::Manual, ContinuationPolicy::Manual);
+    for player in [P1, P2] {
+        let action = Action::SubmitMoveWithContinuation { player, player_move: Move::Cooperate, wants_continue: true };
+        cell = apply(cell, action, &ctx()).unwrap().0;
+    }
+    assert_eq!((cell.current_round, cell.continuation_flags), (2, 0));
+
+    // The last round has no vote, so its decision is ignored
+    let action = Action::SubmitMoveWithContinuation { player: P1, player_move: Move::Cooperate, wants_continue: true };
+    cell = apply(cell, action, &ctx()).unwrap().0;
+    assert_eq!(cell.continuation_flags, 0);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use alloy_primitives::U256;
+use common::{ctx, play, started_cell, vote, P1, P2};
+use prisoners_dilemma_core::{apply, Action, Cell, CellState, Effect, Error, Move};
+
+// A voted cell of stake 100 whose first round was played and waits on votes
+fn continuing_cell(total_rounds: u8) -> Cell {
+    play(started_cell(total_rounds), Move::Cooperate, Move::Cooperate).0
+}
+
+#[test]
+fn a_raise_both_players_deposit_sets_the_next_rounds_stake() {
+    let cell = continuing_cell(3);
+    assert_eq!(cell.state, CellState::AwaitingContinuation);
+
+    let propose = Action::ProposeRaise { player: P1, stake: U256::from(300), deposit: U256::from(200) };
+    let cell = apply(cell, propose, &ctx()).unwrap().0;
+    let cell = apply(cell, Action::AcceptRaise { player: P2, deposit: U256::from(200) }, &ctx()).unwrap().0;
+    let (cell, _) = vote(cell, P1, true);
+    let (cell, _) = vote(cell, P2, true);
+    assert_eq!((cell.current_stake(), cell.raise_stake), (U256::from(300), U256::ZERO));
+
+    let cell = apply(cell, Action::SubmitMove { player: P1, player_move: Move::Cooperate }, &ctx()).unwrap().0;
+    let (cell, _) = apply(cell, Action::SubmitMove { player: P2, player_move: Move::Defect }, &ctx()).unwrap();
+    // The round is played at its third of the raised stake
+    assert_eq!((cell.rounds[1].player1_payout, cell.rounds[1].player2_payout), (U256::from(50), U256::from(150)));
+    // Later rounds keep the raised stake
+    let (cell, _) = vote(cell, P1, true);
+    let (cell, _) = vote(cell, P2, true);
+    assert_eq!(cell.stake_of(&cell.rounds[2]), U256::from(300));
+}
+
+#[test]
+fn a_raise_only_one_player_deposits_is_refunded() {
+    let cell = continuing_cell(3);
+    let propose = Action::ProposeRaise { player: P2, stake: U256::from(150), deposit: U256::from(50) };
+    let cell = apply(cell, propose, &ctx()).unwrap().0;
+    let (cell, _) = vote(cell, P1, true);
+    let (cell, effects) = vote(cell, P2, true);
+
+    assert!(effects.contains(&Effect::RaiseRefunded { player: P2, amount: U256::from(50) }));
+    assert_eq!((cell.current_stake(), cell.rounds[1].stake), (U256::from(100), U256::ZERO));
+}
+
+#[test]
+fn raises_need_the_continuation_phase_and_an_exact_top_up() {
+    let cell = continuing_cell(3);
+    let raise = |player, stake: u64, deposit: u64| Action::ProposeRaise {
+        player,
+        stake: U256::from(stake),
+        deposit: U256::from(deposit),
+    };
+    assert_eq!(apply(cell.clone(), raise(P1, 100, 0), &ctx()).unwrap_err(), Error::InvalidRaise);
+    assert_eq!(apply(cell.clone(), raise(P1, 200, 50), &ctx()).unwrap_err(), Error::InvalidRaise);
+    let accept = Action::AcceptRaise { player: P2, deposit: U256::from(100) };
+    assert_eq!(apply(cell.clone(), accept.clone(), &ctx()).unwrap_err(), Error::NoRaiseProposed);
+
+    let proposed = apply(cell, raise(P1, 200, 100), &ctx()).unwrap().0;
+    assert_eq!(apply(proposed.clone(), raise(P2, 300, 200), &ctx()).unwrap_err(), Error::RaisePending);
+    let again = Action::AcceptRaise { player: P1, deposit: U256::from(100) };
+    assert_eq!(apply(proposed.clone(), again, &ctx()).unwrap_err(), Error::RaisePending);
+
+    // Both deposits are refunded when the cell ends instead of continuing
+    let accepted = apply(proposed, accept, &ctx()).unwrap().0;
+    let (cell, _) = vote(accepted, P1, false);
+    let (cell, effects) = vote(cell, P2, true);
+    assert!(cell.is_complete);
+    assert!(effects.contains(&Effect::RaiseRefunded { player: P1, amount: U256::from(100) }));
+    assert!(effects.contains(&Effect::RaiseRefunded { player: P2, amount: U256::from(100) }));
+}
+
+#[test]
+fn raises_wait_for_the_round_to_be_played() {
+    let cell = continuing_cell(3);
+    let (cell, _) = vote(cell, P1, true);
+    let (cell, _) = vote(cell, P2, true);
+    assert_eq!(cell.state, CellState::AwaitingMoves);
+
+    let propose = Action::ProposeRaise { player: P1, stake: U256::from(200), deposit: U256::from(100) };
+    assert_eq!(apply(cell, propose, &ctx()).unwrap_err(), Error::RaiseUnavailable);
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use alloy_primitives::{Address, B256, U256};
+use common::{ctx, P1, P2};
+use prisoners_dilemma_core::{
+    apply, merkle_proof, merkle_root, seal_id, verify_proof, Action, Cell, CellState, Effect, Error, Move,
+    SealedReveal, SealedStrategy,
+};
+
+const C: Move = Move::Cooperate;
+const D: Move = Move::Defect;
+
+// The nonce player 1 creates the sealed cells with
+const NONCE: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+fn strategy(player: Address, moves: &[Move]) -> SealedStrategy {
+    SealedStrategy { seal: seal_id(P1, NONCE), player, moves: moves.to_vec(), seed: B256::repeat_byte(player.0[0] + 7) }
+}
+
+fn sealed_cell(p1: &SealedStrategy, p2: &SealedStrategy) -> Cell {
+    let stake = U256::from(100);
+    let total_rounds = p1.moves.len() as u8;
+    let create = Action::CreateSealed { player: P1, stake, total_rounds, nonce: NONCE, root: p1.root() };
+    let cell = apply(Cell::default(), create, &ctx()).unwrap().0;
+    apply(cell, Action::JoinSealed { player: P2, stake, root: p2.root() }, &ctx()).unwrap().0
+}
+
+fn reveal_all(cell: Cell, strategy: &SealedStrategy) -> Result<(Cell, Vec<Effect>), Error> {
+    let reveals = (1..=strategy.moves.len() as u8).filter_map(|round| strategy.reveal(round)).collect();
+    apply(cell, Action::RevealSealed { player: strategy.player, reveals }, &ctx())
+}
+
+#[test]
+fn proofs_verify_every_leaf_of_uneven_trees() {
+    for count in 1..=9u8 {
+        let leaves: Vec<B256> = (0..count).map(B256::repeat_byte).collect();
+        let root = merkle_root(&leaves);
+        for (idx, &leaf) in leaves.iter().enumerate() {
+            let proof = merkle_proof(&leaves, idx);
+            assert!(verify_proof(root, leaf, idx, leaves.len(), &proof));
+            // A leaf only proves at its own position
+            let other = (idx + 1) % leaves.len();
+            assert_eq!(verify_proof(root, leaf, other, leaves.len(), &proof), other == idx);
+        }
+    }
+}
+
+#[test]
+fn two_reveals_play_a_whole_cell() {
+    let p1 = strategy(P1, &[C, C, D]);
+    let p2 = strategy(P2, &[C, D, D]);
+    let cell = sealed_cell(&p1, &p2);
+
+    let (cell, _) = reveal_all(cell, &p1).unwrap();
+    assert_eq!((cell.current_round, cell.sealed_moves.len()), (1, 3));
+
+    let (cell, effects) = reveal_all(cell, &p2).unwrap();
+    assert_eq!(cell.state, CellState::Completed);
+    assert!(cell.sealed_moves.is_empty());
+    assert!(cell.rounds.iter().all(|round| round.is_finished && round.sealed));
+    // Rounds are played at a third of the stake, and the 1 left over is refunded
+    assert!(effects.contains(&Effect::Payout { player: P1, amount: U256::from(66) }));
+    assert!(effects.contains(&Effect::Payout { player: P2, amount: U256::from(99) }));
+}
+
+#[test]
+fn rounds_resolve_as_both_moves_arrive() {
+    let p1 = strategy(P1, &[C, D]);
+    let p2 = strategy(P2, &[D, D]);
+    let cell = sealed_cell(&p1, &p2);
+
+    let reveal = |cell, strategy: &SealedStrategy, round| {
+        let reveals = vec![strategy.reveal(round).unwrap()];
+        apply(cell, Action::RevealSealed { player: strategy.player, reveals }, &ctx())
+    };
+    let (cell, _) = reveal(cell, &p2, 2).unwrap();
+    let (cell, _) = reveal(cell, &p1, 1).unwrap();
+    assert_eq!(cell.current_round, 1);
+    let (cell, _) = reveal(cell, &p2, 1).unwrap();
+    // Round 1 resolved, then round 2 waits on player 1 alone
+    assert_eq!((cell.current_round, cell.state), (2, CellState::AwaitingMoves));
+    assert_eq!(reveal(cell.clone(), &p2, 1).unwrap_err(), Error::RoundAlreadyFinished);
+
+    let (cell, _) = reveal(cell, &p1, 2).unwrap();
+    assert!(cell.is_complete);
+}
+
+#[test]
+fn moves_outside_the_sealed_strategy_are_rejected() {
+    let p1 = strategy(P1, &[C, C]);
+    let p2 = strategy(P2, &[D, C]);
+    let cell = sealed_cell(&p1, &p2);
+
+    let mut flipped = p1.reveal(1).unwrap();
+    flipped.player_move = D;
+    let mut moved = p1.reveal(1).unwrap();
+    moved.round = 2;
+    // Player 2 cannot replay player 1's leaves, which are bound to player 1
+    let stolen = p1.reveal(1).unwrap();
+    // Nor can anyone replay leaves sealed for another cell
+    let elsewhere = SealedStrategy { seal: seal_id(P1, NONCE + U256::from(1)), ..p1.clone() }.reveal(1).unwrap();
+    for (player, reveal) in [(P1, flipped), (P1, moved), (P2, stolen), (P1, elsewhere)] {
+        let action = Action::RevealSealed { player, reveals: vec![reveal] };
+        assert_eq!(apply(cell.clone(), action, &ctx()).unwrap_err(), Error::InvalidProof);
+    }
+
+    let action = Action::SubmitMove { player: P1, player_move: C };
+    assert_eq!(apply(cell.clone(), action, &ctx()).unwrap_err(), Error::SealedCell);
+    let join = Action::Join { player: P2, stake: U256::from(100) };
+    let open = apply(Cell::default(), Action::CreateSealed { player: P1, stake: U256::from(100), total_rounds: 2, nonce: NONCE, root: p1.root() }, &ctx()).unwrap().0;
+    assert_eq!(apply(open, join, &ctx()).unwrap_err(), Error::SealedCell);
+}
+
+#[test]
+fn a_round_only_one_player_revealed_can_time_out() {
+    let p1 = strategy(P1, &[C, D]);
+    let cell = sealed_cell(&p1, &strategy(P2, &[C, C]));
+    let reveals = vec![p1.reveal(1).unwrap()];
+    let (cell, _) = apply(cell, Action::RevealSealed { player: P1, reveals }, &ctx()).unwrap();
+
+    let claim = |player| Action::ClaimRevealTimeout { player };
+    assert_eq!(apply(cell.clone(), claim(P2), &ctx()).unwrap_err(), Error::NoRevealPending);
+    let (cell, effects) = apply(cell, claim(P1), &ctx()).unwrap();
+    assert_eq!(cell.state, CellState::TimedOut);
+    assert!(effects.contains(&Effect::Payout { player: P1, amount: U256::from(200) }));
+}
+
+#[test]
+fn a_reveal_holds_at_most_one_move_per_round() {
+    let p1 = strategy(P1, &[C, D]);
+    let cell = sealed_cell(&p1, &strategy(P2, &[C, C]));
+
+    let reveals = vec![p1.reveal(1).unwrap(), p1.reveal(2).unwrap(), p1.reveal(1).unwrap()];
+    let action = Action::RevealSealed { player: P1, reveals };
+    assert_eq!(apply(cell, action, &ctx()).unwrap_err(), Error::TooManyReveals);
+}
+
+#[test]
+fn packed_reveals_decode_only_when_whole() {
+    let p1 = strategy(P1, &[C, D, D]);
+    let reveals: Vec<_> = (1..=3).filter_map(|round| p1.reveal(round)).collect();
+    let data = SealedReveal::encode_all(&reveals);
+    assert_eq!(SealedReveal::decode_all(&data), Some(reveals));
+    assert_eq!(SealedReveal::decode_all(&[]), Some(vec![]));
+
+    assert_eq!(SealedReveal::decode_all(&data[..data.len() - 1]), None);
+    assert_eq!(SealedReveal::decode_all(&[data.as_slice(), &[0]].concat()), None);
+    let mut bad_move = data.clone();
+    bad_move[1] = 2;
+    assert_eq!(SealedReveal::decode_all(&bad_move), None);
+}
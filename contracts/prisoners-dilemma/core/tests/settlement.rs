@@ -0,0 +1,153 @@
+mod common;
+
+use alloy_primitives::U256;
+use common::{ctx, open_cell, play, started_cell, vote, P1, P2};
+use prisoners_dilemma_core::{apply, preview_settlement, settlement, Action, Cell, ChannelState, Effect, Error, Move, PayoffMatrix};
+
+#[test]
+fn an_open_cell_would_refund_its_creator() {
+    let (p1, p2) = preview_settlement(&open_cell(2), &PayoffMatrix::CONTRACT);
+    assert_eq!((p1.accumulated, p1.if_ended), (U256::ZERO, U256::from(100)));
+    assert_eq!(p2.if_ended, U256::ZERO);
+}
+
+#[test]
+fn remaining_rounds_span_the_best_and_worst_payoffs() {
+    let cell = apply(open_cell(3), Action::Join { player: P2, stake: U256::from(100) }, &ctx()).unwrap().0;
+    let (cell, _) = play(cell, Move::Cooperate, Move::Defect);
+
+    // Rounds are played at a third of the stake, 33, and ending now refunds
+    // the 67 left for the unplayed rounds
+    let (p1, p2) = preview_settlement(&cell, &PayoffMatrix::CONTRACT);
+    assert_eq!((p1.accumulated, p1.if_ended), (U256::from(16), U256::from(83)));
+    assert_eq!(p2.if_ended, U256::from(116));
+    // Player 1 does best by defecting into cooperation throughout and worst
+    // by meeting defections; the 1 no round uses is refunded either way
+    assert_eq!((p1.best_case, p1.worst_case), (U256::from(115), U256::from(49)));
+    assert_eq!((p2.best_case, p2.worst_case), (U256::from(148), U256::from(82)));
+}
+
+#[test]
+fn a_completed_cell_previews_what_it_paid() {
+    let cell = apply(open_cell(1), Action::Join { player: P2, stake: U256::from(100) }, &ctx()).unwrap().0;
+    let (cell, effects) = play(cell, Move::Defect, Move::Cooperate);
+
+    let (p1, p2) = preview_settlement(&cell, &PayoffMatrix::CONTRACT);
+    let paid = |player| {
+        effects.iter().find_map(|effect| match effect {
+            Effect::Payout { player: to, amount } if *to == player => Some(*amount),
+            _ => None,
+        })
+    };
+    assert_eq!((Some(p1.if_ended), Some(p2.if_ended)), (paid(P1), paid(P2)));
+    assert_eq!((p1.best_case, p1.worst_case), (p1.if_ended, p1.if_ended));
+}
+
+#[test]
+fn settlements_never_exceed_the_pot() {
+    let (mut cell, _) = play(started_cell(3), Move::Cooperate, Move::Cooperate);
+    for (p1_move, p2_move) in [(Move::Cooperate, Move::Defect), (Move::Defect, Move::Defect)] {
+        for player in [P1, P2] {
+            cell = vote(cell, player, true).0;
+        }
+        cell = play(cell, p1_move, p2_move).0;
+    }
+    // Each round is played at its third of the stake, so even the richest
+    // rounds are paid in full out of the 200 deposited
+    assert_eq!(cell.total_payouts(), (U256::from(65), U256::from(98)));
+    assert_eq!(settlement(&cell), (U256::from(66), U256::from(99)));
+
+    let (cell, _) = play(started_cell(1), Move::Defect, Move::Defect);
+    assert_eq!(settlement(&cell), (U256::from(50), U256::from(50)));
+}
+
+#[test]
+fn cooperating_and_defecting_throughout_settle_differently() {
+    let play_out = |p1_move, p2_move| {
+        let (mut cell, _) = play(started_cell(3), p1_move, p2_move);
+        for _ in 1..3 {
+            for player in [P1, P2] {
+                cell = vote(cell, player, true).0;
+            }
+            cell = play(cell, p1_move, p2_move).0;
+        }
+        assert!(cell.is_complete);
+        settlement(&cell)
+    };
+    assert_eq!(play_out(Move::Cooperate, Move::Cooperate), (U256::from(100), U256::from(100)));
+    assert_eq!(play_out(Move::Defect, Move::Defect), (U256::from(49), U256::from(49)));
+    assert_eq!(play_out(Move::Defect, Move::Cooperate), (U256::from(148), U256::from(49)));
+}
+
+#[test]
+fn streamed_cells_credit_each_round_instead_of_paying_on_completion() {
+    let stake = U256::from(100);
+    let create = Action::CreateStreamed { player: P1, stake, total_rounds: 2, noise_bps: 0, continuation_bps: 0 };
+    let cell = apply(Cell::default(), create, &ctx()).unwrap().0;
+    let started = apply(cell, Action::Join { player: P2, stake }, &ctx()).unwrap().0;
+
+    let submit = |cell, player, player_move| {
+        let action = Action::SubmitMoveWithContinuation { player, player_move, wants_continue: true };
+        apply(cell, action, &ctx()).unwrap()
+    };
+    let (cell, _) = submit(started.clone(), P1, Move::Cooperate);
+    let (cell, effects) = submit(cell, P2, Move::Defect);
+    assert!(effects.contains(&Effect::Credit { player: P1, round_num: 1, amount: U256::from(25) }));
+    assert!(effects.contains(&Effect::Credit { player: P2, round_num: 1, amount: U256::from(75) }));
+
+    // The second round is funded by its own half of the stake
+    let (cell, effects) = play(cell, Move::Cooperate, Move::Cooperate);
+    assert!(cell.is_complete);
+    assert!(effects.contains(&Effect::Credit { player: P1, round_num: 2, amount: U256::from(50) }));
+    assert!(!effects.iter().any(|effect| matches!(effect, Effect::Payout { .. })));
+    assert_eq!(settlement(&cell), (U256::from(75), U256::from(125)));
+
+    let state = ChannelState::new(U256::from(1), stake / U256::from(2), vec![(Move::Cooperate, Move::Cooperate)], &PayoffMatrix::CONTRACT);
+    assert_eq!(apply(started, Action::SubmitChannelState { state }, &ctx()).unwrap_err(), Error::ChannelUnavailable);
+}
+
+#[test]
+fn streamed_and_lump_sum_cells_settle_the_same_moves_alike() {
+    // Plays `moves` on a fresh cell of `total_rounds`, stopping after the
+    // last of them, and totals what each player was paid or credited
+    let play_out = |streamed: bool, total_rounds: u8, moves: &[(Move, Move)]| {
+        let (player, stake) = (P1, U256::from(100));
+        let create = if streamed {
+            Action::CreateStreamed { player, stake, total_rounds, noise_bps: 0, continuation_bps: 0 }
+        } else {
+            Action::Create { player, stake, total_rounds, noise_bps: 0, continuation_bps: 0 }
+        };
+        let cell = apply(Cell::default(), create, &ctx()).unwrap().0;
+        let mut cell = apply(cell, Action::Join { player: P2, stake }, &ctx()).unwrap().0;
+        let mut effects = Vec::new();
+        for (idx, &(p1_move, p2_move)) in moves.iter().enumerate() {
+            let played = play(cell, p1_move, p2_move);
+            effects.extend(played.1);
+            cell = played.0;
+            if !cell.is_complete {
+                for player in [P1, P2] {
+                    let voted = vote(cell, player, idx + 1 < moves.len());
+                    effects.extend(voted.1);
+                    cell = voted.0;
+                }
+            }
+        }
+        assert!(cell.is_complete);
+        let received = |to| {
+            effects.iter().fold(U256::ZERO, |total, effect| match effect {
+                Effect::Payout { player, amount } | Effect::Credit { player, amount, .. } if *player == to => total + amount,
+                _ => total,
+            })
+        };
+        ((received(P1), received(P2)), settlement(&cell))
+    };
+
+    let (c, d) = (Move::Cooperate, Move::Defect);
+    // Played to the end, and stopped halfway with half the stake unspent
+    for (total_rounds, moves) in [(3, vec![(c, d), (d, d), (c, c)]), (4, vec![(d, c), (c, c)])] {
+        let lump_sum = play_out(false, total_rounds, &moves);
+        assert_eq!(play_out(true, total_rounds, &moves), lump_sum);
+        assert_eq!(lump_sum.0, lump_sum.1);
+    }
+    assert_eq!(play_out(true, 4, &[(d, c), (c, c)]).0, (U256::from(112), U256::from(87)));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use alloy_primitives::U256;
+use common::{ctx, open_cell, P1, P2};
+use prisoners_dilemma_core::{apply, Action, Cell, CellState, Effect, Error, Move};
+
+fn step(cell: Cell, action: Action) -> (Cell, Vec<(CellState, CellState)>) {
+    let (cell, effects) = apply(cell, action, &ctx()).unwrap();
+    let changes = effects
+        .into_iter()
+        .filter_map(|effect| match effect {
+            Effect::StateChanged { from, to } => Some((from, to)),
+            _ => None,
+        })
+        .collect();
+    (cell, changes)
+}
+
+#[test]
+fn a_voted_cell_walks_through_every_live_state() {
+    use CellState::*;
+    let cell = open_cell(2);
+    assert_eq!(cell.state, Open);
+
+    let (cell, changes) = step(cell, Action::Join { player: P2, stake: U256::from(100) });
+    assert_eq!(changes, [(Open, AwaitingMoves)]);
+
+    let (cell, _) = step(cell, Action::SubmitMove { player: P1, player_move: Move::Cooperate });
+    let (cell, changes) = step(cell, Action::SubmitMove { player: P2, player_move: Move::Cooperate });
+    assert_eq!(changes, [(AwaitingMoves, AwaitingContinuation)]);
+
+    let (cell, changes) = step(cell, Action::ContinuationDecision { player: P1, wants_continue: true });
+    assert!(changes.is_empty());
+    let (cell, changes) = step(cell, Action::ContinuationDecision { player: P2, wants_continue: true });
+    assert_eq!(changes, [(AwaitingContinuation, AwaitingMoves)]);
+
+    let (cell, _) = step(cell, Action::SubmitMove { player: P1, player_move: Move::Defect });
+    let (cell, changes) = step(cell, Action::SubmitMove { player: P2, player_move: Move::Defect });
+    assert_eq!(changes, [(AwaitingMoves, Completed)]);
+    assert!(cell.state.is_terminal());
+}
+
+#[test]
+fn cancelling_is_only_possible_while_open() {
+    let (cell, changes) = step(open_cell(1), Action::Cancel { player: P1 });
+    assert_eq!(changes, [(CellState::Open, CellState::Cancelled)]);
+    assert!(cell.is_cancelled());
+
+    let (cell, _) = step(open_cell(1), Action::Join { player: P2, stake: U256::from(100) });
+    assert_eq!(apply(cell, Action::Cancel { player: P1 }, &ctx()).unwrap_err(), Error::OpponentJoined);
+}
+
+#[test]
+fn the_transition_table_has_no_way_out_of_a_terminal_state() {
+    for from in CellState::ALL.into_iter().filter(|state| state.is_terminal()) {
+        assert!(CellState::ALL.into_iter().all(|to| !from.can_become(to)), "{from:?}");
+    }
+}
+
+#[test]
+fn only_a_round_in_play_can_time_out() {
+    let timing_out: Vec<CellState> = CellState::ALL.into_iter().filter(|state| state.can_become(CellState::TimedOut)).collect();
+    assert_eq!(timing_out, [CellState::AwaitingMoves]);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use alloy_primitives::U256;
+use common::{ctx, open_cell, play, started_cell, vote, P1, P2};
+use prisoners_dilemma_core::{apply, market_payout, winning_buckets, Action, Bucket, Cell, Market, Move, WAGER_BUCKETS};
+
+const C: Move = Move::Cooperate;
+const D: Move = Move::Defect;
+
+fn amounts(values: [u64; WAGER_BUCKETS]) -> [U256; WAGER_BUCKETS] {
+    values.map(U256::from)
+}
+
+// A completed voted cell whose rounds went as `moves`
+fn completed(moves: &[(Move, Move)]) -> Cell {
+    let mut cell = started_cell(moves.len() as u8);
+    for (idx, &(p1_move, p2_move)) in moves.iter().enumerate() {
+        if idx > 0 {
+            cell = vote(vote(cell, P1, true).0, P2, true).0;
+        }
+        cell = play(cell, p1_move, p2_move).0;
+    }
+    assert!(cell.is_complete);
+    cell
+}
+
+#[test]
+fn buckets_follow_cooperation_and_who_is_sent_more() {
+    let mask = |buckets: &[Bucket]| buckets.iter().fold(0, |mask, bucket| mask | bucket.mask());
+    assert_eq!(winning_buckets(&completed(&[(C, C), (C, C)])), mask(&[Bucket::MutualCooperation, Bucket::Even]));
+    assert_eq!(winning_buckets(&completed(&[(C, C), (C, D)])), mask(&[Bucket::AnyDefection, Bucket::Player2Ahead]));
+    assert_eq!(winning_buckets(&completed(&[(D, C), (C, D)])), mask(&[Bucket::AnyDefection, Bucket::Even]));
+
+    // A cell that finished no rounds voids both markets
+    let (cancelled, _) = apply(open_cell(2), Action::Cancel { player: P1 }, &ctx()).unwrap();
+    assert_eq!(winning_buckets(&cancelled), 0);
+}
+
+#[test]
+fn a_streamed_cells_winner_follows_what_it_credited() {
+    let create = Action::CreateStreamed { player: P1, stake: U256::from(100), total_rounds: 3, noise_bps: 0, continuation_bps: 0 };
+    let mut cell = apply(Cell::default(), create, &ctx()).unwrap().0;
+    cell = apply(cell, Action::Join { player: P2, stake: U256::from(100) }, &ctx()).unwrap().0;
+    for (idx, (p1_move, p2_move)) in [(D, D), (C, D), (D, C)].into_iter().enumerate() {
+        if idx > 0 {
+            cell = vote(vote(cell, P1, true).0, P2, true).0;
+        }
+        cell = play(cell, p1_move, p2_move).0;
+    }
+    // Every round is credited in full, so even totals leave nobody ahead
+    assert_eq!(cell.total_payouts(), (U256::from(81), U256::from(81)));
+    assert_eq!(winning_buckets(&cell), Bucket::AnyDefection.mask() | Bucket::Even.mask());
+}
+
+#[test]
+fn winning_stakes_share_their_markets_pool() {
+    // Cooperation pool 40 + 60, winner pool 10 + 20 + 30
+    let pools = amounts([40, 60, 10, 20, 30]);
+    let stakes = amounts([10, 0, 0, 5, 0]);
+    let winners = Bucket::MutualCooperation.mask() | Bucket::Player2Ahead.mask();
+    assert_eq!(market_payout(Market::Cooperation, &stakes, &pools, winners), U256::from(25));
+    assert_eq!(market_payout(Market::Winner, &stakes, &pools, winners), U256::from(15));
+    // Backing the losing bucket of a market pays nothing from it
+    let losing = amounts([0, 7, 3, 0, 0]);
+    assert_eq!(market_payout(Market::Cooperation, &losing, &pools, winners), U256::ZERO);
+    assert_eq!(market_payout(Market::Winner, &losing, &pools, winners), U256::ZERO);
+}
+
+#[test]
+fn shares_round_down_leaving_dust_in_the_pool() {
+    // Three equal winning stakes split a pool of 10
+    let pools = amounts([3, 7, 0, 0, 0]);
+    let stakes = amounts([1, 0, 0, 0, 0]);
+    let winners = Bucket::MutualCooperation.mask() | Bucket::Even.mask();
+    assert_eq!(market_payout(Market::Cooperation, &stakes, &pools, winners), U256::from(3));
+}
+
+#[test]
+fn void_markets_refund_every_stake() {
+    let pools = amounts([40, 60, 10, 0, 30]);
+    let stakes = amounts([10, 20, 5, 0, 5]);
+    // Nobody backed the winning bucket of the winner market
+    let winners = Bucket::AnyDefection.mask() | Bucket::Player2Ahead.mask();
+    assert_eq!(market_payout(Market::Winner, &stakes, &pools, winners), U256::from(10));
+    assert_eq!(market_payout(Market::Cooperation, &stakes, &pools, winners), U256::from(20) * U256::from(100) / U256::from(60));
+    // No winning buckets at all refunds both markets
+    assert_eq!(market_payout(Market::Cooperation, &stakes, &pools, 0), U256::from(30));
+    assert_eq!(market_payout(Market::Winner, &stakes, &pools, 0), U256::from(10));
+}
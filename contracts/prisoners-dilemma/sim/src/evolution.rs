@@ -0,0 +1,124 @@
+// Round-robin tournaments and replicator dynamics over their payoffs
+use rand::Rng;
+
+use crate::{
+    game::{play, GameConfig},
+    strategy::Strategy,
+};
+
+// Average results of every strategy against every other (and itself)
+pub struct Tournament {
+    pub strategies: Vec<Strategy>,
+    // payoffs[i][j]: average payout per game to strategy i when facing j, in stakes
+    pub payoffs: Vec<Vec<f64>>,
+    // rounds[i][j]: average number of rounds played between i and j
+    pub rounds: Vec<Vec<f64>>,
+}
+
+impl Tournament {
+    pub fn run(strategies: &[Strategy], games: u32, config: &GameConfig, rng: &mut impl Rng) -> Self {
+        let n = strategies.len();
+        let mut payoffs = vec![vec![0.0; n]; n];
+        let mut rounds = vec![vec![0.0; n]; n];
+
+        for (i, &p1) in strategies.iter().enumerate() {
+            for (j, &p2) in strategies.iter().enumerate().skip(i) {
+                let (mut total1, mut total2, mut total_rounds) = (0.0, 0.0, 0.0);
+                for _ in 0..games {
+                    let result = play(p1, p2, config, rng);
+                    total1 += result.player1_payout;
+                    total2 += result.player2_payout;
+                    total_rounds += f64::from(result.rounds);
+                }
+                let games = f64::from(games.max(1));
+                if i == j {
+                    payoffs[i][i] = (total1 + total2) / (2.0 * games);
+                } else {
+                    payoffs[i][j] = total1 / games;
+                    payoffs[j][i] = total2 / games;
+                }
+                rounds[i][j] = total_rounds / games;
+                rounds[j][i] = total_rounds / games;
+            }
+        }
+
+        Tournament { strategies: strategies.to_vec(), payoffs, rounds }
+    }
+
+    // Expected payout per game against a population with the given shares
+    pub fn fitness(&self, shares: &[f64]) -> Vec<f64> {
+        self.payoffs
+            .iter()
+            .map(|row| row.iter().zip(shares).map(|(payoff, share)| payoff * share).sum())
+            .collect()
+    }
+}
+
+pub struct Generation {
+    pub shares: Vec<f64>,
+    pub fitness: Vec<f64>,
+}
+
+// Discrete-time replicator dynamics starting from a uniform population:
+// each strategy's share grows in proportion to its fitness relative to the mean
+pub fn replicator(tournament: &Tournament, generations: u32) -> Vec<Generation> {
+    let n = tournament.strategies.len();
+    let mut shares = vec![1.0 / n as f64; n];
+    let mut history = Vec::with_capacity(generations as usize + 1);
+
+    for _ in 0..=generations {
+        let fitness = tournament.fitness(&shares);
+        let mean: f64 = shares.iter().zip(&fitness).map(|(share, fit)| share * fit).sum();
+        let next = if mean > 0.0 {
+            shares.iter().zip(&fitness).map(|(share, fit)| share * fit / mean).collect()
+        } else {
+            shares.clone()
+        };
+        history.push(Generation { shares, fitness });
+        shares = next;
+    }
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use prisoners_dilemma_core::PayoffMatrix;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn tournament(payoffs: Vec<Vec<f64>>) -> Tournament {
+        let n = payoffs.len();
+        Tournament { strategies: Strategy::ALL[..n].to_vec(), payoffs, rounds: vec![vec![1.0; n]; n] }
+    }
+
+    #[test]
+    fn shares_grow_with_fitness_relative_to_the_mean() {
+        let generations = replicator(&tournament(vec![vec![3.0, 0.0], vec![5.0, 1.0]]), 1);
+        assert_eq!(generations.len(), 2);
+        assert_eq!(generations[0].shares, [0.5, 0.5]);
+        assert_eq!(generations[0].fitness, [1.5, 3.0]);
+        // Mean fitness 2.25, so the shares become 0.5 * 1.5 / 2.25 and 0.5 * 3 / 2.25
+        let shares = &generations[1].shares;
+        assert!((shares[0] - 1.0 / 3.0).abs() < 1e-12 && (shares[1] - 2.0 / 3.0).abs() < 1e-12);
+        assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn a_population_with_no_fitness_stays_put() {
+        let generations = replicator(&tournament(vec![vec![0.0; 2]; 2]), 3);
+        assert!(generations.iter().all(|generation| generation.shares == [0.5, 0.5]));
+    }
+
+    #[test]
+    fn tournaments_average_both_seats() {
+        let config = GameConfig { min_rounds: 2, max_rounds: 2, noise: 0.0, payoffs: PayoffMatrix::CONTRACT };
+        let strategies = [Strategy::AlwaysCooperate, Strategy::AlwaysDefect];
+        let tournament = Tournament::run(&strategies, 3, &config, &mut StdRng::seed_from_u64(0));
+        // Two rounds a game, each played at half the stake, so the sucker's
+        // and temptation's 0.5 and 1.5 and the punishment's 0.5 are halved
+        assert_eq!(tournament.payoffs, [[1.0, 0.5], [1.5, 0.5]]);
+        assert_eq!(tournament.rounds, [[2.0, 2.0], [2.0, 2.0]]);
+        assert_eq!(tournament.fitness(&[0.5, 0.5]), [0.75, 1.0]);
+    }
+}
@@ -0,0 +1,153 @@
+// A single iterated game, played through the contract's own state machine
+use alloy_primitives::{Address, U256};
+use prisoners_dilemma_core::{apply, Action, Cell, Context, Effect, Move, PayoffMatrix};
+use rand::Rng;
+
+use crate::strategy::Strategy;
+
+// Every game is staked with 1 ETH so payouts read directly as multiples of the stake
+const STAKE_WEI: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GameConfig {
+    pub min_rounds: u8,
+    pub max_rounds: u8,
+    // Probability that an executed move is the opposite of the intended one
+    pub noise: f64,
+    pub payoffs: PayoffMatrix,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GameResult {
+    // Total payouts as multiples of the stake
+    pub player1_payout: f64,
+    pub player2_payout: f64,
+    pub rounds: u8,
+}
+
+pub fn play(p1: Strategy, p2: Strategy, config: &GameConfig, rng: &mut impl Rng) -> GameResult {
+    let ctx = Context { min_stake: U256::ZERO, payoffs: config.payoffs };
+    let player1 = Address::with_last_byte(1);
+    let player2 = Address::with_last_byte(2);
+    let stake = U256::from(STAKE_WEI);
+    let total_rounds = rng.gen_range(config.min_rounds..=config.max_rounds);
+
+    let mut payouts = (U256::ZERO, U256::ZERO);
+    let mut cell = Cell::default();
+    cell = step(cell, Action::Create { player: player1, stake, total_rounds, noise_bps: 0, continuation_bps: 0 }, &ctx, &mut payouts);
+    cell = step(cell, Action::Join { player: player2, stake }, &ctx, &mut payouts);
+
+    // Executed moves from each player's point of view: (own, opponent's)
+    let mut history1: Vec<(Move, Move)> = Vec::new();
+    let mut history2: Vec<(Move, Move)> = Vec::new();
+
+    while !cell.is_complete {
+        let move1 = tremble(p1.next_move(&history1, rng), config.noise, rng);
+        let move2 = tremble(p2.next_move(&history2, rng), config.noise, rng);
+        cell = step(cell, Action::SubmitMove { player: player1, player_move: move1 }, &ctx, &mut payouts);
+        cell = step(cell, Action::SubmitMove { player: player2, player_move: move2 }, &ctx, &mut payouts);
+        history1.push((move1, move2));
+        history2.push((move2, move1));
+
+        if cell.is_complete {
+            break;
+        }
+        let wants_continue = p1.wants_continue(&history1);
+        cell = step(cell, Action::ContinuationDecision { player: player1, wants_continue }, &ctx, &mut payouts);
+        let wants_continue = p2.wants_continue(&history2);
+        cell = step(cell, Action::ContinuationDecision { player: player2, wants_continue }, &ctx, &mut payouts);
+    }
+
+    GameResult {
+        player1_payout: in_stakes(payouts.0),
+        player2_payout: in_stakes(payouts.1),
+        rounds: cell.current_round,
+    }
+}
+
+// Applies an action that is valid by construction, collecting any payouts
+fn step(cell: Cell, action: Action, ctx: &Context, payouts: &mut (U256, U256)) -> Cell {
+    let (cell, effects) = apply(cell, action, ctx).expect("simulated players only make valid moves");
+    for effect in effects {
+        if let Effect::Payout { player, amount } = effect {
+            if player == cell.player1 {
+                payouts.0 += amount;
+            } else {
+                payouts.1 += amount;
+            }
+        }
+    }
+    cell
+}
+
+fn tremble(intended: Move, noise: f64, rng: &mut impl Rng) -> Move {
+    if noise > 0.0 && rng.gen_bool(noise) {
+        match intended {
+            Move::Cooperate => Move::Defect,
+            Move::Defect => Move::Cooperate,
+        }
+    } else {
+        intended
+    }
+}
+
+fn in_stakes(amount: U256) -> f64 {
+    let wei = u128::try_from(amount).expect("payouts fit in u128");
+    wei as f64 / STAKE_WEI as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use prisoners_dilemma_core::payoff;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn config(rounds: u8, noise: f64) -> GameConfig {
+        GameConfig { min_rounds: rounds, max_rounds: rounds, noise, payoffs: PayoffMatrix::CONTRACT }
+    }
+
+    fn play_seeded(p1: Strategy, p2: Strategy, config: &GameConfig) -> GameResult {
+        play(p1, p2, config, &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn the_contract_matrix_pays_what_the_rules_pay() {
+        let stake = U256::from(STAKE_WEI);
+        for p1 in [Move::Cooperate, Move::Defect] {
+            for p2 in [Move::Cooperate, Move::Defect] {
+                assert_eq!(PayoffMatrix::CONTRACT.payoff(stake, p1, p2), payoff(stake, p1, p2));
+            }
+        }
+    }
+
+    #[test]
+    fn games_pay_the_rules_payoffs() {
+        let (sucker, temptation) = payoff(U256::from(STAKE_WEI), Move::Cooperate, Move::Defect);
+        let result = play_seeded(Strategy::AlwaysCooperate, Strategy::AlwaysDefect, &config(1, 0.0));
+        assert_eq!(result.rounds, 1);
+        assert_eq!((result.player1_payout, result.player2_payout), (in_stakes(sucker), in_stakes(temptation)));
+    }
+
+    #[test]
+    fn longer_games_are_played_at_a_share_of_the_stake() {
+        // Three rounds played at a third of the stake each earn 0.5 and 1.5 stakes
+        let result = play_seeded(Strategy::AlwaysCooperate, Strategy::AlwaysDefect, &config(3, 0.0));
+        assert_eq!(result.rounds, 3);
+        assert_eq!((result.player1_payout, result.player2_payout), (0.5, 1.5));
+    }
+
+    #[test]
+    fn walking_away_ends_the_game_early() {
+        let result = play_seeded(Strategy::WalkAway, Strategy::AlwaysDefect, &config(5, 0.0));
+        assert_eq!(result.rounds, 1);
+    }
+
+    #[test]
+    fn certain_noise_flips_every_move() {
+        let result = play_seeded(Strategy::AlwaysCooperate, Strategy::AlwaysCooperate, &config(2, 1.0));
+        // Both rounds are mutual defections played at half the stake
+        let (punishment, _) = payoff(U256::from(STAKE_WEI / 2), Move::Defect, Move::Defect);
+        assert_eq!(result.player1_payout, 2.0 * in_stakes(punishment));
+    }
+}
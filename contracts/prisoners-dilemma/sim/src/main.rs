@@ -0,0 +1,176 @@
+// Monte Carlo / evolutionary simulation of the Prisoner's Dilemma contract
+//
+// Every game is played through `prisoners_dilemma_core::apply`, the same rules
+// the contract runs, including its payoff matrix and continuation voting. The
+// payoff matrix can be overridden to try out new values before changing
+// `PayoffMatrix::CONTRACT` for `resolve_round`.
+mod evolution;
+mod game;
+mod strategy;
+
+use std::{fs::File, io::Write, path::PathBuf};
+
+use clap::Parser;
+use eyre::{bail, eyre, Result, WrapErr};
+use prisoners_dilemma_core::PayoffMatrix;
+use rand::{rngs::StdRng, SeedableRng};
+
+use evolution::{replicator, Generation, Tournament};
+use game::GameConfig;
+use strategy::Strategy;
+
+#[derive(Parser)]
+#[command(version, about = "Simulate strategy populations using the contract's payoff rules")]
+struct Args {
+    /// Comma-separated strategies to include (defaults to all)
+    #[arg(long, value_delimiter = ',')]
+    strategies: Vec<String>,
+
+    /// Games played between every pair of strategies
+    #[arg(long, default_value_t = 500)]
+    games: u32,
+
+    /// Fewest rounds a cell can be created with
+    #[arg(long, default_value_t = 2)]
+    min_rounds: u8,
+
+    /// Most rounds a cell can be created with
+    #[arg(long, default_value_t = 5)]
+    max_rounds: u8,
+
+    /// Probability that a player's move is flipped
+    #[arg(long, default_value_t = 0.0)]
+    noise: f64,
+
+    /// Generations of replicator dynamics
+    #[arg(long, default_value_t = 100)]
+    generations: u32,
+
+    /// Payout when both cooperate, in basis points of the stake
+    #[arg(long, default_value_t = PayoffMatrix::CONTRACT.reward)]
+    reward: u32,
+
+    /// Payout for cooperating against a defector, in basis points of the stake
+    #[arg(long, default_value_t = PayoffMatrix::CONTRACT.sucker)]
+    sucker: u32,
+
+    /// Payout for defecting against a cooperator, in basis points of the stake
+    #[arg(long, default_value_t = PayoffMatrix::CONTRACT.temptation)]
+    temptation: u32,
+
+    /// Payout when both defect, in basis points of the stake
+    #[arg(long, default_value_t = PayoffMatrix::CONTRACT.punishment)]
+    punishment: u32,
+
+    /// Seed for reproducible runs
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Write population shares per generation to this CSV file
+    #[arg(long)]
+    csv: Option<PathBuf>,
+
+    /// Write the pairwise payoff matrix to this CSV file
+    #[arg(long)]
+    matrix_csv: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let strategies = if args.strategies.is_empty() {
+        Strategy::ALL.to_vec()
+    } else {
+        args.strategies
+            .iter()
+            .map(|name| Strategy::from_name(name).ok_or_else(|| eyre!("unknown strategy {name}")))
+            .collect::<Result<Vec<_>>>()?
+    };
+    if args.min_rounds == 0 || args.min_rounds > args.max_rounds {
+        bail!("rounds must satisfy 1 <= min-rounds <= max-rounds");
+    }
+    if !(0.0..=1.0).contains(&args.noise) {
+        bail!("noise must be a probability between 0 and 1");
+    }
+
+    let config = GameConfig {
+        min_rounds: args.min_rounds,
+        max_rounds: args.max_rounds,
+        noise: args.noise,
+        payoffs: PayoffMatrix {
+            reward: args.reward,
+            sucker: args.sucker,
+            temptation: args.temptation,
+            punishment: args.punishment,
+        },
+    };
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let tournament = Tournament::run(&strategies, args.games, &config, &mut rng);
+    let generations = replicator(&tournament, args.generations);
+
+    print_summary(&tournament, &generations);
+    if let Some(path) = &args.matrix_csv {
+        write_matrix_csv(path, &tournament).wrap_err_with(|| format!("writing {}", path.display()))?;
+    }
+    if let Some(path) = &args.csv {
+        write_generations_csv(path, &tournament, &generations)
+            .wrap_err_with(|| format!("writing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn print_summary(tournament: &Tournament, generations: &[Generation]) {
+    let n = tournament.strategies.len() as f64;
+    let uniform = vec![1.0 / n; tournament.strategies.len()];
+    let fitness = tournament.fitness(&uniform);
+    let last = generations.last().expect("replicator always yields the initial generation");
+
+    println!("Average payout per game, in stakes (uniform population):");
+    println!("  {:<24} {:>10} {:>10} {:>12}", "strategy", "payout", "rounds", "final share");
+    let mut order: Vec<usize> = (0..tournament.strategies.len()).collect();
+    order.sort_by(|&a, &b| last.shares[b].total_cmp(&last.shares[a]));
+    for i in order {
+        let rounds = tournament.rounds[i].iter().sum::<f64>() / n;
+        println!(
+            "  {:<24} {:>10.4} {:>10.2} {:>12.4}",
+            tournament.strategies[i].name(),
+            fitness[i],
+            rounds,
+            last.shares[i],
+        );
+    }
+    println!("after {} generations of replicator dynamics", generations.len() - 1);
+}
+
+fn write_matrix_csv(path: &PathBuf, tournament: &Tournament) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "strategy,opponent,avg_payout,avg_rounds")?;
+    for (i, strategy) in tournament.strategies.iter().enumerate() {
+        for (j, opponent) in tournament.strategies.iter().enumerate() {
+            writeln!(
+                file,
+                "{},{},{},{}",
+                strategy.name(),
+                opponent.name(),
+                tournament.payoffs[i][j],
+                tournament.rounds[i][j],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn write_generations_csv(path: &PathBuf, tournament: &Tournament, generations: &[Generation]) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "generation,strategy,share,fitness")?;
+    for (generation, state) in generations.iter().enumerate() {
+        for (i, strategy) in tournament.strategies.iter().enumerate() {
+            writeln!(file, "{},{},{},{}", generation, strategy.name(), state.shares[i], state.fitness[i])?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,169 @@
+// Strategies for the iterated game
+//
+// Each strategy sees the history of executed moves as (own move, opponent's
+// move) pairs, exactly as they are revealed on-chain after every round.
+use prisoners_dilemma_core::Move;
+use rand::Rng;
+
+pub type History = [(Move, Move)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    AlwaysCooperate,
+    AlwaysDefect,
+    TitForTat,
+    SuspiciousTitForTat,
+    TitForTwoTats,
+    GenerousTitForTat,
+    GrimTrigger,
+    Pavlov,
+    Random,
+    WalkAway,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 10] = [
+        Strategy::AlwaysCooperate,
+        Strategy::AlwaysDefect,
+        Strategy::TitForTat,
+        Strategy::SuspiciousTitForTat,
+        Strategy::TitForTwoTats,
+        Strategy::GenerousTitForTat,
+        Strategy::GrimTrigger,
+        Strategy::Pavlov,
+        Strategy::Random,
+        Strategy::WalkAway,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::AlwaysCooperate => "always-cooperate",
+            Strategy::AlwaysDefect => "always-defect",
+            Strategy::TitForTat => "tit-for-tat",
+            Strategy::SuspiciousTitForTat => "suspicious-tit-for-tat",
+            Strategy::TitForTwoTats => "tit-for-two-tats",
+            Strategy::GenerousTitForTat => "generous-tit-for-tat",
+            Strategy::GrimTrigger => "grim-trigger",
+            Strategy::Pavlov => "pavlov",
+            Strategy::Random => "random",
+            Strategy::WalkAway => "walk-away",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        Strategy::ALL.into_iter().find(|strategy| strategy.name() == name)
+    }
+
+    // The move this strategy intends to play next
+    pub fn next_move(self, history: &History, rng: &mut impl Rng) -> Move {
+        let last_opponent = history.last().map(|&(_, theirs)| theirs);
+        match self {
+            Strategy::AlwaysCooperate | Strategy::WalkAway => Move::Cooperate,
+            Strategy::AlwaysDefect => Move::Defect,
+            Strategy::TitForTat => last_opponent.unwrap_or(Move::Cooperate),
+            Strategy::SuspiciousTitForTat => last_opponent.unwrap_or(Move::Defect),
+            Strategy::TitForTwoTats => {
+                let defected_twice = history.len() >= 2
+                    && history[history.len() - 2..].iter().all(|&(_, theirs)| theirs == Move::Defect);
+                if defected_twice { Move::Defect } else { Move::Cooperate }
+            }
+            Strategy::GenerousTitForTat => match last_opponent {
+                // Forgive a tenth of defections so noise doesn't lock in retaliation
+                Some(Move::Defect) if !rng.gen_bool(0.1) => Move::Defect,
+                _ => Move::Cooperate,
+            },
+            Strategy::GrimTrigger => {
+                if history.iter().any(|&(_, theirs)| theirs == Move::Defect) {
+                    Move::Defect
+                } else {
+                    Move::Cooperate
+                }
+            }
+            Strategy::Pavlov => match history.last() {
+                // Win-stay, lose-shift: repeat the last move if the opponent cooperated
+                Some(&(mine, Move::Cooperate)) => mine,
+                Some(&(Move::Cooperate, Move::Defect)) => Move::Defect,
+                Some(&(Move::Defect, Move::Defect)) => Move::Cooperate,
+                None => Move::Cooperate,
+            },
+            Strategy::Random => {
+                if rng.gen_bool(0.5) { Move::Cooperate } else { Move::Defect }
+            }
+        }
+    }
+
+    // Decision submitted through `submit_continuation_decision` after a round
+    pub fn wants_continue(self, history: &History) -> bool {
+        match self {
+            // Leaves the cell as soon as it has been defected on
+            Strategy::WalkAway => history.iter().all(|&(_, theirs)| theirs == Move::Cooperate),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use Move::{Cooperate as C, Defect as D};
+
+    fn next(strategy: Strategy, history: &History) -> Move {
+        strategy.next_move(history, &mut StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for strategy in Strategy::ALL {
+            assert_eq!(Strategy::from_name(strategy.name()), Some(strategy));
+        }
+        assert_eq!(Strategy::from_name("tit-for-three-tats"), None);
+    }
+
+    #[test]
+    fn tit_for_tat_copies_the_last_opponent_move() {
+        assert_eq!(next(Strategy::TitForTat, &[]), C);
+        assert_eq!(next(Strategy::TitForTat, &[(C, D)]), D);
+        assert_eq!(next(Strategy::TitForTat, &[(C, D), (D, C)]), C);
+        assert_eq!(next(Strategy::SuspiciousTitForTat, &[]), D);
+        assert_eq!(next(Strategy::SuspiciousTitForTat, &[(D, C)]), C);
+    }
+
+    #[test]
+    fn tit_for_two_tats_waits_for_a_second_defection() {
+        assert_eq!(next(Strategy::TitForTwoTats, &[(C, D)]), C);
+        assert_eq!(next(Strategy::TitForTwoTats, &[(C, D), (C, C)]), C);
+        assert_eq!(next(Strategy::TitForTwoTats, &[(C, D), (C, D)]), D);
+    }
+
+    #[test]
+    fn grim_trigger_never_forgives() {
+        assert_eq!(next(Strategy::GrimTrigger, &[(C, C)]), C);
+        assert_eq!(next(Strategy::GrimTrigger, &[(C, D), (D, C), (D, C)]), D);
+    }
+
+    #[test]
+    fn pavlov_stays_after_a_win_and_shifts_after_a_loss() {
+        assert_eq!(next(Strategy::Pavlov, &[]), C);
+        assert_eq!(next(Strategy::Pavlov, &[(C, C)]), C);
+        assert_eq!(next(Strategy::Pavlov, &[(D, C)]), D);
+        assert_eq!(next(Strategy::Pavlov, &[(C, D)]), D);
+        assert_eq!(next(Strategy::Pavlov, &[(D, D)]), C);
+    }
+
+    #[test]
+    fn generous_tit_for_tat_forgives_some_defections() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let forgiven = (0..1_000).filter(|_| Strategy::GenerousTitForTat.next_move(&[(C, D)], &mut rng) == C).count();
+        assert!((50..150).contains(&forgiven), "forgave {forgiven} of 1000");
+        assert_eq!(next(Strategy::GenerousTitForTat, &[(D, C)]), C);
+    }
+
+    #[test]
+    fn only_walk_away_leaves_once_defected_on() {
+        assert!(Strategy::WalkAway.wants_continue(&[(C, C)]));
+        assert!(!Strategy::WalkAway.wants_continue(&[(C, C), (C, D)]));
+        assert!(Strategy::GrimTrigger.wants_continue(&[(D, D)]));
+    }
+}
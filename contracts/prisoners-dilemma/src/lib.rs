@@ -16,52 +16,30 @@
 // Allow `cargo stylus export-abi` to generate a main function.
 #![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
 #![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+// The exported ABI's type list outgrows the default limit
+#![recursion_limit = "256"]
 
 #[macro_use]
 extern crate alloc;
 
 use alloc::vec::Vec;
-use stylus_sdk::{alloy_primitives::{U256, Address}, prelude::*, stylus_core};
-use alloy_sol_types::sol;
-
-// Game move options
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Move {
-    Cooperate = 0,
-    Defect = 1,
-}
-
-impl From<u8> for Move {
-    fn from(value: u8) -> Self {
-        match value {
-            0 => Move::Cooperate,
-            _ => Move::Defect,
-        }
-    }
-}
-
-// Round state within a cell
-#[derive(Clone)]
-pub struct Round {
-    pub player1_move: Option<Move>,
-    pub player2_move: Option<Move>,
-    pub player1_payout: U256,
-    pub player2_payout: U256,
-    pub is_finished: bool,
-}
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{keccak256, Address, FixedBytes, I256, U256, U32, U8},
+    prelude::*,
+    storage::{StorageU256, StorageVec},
+    stylus_core::{self, calls::context::Call},
+};
+use alloy_sol_types::{sol, SolCall};
 
-// Cell represents a multi-round game between two players
-#[derive(Clone)]
-pub struct Cell {
-    pub player1: Address,
-    pub player2: Address,
-    pub stake_amount: U256,
-    pub total_rounds: u8,
-    pub current_round: u8,
-    pub is_complete: bool,
-    pub rounds: Vec<Round>,
-    pub continuation_flags: u8,
-}
+// Game types and rules live in the pure `prisoners-dilemma-core` crate
+pub use prisoners_dilemma_core::{Cell, GroupCell, GroupRound, Move, Round};
+use prisoners_dilemma_core::{
+    apply, apply_group, channel_digest, deserialize_cell, deserialize_group_cell, seal_id, serialize_cell, serialize_group_cell,
+    Action, Context, CellState, ChannelState, ContinuationPolicy, Effect, Error, GroupAction, PayoffMatrix, SealedReveal,
+    SettlementPreview, P1_RAISED, P2_RAISED,
+};
+use prisoners_dilemma_core::{market_payout, preview_settlement, winning_buckets, Badge, Bucket, Market, WAGER_BUCKETS};
 
 // Contract storage
 sol_storage! {
@@ -69,11 +47,55 @@ sol_storage! {
     pub struct PrisonersDilemma {
         uint256 cell_counter;
         mapping(uint256 => bytes) cells;
+        // Superseded by `active_cells`, kept for the storage layout
         mapping(address => uint256) player_to_cell;
         mapping(bytes32 => uint256) players_to_cell;
+        // Everything deposited into each cell, the first column of the escrow ledger
         mapping(uint256 => uint256) cell_stakes;
         uint256 min_stake;
         address owner;
+        // N-player public goods cells, sharing ids and escrow with two-player cells
+        mapping(uint256 => bytes) group_cells;
+        // Spectator wagers: stake per (cell, bettor, bucket), pool per (cell, bucket),
+        // and the winning buckets of each settled cell
+        mapping(bytes32 => uint256) wagers;
+        mapping(bytes32 => uint256) wager_pools;
+        mapping(uint256 => uint8) wager_results;
+        // Commit-reveal moves per (cell, round, player), and the salt of the first reveal
+        mapping(bytes32 => bytes32) move_commitments;
+        mapping(bytes32 => bytes32) reveal_salts;
+        // When the opponent of each first reveal must have revealed by, per (cell, round, revealer)
+        mapping(bytes32 => uint256) reveal_deadlines;
+        // Ids of the cells each player is currently in, at most `max_active_cells` (0 = default)
+        mapping(address => uint256[]) active_cells;
+        uint32 max_active_cells;
+        // Rematches: the only player who may join each offered cell, and the
+        // links between consecutive cells of a pair
+        mapping(uint256 => address) rematch_opponents;
+        mapping(uint256 => uint256) previous_cell;
+        mapping(uint256 => uint256) next_cell;
+        // Append-only history of every cell a player entered and every cell a pair played
+        mapping(address => uint256[]) player_cells;
+        mapping(bytes32 => uint256[]) pair_cells;
+        // Lobby of two-player cells waiting for an opponent, and each one's position + 1
+        uint256[] open_cells;
+        mapping(uint256 => uint256) open_cell_slots;
+        // The companion contract minting achievement badges, or zero to mint none
+        address badge_contract;
+        // Escrow ledger: paid out and still owed per cell, and the total the
+        // contract holds for players, offers and bettors
+        mapping(uint256 => uint256) cell_paid;
+        mapping(uint256 => uint256) cell_outstanding;
+        uint256 total_escrowed;
+        // Set while a mutating entry point runs
+        bool reentrancy_lock;
+        // When the challenge window of each disputed cell's channel state closes
+        mapping(uint256 => uint256) channel_deadlines;
+        // What streamed cells and lapsed raises have credited each player and
+        // they have not withdrawn yet
+        mapping(address => uint256) balances;
+        // Seals sealed cells have been created with, each usable once
+        mapping(bytes32 => bool) used_seals;
     }
 }
 
@@ -83,6 +105,24 @@ sol! {
     event PlayerJoined(uint256 indexed cell_id, address indexed player2);
     event RoundComplete(uint256 indexed cell_id, uint8 round_num);
     event CellComplete(uint256 indexed cell_id);
+    event GroupPlayerJoined(uint256 indexed cell_id, address indexed player);
+    event WagerPlaced(uint256 indexed cell_id, address indexed bettor, uint8 bucket, uint256 amount);
+    event WagersSettled(uint256 indexed cell_id, uint8 winning_buckets);
+    event WagerClaimed(uint256 indexed cell_id, address indexed bettor, uint256 amount);
+    event MoveCommitted(uint256 indexed cell_id, address indexed player, uint8 round_num);
+    event RematchOffered(uint256 indexed previous_cell_id, uint256 indexed cell_id, address indexed player);
+    event ContinuationDrawn(uint256 indexed cell_id, uint8 round_num, uint16 draw, bool continues);
+    event CellCancelled(uint256 indexed cell_id);
+    event ContinuationPolicySet(uint256 indexed cell_id, address indexed player, uint8 policy, int256 param);
+    event CellStateChanged(uint256 indexed cell_id, uint8 from, uint8 to);
+    event ChannelStateSubmitted(uint256 indexed cell_id, uint8 rounds, uint256 challenge_deadline);
+    event StrategySealed(uint256 indexed cell_id, address indexed player, bytes32 root);
+    event SealedMovesRevealed(uint256 indexed cell_id, address indexed player, uint8 count);
+    event PayoutCredited(uint256 indexed cell_id, address indexed player, uint8 round_num, uint256 amount);
+    event Withdrawn(address indexed player, uint256 amount);
+    event RaiseProposed(uint256 indexed cell_id, address indexed player, uint256 stake);
+    event RaiseAccepted(uint256 indexed cell_id, address indexed player, uint256 stake);
+    event RaiseRefunded(uint256 indexed cell_id, address indexed player, uint256 amount);
     error StakeTooLow(uint256 cell_id);
     error AlreadyInCell(uint256 cell_id);
     error CellFull(uint256 cell_id);
@@ -95,6 +135,61 @@ sol! {
     error RoundAlreadyFinished(uint256 cell_id);
     error MaxRoundsReached(uint256 cell_id);
     error InvalidCellData(uint256 cell_id);
+    error InvalidGroupSize(uint256 cell_id);
+    error InvalidMultiplier(uint256 cell_id);
+    error WaitingForPlayers(uint256 cell_id);
+    error InvalidBucket(uint256 cell_id);
+    error BettingClosed(uint256 cell_id);
+    error PlayerCannotBet(uint256 cell_id);
+    error WagersNotSettled(uint256 cell_id);
+    error NoWager(uint256 cell_id);
+    error TransferFailed(uint256 cell_id);
+    error InvalidProbability(uint256 cell_id);
+    error CommitRequired(uint256 cell_id);
+    error AlreadyCommitted(uint256 cell_id);
+    error NoCommitment(uint256 cell_id);
+    error WaitingForCommitment(uint256 cell_id);
+    error InvalidReveal(uint256 cell_id);
+    error ContinuationIsRandom(uint256 cell_id);
+    error TooManyActiveCells(uint256 cell_id);
+    error NotOwner(uint256 cell_id);
+    error CellNotFound(uint256 cell_id);
+    error CellNotComplete(uint256 cell_id);
+    error RematchUnavailable(uint256 cell_id);
+    error NoRematchOffer(uint256 cell_id);
+    error OpponentJoined(uint256 cell_id);
+    error InvalidTransition(uint256 cell_id);
+    error ChannelUnavailable(uint256 cell_id);
+    error InvalidChannelState(uint256 cell_id);
+    error StaleChannelState(uint256 cell_id);
+    error NoChannelState(uint256 cell_id);
+    error ChannelDisputed(uint256 cell_id);
+    error InvalidSignature(uint256 cell_id);
+    error ChallengeWindowOpen(uint256 cell_id);
+    error ChallengeWindowClosed(uint256 cell_id);
+    error SealedCell(uint256 cell_id);
+    error NotSealed(uint256 cell_id);
+    error InvalidProof(uint256 cell_id);
+    error NothingToWithdraw(uint256 cell_id);
+    error RaiseUnavailable(uint256 cell_id);
+    error InvalidRaise(uint256 cell_id);
+    error RaisePending(uint256 cell_id);
+    error NoRaiseProposed(uint256 cell_id);
+    error TooManyCells(uint256 cell_id);
+    error Reentrancy(uint256 cell_id);
+    error InvalidPolicy(uint256 cell_id);
+    error NoRevealPending(uint256 cell_id);
+    error RevealWindowOpen(uint256 cell_id);
+    error TooManyReveals(uint256 cell_id);
+    error SealUsed(uint256 cell_id);
+    error NotCommitReveal(uint256 cell_id);
+}
+
+// The badge companion's mint, called for each `Effect::BadgeEarned`
+sol! {
+    interface IPrisonersDilemmaBadges {
+        function mint(uint256 cell_id, address player, uint8 badge) external;
+    }
 }
 
 // Error types
@@ -112,8 +207,95 @@ pub enum PrisonersDilemmaErrors {
     RoundAlreadyFinished(RoundAlreadyFinished),
     MaxRoundsReached(MaxRoundsReached),
     InvalidCellData(InvalidCellData),
+    InvalidGroupSize(InvalidGroupSize),
+    InvalidMultiplier(InvalidMultiplier),
+    WaitingForPlayers(WaitingForPlayers),
+    InvalidBucket(InvalidBucket),
+    BettingClosed(BettingClosed),
+    PlayerCannotBet(PlayerCannotBet),
+    WagersNotSettled(WagersNotSettled),
+    NoWager(NoWager),
+    TransferFailed(TransferFailed),
+    InvalidProbability(InvalidProbability),
+    CommitRequired(CommitRequired),
+    AlreadyCommitted(AlreadyCommitted),
+    NoCommitment(NoCommitment),
+    WaitingForCommitment(WaitingForCommitment),
+    InvalidReveal(InvalidReveal),
+    ContinuationIsRandom(ContinuationIsRandom),
+    TooManyActiveCells(TooManyActiveCells),
+    NotOwner(NotOwner),
+    CellNotFound(CellNotFound),
+    CellNotComplete(CellNotComplete),
+    RematchUnavailable(RematchUnavailable),
+    NoRematchOffer(NoRematchOffer),
+    OpponentJoined(OpponentJoined),
+    InvalidTransition(InvalidTransition),
+    ChannelUnavailable(ChannelUnavailable),
+    InvalidChannelState(InvalidChannelState),
+    StaleChannelState(StaleChannelState),
+    NoChannelState(NoChannelState),
+    ChannelDisputed(ChannelDisputed),
+    InvalidSignature(InvalidSignature),
+    ChallengeWindowOpen(ChallengeWindowOpen),
+    ChallengeWindowClosed(ChallengeWindowClosed),
+    SealedCell(SealedCell),
+    NotSealed(NotSealed),
+    InvalidProof(InvalidProof),
+    NothingToWithdraw(NothingToWithdraw),
+    RaiseUnavailable(RaiseUnavailable),
+    InvalidRaise(InvalidRaise),
+    RaisePending(RaisePending),
+    NoRaiseProposed(NoRaiseProposed),
+    TooManyCells(TooManyCells),
+    Reentrancy(Reentrancy),
+    InvalidPolicy(InvalidPolicy),
+    NoRevealPending(NoRevealPending),
+    RevealWindowOpen(RevealWindowOpen),
+    TooManyReveals(TooManyReveals),
+    SealUsed(SealUsed),
+    NotCommitReveal(NotCommitReveal),
 }
 
+// Concurrent cells per player until the owner configures a cap, matching the
+// original one-cell-per-player behaviour
+const DEFAULT_MAX_ACTIVE_CELLS: u32 = 1;
+
+// Most cell ids a paginated getter returns per call
+const MAX_PAGE_SIZE: usize = 100;
+
+// Most cells `get_cells` loads per call
+const MAX_BATCH_CELLS: usize = 50;
+
+// Seconds a submitted channel state can be overridden by a newer one
+const CHALLENGE_WINDOW: u64 = 24 * 60 * 60;
+
+// Seconds a player has to reveal their move once their opponent has
+const REVEAL_WINDOW: u64 = 24 * 60 * 60;
+
+// The ecrecover precompile
+const ECRECOVER: Address = Address::with_last_byte(1);
+
+// A two-player cell as returned by `get_cells`: (cell_id, player1, player2,
+// stake, total_rounds, current_round, is_complete, is_cancelled,
+// player1_decided, player1_wants, player2_decided, player2_wants, noise_bps,
+// continuation_bps, state, challenge_deadline, reveal_deadline, rounds_count).
+// `rounds_count` is how many of the listed rounds are the cell's
+type CellView = (U256, Address, Address, U256, u8, u8, bool, bool, bool, bool, bool, bool, u16, u16, u8, U256, U256, u8);
+
+// (player1_move, player2_move, player1_intended, player2_intended,
+// player1_payout, player2_payout, is_finished, sealed, stake); moves are 0
+// until the round is finished, sealed rounds were played from revealed
+// strategies, and stake is what the round is played at after any raise
+type RoundView = (u8, u8, u8, u8, U256, U256, bool, bool, U256);
+
+// One player's side of `preview_settlement`: (accumulated, if_ended,
+// best_case, worst_case)
+type SettlementView = (U256, U256, U256, U256);
+
+// Marks a cell's wagers as settled in `wager_results`, next to the winning bucket bits
+const WAGERS_SETTLED: u8 = 0x80;
+
 #[public]
 impl PrisonersDilemma {
     pub fn initialize(&mut self, min_stake: U256) {
@@ -124,194 +306,618 @@ impl PrisonersDilemma {
         }
     }
 
+    // Owner only: how many cells a player may be in at once (0 restores the default)
+    pub fn set_max_active_cells(&mut self, max_active_cells: u32) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        if self.vm().msg_sender() != self.owner.get() {
+            return Err(PrisonersDilemmaErrors::NotOwner(NotOwner { cell_id: U256::ZERO }));
+        }
+        self.max_active_cells.set(U32::from(max_active_cells));
+        self.unlock();
+        Ok(())
+    }
+
     #[payable]
     pub fn create_cell(&mut self, total_rounds: u8) -> Result<U256, PrisonersDilemmaErrors> {
-        let sender = self.vm().msg_sender();
-        let stake = self.vm().msg_value();
-        
-        if stake < self.min_stake.get() {
-            return Err(PrisonersDilemmaErrors::StakeTooLow(StakeTooLow { cell_id: U256::ZERO }));
-        }
-        if self.player_to_cell.get(sender) != U256::ZERO {
-            return Err(PrisonersDilemmaErrors::AlreadyInCell(AlreadyInCell { cell_id: U256::ZERO }));
+        self.lock()?;
+        let cell_id = self.open_cell(|player, stake| Action::Create { player, stake, total_rounds, noise_bps: 0, continuation_bps: 0 })?;
+        self.unlock();
+        Ok(cell_id)
+    }
+
+    // A cell for research setups, with probabilities in basis points:
+    // - `noise_bps`: each move is flipped before the round resolves
+    // - `continuation_bps`: δ, the chance of another round after each one, replacing
+    //   continuation votes (`total_rounds` stays the cap); zero keeps voting
+    // Moves must go through `commit_move`/`reveal_move` when either is set
+    #[payable]
+    pub fn create_research_cell(&mut self, total_rounds: u8, noise_bps: u16, continuation_bps: u16) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let cell_id = self.open_cell(|player, stake| Action::Create { player, stake, total_rounds, noise_bps, continuation_bps })?;
+        self.unlock();
+        Ok(cell_id)
+    }
+
+    // A cell whose round payouts are credited to the players' withdrawable
+    // balances as each round resolves, rather than sent when it completes.
+    // Takes the same settings as `create_research_cell`
+    #[payable]
+    pub fn create_streamed_cell(&mut self, total_rounds: u8, noise_bps: u16, continuation_bps: u16) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let cell_id = self.open_cell(|player, stake| Action::CreateStreamed { player, stake, total_rounds, noise_bps, continuation_bps })?;
+        self.unlock();
+        Ok(cell_id)
+    }
+
+    // A cell played from sealed strategies: `root` is the Merkle root of
+    // your move for every round, built from the core's `sealed_leaf`. The
+    // joiner seals theirs with `join_sealed_cell`, then both reveal through
+    // `reveal_sealed_moves`. Sealed cells always run to `total_rounds`.
+    // Leaves are bound to the core's `seal_id` of you and a `nonce` of your
+    // choosing, which no other sealed cell may have used; see `get_cell_seal`
+    #[payable]
+    pub fn create_sealed_cell(&mut self, nonce: U256, total_rounds: u8, root: FixedBytes<32>) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let seal = seal_id(self.vm().msg_sender(), nonce);
+        if self.used_seals.get(seal) {
+            return Err(PrisonersDilemmaErrors::SealUsed(SealUsed { cell_id: U256::ZERO }));
         }
-        
-        let cell_id = self.cell_counter.get() + U256::from(1);
-        self.cell_counter.set(cell_id);
-        
-        let cell = Cell {
-            player1: sender,
-            player2: Address::ZERO,
-            stake_amount: stake,
-            total_rounds,
-            current_round: 0,
-            is_complete: false,
-            rounds: Vec::new(),
-            continuation_flags: 0,
-        };
-        
-        self.store_cell(cell_id, &cell);
-        self.player_to_cell.setter(sender).set(cell_id);
-        self.cell_stakes.setter(cell_id).set(stake);
-        
-    stylus_core::log(self.vm(), CellCreated { cell_id, player1: sender, stake });
-    Ok(cell_id)
+        let cell_id = self.open_cell(|player, stake| Action::CreateSealed { player, stake, total_rounds, nonce, root })?;
+        self.used_seals.setter(seal).set(true);
+        self.unlock();
+        Ok(cell_id)
     }
 
     #[payable]
     pub fn join_cell(&mut self, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        self.take_seat(cell_id, |player, stake| Action::Join { player, stake })?;
+        self.unlock();
+        Ok(())
+    }
+
+    // Join a sealed cell, committing the root of your own strategy
+    #[payable]
+    pub fn join_sealed_cell(&mut self, cell_id: U256, root: FixedBytes<32>) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        self.take_seat(cell_id, |player, stake| Action::JoinSealed { player, stake, root })?;
+        self.unlock();
+        Ok(())
+    }
+
+    // Withdraw a cell nobody has joined yet, refunding the stake and voiding its wagers
+    pub fn cancel_cell(&mut self, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
         let sender = self.vm().msg_sender();
-        let stake = self.vm().msg_value();
-        
-        if self.player_to_cell.get(sender) != U256::ZERO {
-            return Err(PrisonersDilemmaErrors::AlreadyInCell(AlreadyInCell { cell_id }));
-        }
+        let cell = self.load_cell(cell_id);
+        let (cell, effects) = self.apply(cell_id, cell, Action::Cancel { player: sender })?;
 
-        let mut cell = self.load_cell(cell_id);
-        if cell.player2 != Address::ZERO {
-            return Err(PrisonersDilemmaErrors::CellFull(CellFull { cell_id }));
-        }
-        if stake != cell.stake_amount {
-            return Err(PrisonersDilemmaErrors::WrongStake(WrongStake { cell_id }));
-        }
-        
-        cell.player2 = sender;
-        cell.current_round = 1;
-        
-        // Initialize first round
-        cell.rounds.push(Round {
-            player1_move: None,
-            player2_move: None,
-            player1_payout: U256::ZERO,
-            player2_payout: U256::ZERO,
-            is_finished: false,
-        });
-        
-        self.store_cell(cell_id, &cell);
-        self.player_to_cell.setter(sender).set(cell_id);
-        self.cell_stakes.setter(cell_id).set(cell.stake_amount + stake);
-        
-        let key = self.hash_players(cell.player1, sender);
-        self.players_to_cell.setter(key.into()).set(cell_id);
-        
-    stylus_core::log(self.vm(), PlayerJoined { cell_id, player2: sender });
-    Ok(())
+        self.close_open_cell(cell_id);
+        self.finish_turn(cell_id, &cell, effects);
+        stylus_core::log(self.vm(), CellCancelled { cell_id });
+        self.unlock();
+        Ok(())
     }
 
     pub fn submit_move(&mut self, cell_id: U256, move_choice: u8) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
         let sender = self.vm().msg_sender();
-        let mut cell = self.load_cell(cell_id);
+        let cell = self.load_cell(cell_id);
+
+        let action = Action::SubmitMove { player: sender, player_move: Move::from(move_choice) };
+        let (cell, effects) = self.apply(cell_id, cell, action)?;
         
-        if cell.is_complete {
-            return Err(PrisonersDilemmaErrors::CellIsComplete(CellIsComplete { cell_id }));
-        }
-        if cell.player2 == Address::ZERO {
-            return Err(PrisonersDilemmaErrors::NeedPlayer2(NeedPlayer2 { cell_id }));
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Submit a move and your continuation vote for the round at once. When
+    // both players do, the round resolves and the next one opens in the same
+    // transaction. On the last round the vote is ignored
+    pub fn submit_move_with_continuation(&mut self, cell_id: U256, move_choice: u8, wants_continue: bool) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_cell(cell_id);
+
+        let action = Action::SubmitMoveWithContinuation { player: sender, player_move: Move::from(move_choice), wants_continue };
+        let (cell, effects) = self.apply(cell_id, cell, action)?;
+
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Commit to this round's move without revealing it, as
+    // keccak256(cell_id, player, move_choice, salt); see `get_move_commitment`.
+    // Only noise and δ cells take commitments. The first commitment of a
+    // round gives the opponent the reveal window to commit theirs, see
+    // `claim_reveal_timeout`
+    pub fn commit_move(&mut self, cell_id: U256, commitment: FixedBytes<32>) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_cell(cell_id);
+        self.apply(cell_id, cell.clone(), Action::CommitMove { player: sender })?;
+        let opponent = if sender == cell.player1 { cell.player2 } else { cell.player1 };
+
+        let key = round_key(cell_id, cell.current_round, sender);
+        if self.move_commitments.get(key) != FixedBytes::ZERO {
+            return Err(PrisonersDilemmaErrors::AlreadyCommitted(AlreadyCommitted { cell_id }));
         }
-        if sender != cell.player1 && sender != cell.player2 {
-            return Err(PrisonersDilemmaErrors::NotInCell(NotInCell { cell_id }));
+        self.move_commitments.setter(key).set(commitment);
+
+        // Only the player left waiting has a deadline running
+        let opponent_key = round_key(cell_id, cell.current_round, opponent);
+        if self.move_commitments.get(opponent_key) == FixedBytes::ZERO {
+            let deadline = U256::from(self.vm().block_timestamp() + REVEAL_WINDOW);
+            self.reveal_deadlines.setter(key).set(deadline);
+        } else {
+            self.reveal_deadlines.delete(opponent_key);
         }
 
-        if cell.current_round == 0 {
-            return Err(PrisonersDilemmaErrors::NoRoundStarted(NoRoundStarted { cell_id }));
+        stylus_core::log(self.vm(), MoveCommitted { cell_id, player: sender, round_num: cell.current_round });
+        self.unlock();
+        Ok(())
+    }
+
+    // Reveal a committed move once both players have committed. The second
+    // reveal resolves the round, drawing any noise from both players' salts.
+    // The second revealer knows both salts and so the draws before revealing;
+    // withholding the reveal past the window forfeits the cell instead, see
+    // `claim_reveal_timeout`
+    pub fn reveal_move(&mut self, cell_id: U256, move_choice: u8, salt: FixedBytes<32>) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_cell(cell_id);
+        self.apply(cell_id, cell.clone(), Action::CommitMove { player: sender })?;
+        let opponent = if sender == cell.player1 { cell.player2 } else { cell.player1 };
+
+        let key = round_key(cell_id, cell.current_round, sender);
+        let commitment = self.move_commitments.get(key);
+        if commitment == FixedBytes::ZERO {
+            return Err(PrisonersDilemmaErrors::NoCommitment(NoCommitment { cell_id }));
         }
-        let round_idx = (cell.current_round - 1) as usize;
-        if round_idx >= cell.rounds.len() {
-            return Err(PrisonersDilemmaErrors::RoundNotReady(RoundNotReady { cell_id }));
+        if self.get_move_commitment(cell_id, sender, move_choice, salt) != commitment {
+            return Err(PrisonersDilemmaErrors::InvalidReveal(InvalidReveal { cell_id }));
         }
 
-        let round = &mut cell.rounds[round_idx];
-        if round.is_finished {
-            return Err(PrisonersDilemmaErrors::RoundAlreadyFinished(RoundAlreadyFinished { cell_id }));
+        // Nobody may reveal before both moves are locked in
+        let opponent_key = round_key(cell_id, cell.current_round, opponent);
+        let opponent_moved = cell
+            .rounds
+            .get((cell.current_round as usize).wrapping_sub(1))
+            .is_some_and(|round| if sender == cell.player1 { round.player2_move.is_some() } else { round.player1_move.is_some() });
+        if !opponent_moved && self.move_commitments.get(opponent_key) == FixedBytes::ZERO {
+            return Err(PrisonersDilemmaErrors::WaitingForCommitment(WaitingForCommitment { cell_id }));
         }
-        
-        let player_move = Move::from(move_choice);
-        
-        if sender == cell.player1 {
-            if round.player1_move.is_some() {
-                return Err(PrisonersDilemmaErrors::RoundAlreadyFinished(RoundAlreadyFinished { cell_id }));
-            }
-            round.player1_move = Some(player_move);
+
+        // The opponent has revealed already: combine both salts
+        let entropy = if opponent_moved {
+            let opponent_salt = self.reveal_salts.get(opponent_key);
+            let (salt1, salt2) = if sender == cell.player1 { (salt, opponent_salt) } else { (opponent_salt, salt) };
+            Some(keccak256([salt1.as_slice(), salt2.as_slice()].concat()))
         } else {
-            if round.player2_move.is_some() {
-                return Err(PrisonersDilemmaErrors::RoundAlreadyFinished(RoundAlreadyFinished { cell_id }));
-            }
-            round.player2_move = Some(player_move);
-        }
-        
-        // Check if round is complete
-        if round.player1_move.is_some() && round.player2_move.is_some() {
-            self.resolve_round(&mut cell, cell_id, round_idx);
+            self.reveal_salts.setter(key).set(salt);
+            let deadline = U256::from(self.vm().block_timestamp() + REVEAL_WINDOW);
+            self.reveal_deadlines.setter(key).set(deadline);
+            None
+        };
+
+        let action = Action::RevealMove { player: sender, player_move: Move::from(move_choice), entropy };
+        let (cell, effects) = self.apply(cell_id, cell, action)?;
+
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // End a round your opponent has not committed or revealed in once the
+    // window after your commitment or reveal has passed: the cell times out
+    // and you are paid everything left in its pot. Anyone can call it
+    pub fn claim_reveal_timeout(&mut self, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let cell = self.load_cell(cell_id);
+        let Some((player, deadline)) = self.waiting_deadline(cell_id, &cell) else {
+            return Err(PrisonersDilemmaErrors::NoRevealPending(NoRevealPending { cell_id }));
+        };
+        if U256::from(self.vm().block_timestamp()) < deadline {
+            return Err(PrisonersDilemmaErrors::RevealWindowOpen(RevealWindowOpen { cell_id }));
         }
-        
-    self.store_cell(cell_id, &cell);
-    Ok(())
+        let (cell, effects) = self.apply(cell_id, cell, Action::ClaimRevealTimeout { player })?;
+
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
     }
 
     pub fn submit_continuation_decision(&mut self, cell_id: U256, wants_continue: bool) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
         let sender = self.vm().msg_sender();
-        let mut cell = self.load_cell(cell_id);
+        let cell = self.load_cell(cell_id);
+
+        let action = Action::ContinuationDecision { player: sender, wants_continue };
+        let (cell, effects) = self.apply(cell_id, cell, action)?;
         
-        if cell.is_complete {
-            return Err(PrisonersDilemmaErrors::CellIsComplete(CellIsComplete { cell_id }));
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Set a standing continuation vote for the rest of the cell, cast for you
+    // whenever a round resolves unless you already voted in it:
+    // 0 = vote by hand, 1 = always continue, 2 = stop after being defected on,
+    // 3 = stop after `param` rounds, 4 = stop once your net payout (payouts
+    // minus the stake of each round played, in wei) falls below `param`
+    pub fn set_continuation_policy(&mut self, cell_id: U256, policy: u8, param: I256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_cell(cell_id);
+
+        let Some(policy) = ContinuationPolicy::from_parts(policy, param) else {
+            return Err(PrisonersDilemmaErrors::InvalidPolicy(InvalidPolicy { cell_id }));
+        };
+        let action = Action::SetContinuationPolicy { player: sender, policy };
+        let (cell, effects) = self.apply(cell_id, cell, action)?;
+
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Submit the latest state both players signed while playing off-chain,
+    // see `ChannelState` for its encoding and `get_channel_digest` for what
+    // is signed. The cell is disputed until the challenge window closes, and
+    // a state with more rounds can replace it until then
+    pub fn settle_offchain(
+        &mut self,
+        cell_id: U256,
+        state: Bytes,
+        sig1: Bytes,
+        sig2: Bytes,
+    ) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let cell = self.load_cell(cell_id);
+        let invalid = || PrisonersDilemmaErrors::InvalidChannelState(InvalidChannelState { cell_id });
+        let state = ChannelState::decode(&state).filter(|state| state.cell_id == cell_id).ok_or_else(invalid)?;
+
+        let digest = channel_digest(self.vm().contract_address(), self.vm().chain_id(), &state);
+        if self.recover(digest, &sig1) != Some(cell.player1) || self.recover(digest, &sig2) != Some(cell.player2) {
+            return Err(PrisonersDilemmaErrors::InvalidSignature(InvalidSignature { cell_id }));
         }
-        if sender != cell.player1 && sender != cell.player2 {
-            return Err(PrisonersDilemmaErrors::NotInCell(NotInCell { cell_id }));
+        let now = U256::from(self.vm().block_timestamp());
+        if cell.state == CellState::Disputed && now >= self.channel_deadlines.get(cell_id) {
+            return Err(PrisonersDilemmaErrors::ChallengeWindowClosed(ChallengeWindowClosed { cell_id }));
         }
-        if cell.current_round >= cell.total_rounds {
-            return Err(PrisonersDilemmaErrors::MaxRoundsReached(MaxRoundsReached { cell_id }));
+        let (cell, effects) = self.apply(cell_id, cell, Action::SubmitChannelState { state })?;
+
+        self.channel_deadlines.setter(cell_id).set(now + U256::from(CHALLENGE_WINDOW));
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Apply a disputed cell's channel state once its challenge window has
+    // closed: the cell completes if every round was played off-chain, and
+    // otherwise play continues on-chain from the next round. Anyone can call it
+    pub fn finalize_offchain(&mut self, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let cell = self.load_cell(cell_id);
+        if U256::from(self.vm().block_timestamp()) < self.channel_deadlines.get(cell_id) {
+            return Err(PrisonersDilemmaErrors::ChallengeWindowOpen(ChallengeWindowOpen { cell_id }));
         }
-        
-        // Set continuation flags using bit positions:
-        // Bit 0 (value 1): Player 1 wants to continue
-        // Bit 1 (value 2): Player 2 wants to continue  
-        // Bit 2 (value 4): Player 1 has decided
-        // Bit 3 (value 8): Player 2 has decided
-        if sender == cell.player1 {
-            // Player 1 decision
-            if wants_continue {
-                cell.continuation_flags |= 1; // Set P1 wants continue
-            } else {
-                cell.continuation_flags &= !1; // Clear P1 wants continue
-            }
-            cell.continuation_flags |= 4; // Mark P1 as decided
-        } else {
-            // Player 2 decision
-            if wants_continue {
-                cell.continuation_flags |= 2; // Set P2 wants continue
-            } else {
-                cell.continuation_flags &= !2; // Clear P2 wants continue
+        let (cell, effects) = self.apply(cell_id, cell, Action::FinalizeChannel)?;
+
+        self.channel_deadlines.delete(cell_id);
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Reveal moves of your sealed strategy, any number of rounds at once,
+    // packed as the core's `SealedReveal::encode_all` lays them out. Each
+    // round resolves as soon as both players' moves for it are revealed.
+    // Revealing the current round before your opponent gives them the reveal
+    // window to reveal theirs, see `claim_reveal_timeout`
+    pub fn reveal_sealed_moves(&mut self, cell_id: U256, reveals: Bytes) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_cell(cell_id);
+
+        let invalid = || PrisonersDilemmaErrors::InvalidProof(InvalidProof { cell_id });
+        let reveals = SealedReveal::decode_all(&reveals).ok_or_else(invalid)?;
+        let (cell, effects) = self.apply(cell_id, cell, Action::RevealSealed { player: sender, reveals })?;
+
+        // Only the player left waiting has a deadline running, from when they
+        // first revealed the round
+        let round_num = cell.current_round;
+        let waiting = match cell.sealed_moves.get((round_num as usize).wrapping_sub(1)) {
+            Some((Some(_), None)) => Some(cell.player1),
+            Some((None, Some(_))) => Some(cell.player2),
+            _ => None,
+        };
+        if let Some(player) = waiting.filter(|_| !cell.is_complete) {
+            let key = round_key(cell_id, round_num, player);
+            if self.reveal_deadlines.get(key).is_zero() {
+                let deadline = U256::from(self.vm().block_timestamp() + REVEAL_WINDOW);
+                self.reveal_deadlines.setter(key).set(deadline);
             }
-            cell.continuation_flags |= 8; // Mark P2 as decided
         }
-        
-        // Check if BOTH players have decided
-        let p1_decided = (cell.continuation_flags & 4) != 0;
-        let p2_decided = (cell.continuation_flags & 8) != 0;
-        
-        if p1_decided && p2_decided {
-            let p1_wants = (cell.continuation_flags & 1) != 0;
-            let p2_wants = (cell.continuation_flags & 2) != 0;
-
-            if p1_wants && p2_wants && cell.current_round < cell.total_rounds {
-                // Both want to continue - create next round
-                cell.current_round += 1;
-                cell.rounds.push(Round {
-                    player1_move: None,
-                    player2_move: None,
-                    player1_payout: U256::ZERO,
-                    player2_payout: U256::ZERO,
-                    is_finished: false,
-                });
-                cell.continuation_flags = 0; // Reset all flags
+
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Propose raising the stake from the next round on to `stake`, sending the
+    // top-up over the current stake. Only while the current round waits on
+    // continuation votes; the raise applies if the opponent calls
+    // `accept_raise` before the next round opens, and otherwise the top-up is
+    // credited back to your withdrawable balance
+    #[payable]
+    pub fn propose_raise(&mut self, cell_id: U256, stake: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let deposit = self.vm().msg_value();
+        let cell = self.load_cell(cell_id);
+
+        let (cell, effects) = self.apply(cell_id, cell, Action::ProposeRaise { player: sender, stake, deposit })?;
+
+        self.deposit(cell_id, deposit);
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Match the opponent's pending raise by sending the same top-up, see `get_pending_raise`
+    #[payable]
+    pub fn accept_raise(&mut self, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let deposit = self.vm().msg_value();
+        let cell = self.load_cell(cell_id);
+
+        let (cell, effects) = self.apply(cell_id, cell, Action::AcceptRaise { player: sender, deposit })?;
+
+        self.deposit(cell_id, deposit);
+        self.finish_turn(cell_id, &cell, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Send the sender everything streamed cells, lapsed raises and failed
+    // payouts have credited them, returning the amount
+    pub fn withdraw(&mut self) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let amount = self.balances.get(sender);
+        if amount.is_zero() {
+            return Err(PrisonersDilemmaErrors::NothingToWithdraw(NothingToWithdraw { cell_id: U256::ZERO }));
+        }
+
+        self.balances.delete(sender);
+        self.release(amount);
+        if self.vm().transfer_eth(sender, amount).is_err() {
+            return Err(PrisonersDilemmaErrors::TransferFailed(TransferFailed { cell_id: U256::ZERO }));
+        }
+        stylus_core::log(self.vm(), Withdrawn { player: sender, amount });
+        self.assert_solvent();
+        self.unlock();
+        Ok(amount)
+    }
+
+    // Offer the opponent of a completed cell another game with the same
+    // settings and stake. The offer is a new open cell kept out of the lobby
+    // and reserved for the opponent, who joins it with `accept_rematch`;
+    // `cancel_cell` withdraws it. Returns the new cell's id
+    #[payable]
+    pub fn offer_rematch(&mut self, previous_cell_id: U256) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let stake = self.vm().msg_value();
+        let previous = self.load_rematchable(previous_cell_id, sender)?;
+
+        // A cancelled offer can be made again
+        let next = self.next_cell.get(previous_cell_id);
+        if !next.is_zero() && self.load_cell(next).state != CellState::Cancelled {
+            return Err(PrisonersDilemmaErrors::RematchUnavailable(RematchUnavailable { cell_id: previous_cell_id }));
+        }
+        // The new cell is created at this stake, so it has to meet the minimum
+        if stake < self.min_stake.get() {
+            return Err(PrisonersDilemmaErrors::StakeTooLow(StakeTooLow { cell_id: previous_cell_id }));
+        }
+        if stake != previous.stake_amount {
+            return Err(PrisonersDilemmaErrors::WrongStake(WrongStake { cell_id: previous_cell_id }));
+        }
+
+        let (total_rounds, noise_bps, continuation_bps) = (previous.total_rounds, previous.noise_bps, previous.continuation_bps);
+        let cell_id = self.open_cell(|player, stake| {
+            if previous.streamed {
+                Action::CreateStreamed { player, stake, total_rounds, noise_bps, continuation_bps }
             } else {
-                // At least one doesn't want to continue or max rounds reached - end cell
-                self.complete_cell(&mut cell, cell_id);
+                Action::Create { player, stake, total_rounds, noise_bps, continuation_bps }
             }
+        })?;
+        self.close_open_cell(cell_id);
+
+        let opponent = if previous.player1 == sender { previous.player2 } else { previous.player1 };
+        self.rematch_opponents.setter(cell_id).set(opponent);
+        self.previous_cell.setter(cell_id).set(previous_cell_id);
+        self.next_cell.setter(previous_cell_id).set(cell_id);
+        stylus_core::log(self.vm(), RematchOffered { previous_cell_id, cell_id, player: sender });
+        self.unlock();
+        Ok(cell_id)
+    }
+
+    // Accept the opponent's rematch offer, matching its stake. The new cell
+    // starts immediately with the offering player as player 1
+    #[payable]
+    pub fn accept_rematch(&mut self, previous_cell_id: U256) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let cell_id = self.next_cell.get(previous_cell_id);
+        if cell_id.is_zero() || self.rematch_opponents.get(cell_id) != self.vm().msg_sender() {
+            return Err(PrisonersDilemmaErrors::NoRematchOffer(NoRematchOffer { cell_id: previous_cell_id }));
         }
-        
-    self.store_cell(cell_id, &cell);
-    Ok(())
+        self.take_seat(cell_id, |player, stake| Action::Join { player, stake })?;
+        self.unlock();
+        Ok(cell_id)
+    }
+
+    // Public goods cells for `max_players` players (3-10). The pot is multiplied
+    // by `multiplier_bps` (basis points, strictly between 1x and `max_players`x).
+    // Totals beyond the players' stakes are scaled down to fit them
+    #[payable]
+    pub fn create_group_cell(&mut self, max_players: u8, total_rounds: u8, multiplier_bps: u32) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let stake = self.vm().msg_value();
+
+        let action = GroupAction::Create { player: sender, stake, max_players, total_rounds, multiplier_bps };
+        let (cell, effects) = self.apply_group(U256::ZERO, GroupCell::default(), action)?;
+        self.check_active_capacity(sender, U256::ZERO)?;
+
+        let cell_id = self.cell_counter.get() + U256::from(1);
+        self.cell_counter.set(cell_id);
+
+        self.store_group_cell(cell_id, &cell);
+        self.enter_cell(sender, cell_id);
+        self.deposit(cell_id, stake);
+
+        self.run_effects(cell_id, &cell.players, effects);
+        self.unlock();
+        Ok(cell_id)
+    }
+
+    #[payable]
+    pub fn join_group_cell(&mut self, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let stake = self.vm().msg_value();
+
+        self.check_active_capacity(sender, cell_id)?;
+
+        let cell = self.load_group_cell(cell_id);
+        let (cell, effects) = self.apply_group(cell_id, cell, GroupAction::Join { player: sender, stake })?;
+
+        self.store_group_cell(cell_id, &cell);
+        self.enter_cell(sender, cell_id);
+        self.deposit(cell_id, stake);
+
+        self.run_effects(cell_id, &cell.players, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Withdraw a group cell that has not filled up; everyone who joined is refunded.
+    // Only the creator can cancel, and only until the last seat is taken
+    pub fn cancel_group_cell(&mut self, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_group_cell(cell_id);
+        let (cell, effects) = self.apply_group(cell_id, cell, GroupAction::Cancel { player: sender })?;
+
+        self.store_group_cell(cell_id, &cell);
+        self.run_effects(cell_id, &cell.players, effects);
+        stylus_core::log(self.vm(), CellCancelled { cell_id });
+        self.unlock();
+        Ok(())
+    }
+
+    // Contribute to the pot (true) or free-ride (false) this round
+    pub fn submit_contribution(&mut self, cell_id: U256, contributes: bool) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_group_cell(cell_id);
+
+        let player_move = if contributes { Move::Cooperate } else { Move::Defect };
+        let action = GroupAction::SubmitMove { player: sender, player_move };
+        let (cell, effects) = self.apply_group(cell_id, cell, action)?;
+
+        self.store_group_cell(cell_id, &cell);
+        self.run_effects(cell_id, &cell.players, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // A single vote against continuing ends the game for every player
+    pub fn submit_group_continuation_decision(&mut self, cell_id: U256, wants_continue: bool) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let cell = self.load_group_cell(cell_id);
+
+        let action = GroupAction::ContinuationDecision { player: sender, wants_continue };
+        let (cell, effects) = self.apply_group(cell_id, cell, action)?;
+
+        self.store_group_cell(cell_id, &cell);
+        self.run_effects(cell_id, &cell.players, effects);
+        self.unlock();
+        Ok(())
+    }
+
+    // Back an outcome bucket of a cell that is still waiting for its second player
+    // (0=mutual cooperation, 1=any defection, 2=player 1 nets more, 3=player 2 nets more, 4=even)
+    #[payable]
+    pub fn place_wager(&mut self, cell_id: U256, bucket: u8) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+        let amount = self.vm().msg_value();
+
+        if Bucket::from_u8(bucket).is_none() {
+            return Err(PrisonersDilemmaErrors::InvalidBucket(InvalidBucket { cell_id }));
+        }
+        let cell = self.load_cell(cell_id);
+        if cell.player1 == Address::ZERO || cell.player2 != Address::ZERO || cell.is_complete {
+            return Err(PrisonersDilemmaErrors::BettingClosed(BettingClosed { cell_id }));
+        }
+        if cell.player1 == sender {
+            return Err(PrisonersDilemmaErrors::PlayerCannotBet(PlayerCannotBet { cell_id }));
+        }
+        if amount.is_zero() {
+            return Err(PrisonersDilemmaErrors::StakeTooLow(StakeTooLow { cell_id }));
+        }
+
+        let key = wager_key(cell_id, Some(sender), bucket);
+        let stake = self.wagers.get(key);
+        self.wagers.setter(key).set(stake + amount);
+        let pool_key = wager_key(cell_id, None, bucket);
+        let pool = self.wager_pools.get(pool_key);
+        self.wager_pools.setter(pool_key).set(pool + amount);
+        self.escrow(amount);
+
+        stylus_core::log(self.vm(), WagerPlaced { cell_id, bettor: sender, bucket, amount });
+        self.unlock();
+        Ok(())
+    }
+
+    // Pays out the caller's winnings (or refunds) from both markets of a settled cell
+    pub fn claim_wager(&mut self, cell_id: U256) -> Result<U256, PrisonersDilemmaErrors> {
+        self.lock()?;
+        let sender = self.vm().msg_sender();
+
+        let result = self.wager_results.get(cell_id).to::<u8>();
+        if result & WAGERS_SETTLED == 0 {
+            return Err(PrisonersDilemmaErrors::WagersNotSettled(WagersNotSettled { cell_id }));
+        }
+        let stakes = self.wagers_of(cell_id, sender);
+        if stakes.iter().all(|stake| stake.is_zero()) {
+            return Err(PrisonersDilemmaErrors::NoWager(NoWager { cell_id }));
+        }
+
+        let pools = self.wager_pools_of(cell_id);
+        let amount = market_payout(Market::Cooperation, &stakes, &pools, result)
+            + market_payout(Market::Winner, &stakes, &pools, result);
+        for bucket in Bucket::ALL {
+            self.wagers.setter(wager_key(cell_id, Some(sender), bucket as u8)).set(U256::ZERO);
+        }
+
+        // Rounding dust of the parimutuel split stays escrowed
+        self.release(amount);
+        if !amount.is_zero() && self.vm().transfer_eth(sender, amount).is_err() {
+            return Err(PrisonersDilemmaErrors::TransferFailed(TransferFailed { cell_id }));
+        }
+        self.assert_solvent();
+        stylus_core::log(self.vm(), WagerClaimed { cell_id, bettor: sender, amount });
+        self.unlock();
+        Ok(amount)
     }
 
     // Getters
@@ -320,8 +926,41 @@ impl PrisonersDilemma {
         (cell.player1, cell.player2, cell.stake_amount, cell.total_rounds, cell.current_round, cell.is_complete)
     }
 
+    // Full state of up to 50 two-player cells in one call, in the order given.
+    // Unknown ids come back with a zero player 1. Rounds are listed after the
+    // cells, each cell's `rounds_count` rounds in turn
+    pub fn get_cells(&self, ids: Vec<U256>) -> Result<(Vec<CellView>, Vec<RoundView>), PrisonersDilemmaErrors> {
+        if ids.len() > MAX_BATCH_CELLS {
+            return Err(PrisonersDilemmaErrors::TooManyCells(TooManyCells { cell_id: U256::ZERO }));
+        }
+        let mut cells = Vec::with_capacity(ids.len());
+        let mut rounds = Vec::new();
+        for cell_id in ids {
+            let cell = self.load_cell(cell_id);
+            let deadlines = (self.channel_deadlines.get(cell_id), self.reveal_deadline(cell_id, &cell));
+            cells.push(cell_view(cell_id, &cell, deadlines));
+            rounds.extend(cell.rounds.iter().map(|round| round_view(&cell, round)));
+        }
+        Ok((cells, rounds))
+    }
+
+    // The cell the player entered most recently among their active cells, or 0
     pub fn get_player_cell(&self, player: Address) -> U256 {
-        self.player_to_cell.get(player)
+        let cells = self.active_cells.get(player);
+        cells.get(cells.len().wrapping_sub(1)).unwrap_or_default()
+    }
+
+    // Every cell the player is currently in, in the order they entered them
+    pub fn get_player_active_cells(&self, player: Address) -> Vec<U256> {
+        let cells = self.active_cells.get(player);
+        (0..cells.len()).filter_map(|i| cells.get(i)).collect()
+    }
+
+    pub fn get_max_active_cells(&self) -> u32 {
+        match self.max_active_cells.get().to::<u32>() {
+            0 => DEFAULT_MAX_ACTIVE_CELLS,
+            cap => cap,
+        }
     }
 
     pub fn get_players_cell(&self, player1: Address, player2: Address) -> U256 {
@@ -329,6 +968,75 @@ impl PrisonersDilemma {
         self.players_to_cell.get(key.into())
     }
 
+    // A page of every cell the player has created or joined, oldest first
+    pub fn get_player_cells(&self, player: Address, offset: U256, limit: U256) -> Vec<U256> {
+        Self::page(&self.player_cells.get(player), offset, limit)
+    }
+
+    pub fn get_player_cell_count(&self, player: Address) -> U256 {
+        U256::from(self.player_cells.get(player).len())
+    }
+
+    // A page of every two-player cell between `a` and `b`, in either seat, oldest first
+    pub fn get_pair_cells(&self, a: Address, b: Address, offset: U256, limit: U256) -> Vec<U256> {
+        let key = self.hash_players(a, b);
+        Self::page(&self.pair_cells.get(key.into()), offset, limit)
+    }
+
+    pub fn get_pair_cell_count(&self, a: Address, b: Address) -> U256 {
+        let key = self.hash_players(a, b);
+        U256::from(self.pair_cells.get(key.into()).len())
+    }
+
+    // A page of the cells waiting for an opponent whose stake is within
+    // [min_stake, max_stake] (0 = no upper bound). `offset` counts matching cells.
+    // Cells leave the lobby by swapping with the last one, so the order is not
+    // stable between pages. Returns (cell_ids, creators, stakes, total_rounds,
+    // noise_bps, continuation_bps)
+    #[allow(clippy::type_complexity)]
+    pub fn get_open_cells(
+        &self,
+        offset: U256,
+        limit: U256,
+        min_stake: U256,
+        max_stake: U256,
+    ) -> (Vec<U256>, Vec<Address>, Vec<U256>, Vec<u8>, Vec<u16>, Vec<u16>) {
+        let mut page = (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut skip = offset.saturating_to::<usize>();
+        let limit = limit.saturating_to::<usize>().min(MAX_PAGE_SIZE);
+
+        for i in 0..self.open_cells.len() {
+            if page.0.len() >= limit {
+                break;
+            }
+            let Some(cell_id) = self.open_cells.get(i) else { continue };
+            let cell = self.load_cell(cell_id);
+            if cell.stake_amount < min_stake || (!max_stake.is_zero() && cell.stake_amount > max_stake) {
+                continue;
+            }
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            page.0.push(cell_id);
+            page.1.push(cell.player1);
+            page.2.push(cell.stake_amount);
+            page.3.push(cell.total_rounds);
+            page.4.push(cell.noise_bps);
+            page.5.push(cell.continuation_bps);
+        }
+        page
+    }
+
+    pub fn get_open_cell_count(&self) -> U256 {
+        U256::from(self.open_cells.len())
+    }
+
+    // Returns (previous_cell_id, next_cell_id) in a series of rematches, 0 where there is none
+    pub fn get_rematch_links(&self, cell_id: U256) -> (U256, U256) {
+        (self.previous_cell.get(cell_id), self.next_cell.get(cell_id))
+    }
+
     pub fn get_min_stake(&self) -> U256 {
         self.min_stake.get()
     }
@@ -340,12 +1048,87 @@ impl PrisonersDilemma {
     // Get continuation decision status for a cell
     // Returns (player1_decided, player1_wants, player2_decided, player2_wants)
     pub fn get_continuation_status(&self, cell_id: U256) -> (bool, bool, bool, bool) {
+        self.load_cell(cell_id).continuation_status()
+    }
+
+    // A player's continuation policy in a cell as (policy, param), see
+    // `set_continuation_policy`; (0, 0) for anyone not in the cell
+    pub fn get_continuation_policy(&self, cell_id: U256, player: Address) -> (u8, I256) {
+        let cell = self.load_cell(cell_id);
+        let policy = if player == cell.player1 {
+            cell.player1_policy
+        } else if player == cell.player2 {
+            cell.player2_policy
+        } else {
+            ContinuationPolicy::Manual
+        };
+        (policy.kind(), policy.param())
+    }
+
+    // Lifecycle state of a two-player cell: 0 open, 1 awaiting moves, 2
+    // awaiting continuation decisions, 3 completed, 4 cancelled, 5 timed out,
+    // 6 disputed
+    pub fn get_cell_state(&self, cell_id: U256) -> u8 {
+        self.load_cell(cell_id).state as u8
+    }
+
+    // What each player has earned so far, would be sent if the cell ended
+    // now, and could end up with at best and at worst if every round up to
+    // `total_rounds` is played, as (player1, player2). Uses the same
+    // settlement as cell completion, so the amounts match what is paid
+    pub fn preview_settlement(&self, cell_id: U256) -> (SettlementView, SettlementView) {
         let cell = self.load_cell(cell_id);
-        let p1_decided = (cell.continuation_flags & 4) != 0;
-        let p1_wants = (cell.continuation_flags & 1) != 0;
-        let p2_decided = (cell.continuation_flags & 8) != 0;
-        let p2_wants = (cell.continuation_flags & 2) != 0;
-        (p1_decided, p1_wants, p2_decided, p2_wants)
+        let (p1, p2) = preview_settlement(&cell, &self.context().payoffs);
+        let view = |p: SettlementPreview| (p.accumulated, p.if_ended, p.best_case, p.worst_case);
+        (view(p1), view(p2))
+    }
+
+    // The message both players sign for a channel state of a cell, an
+    // EIP-191 personal message hash bound to this contract and chain
+    pub fn get_channel_digest(&self, state: Bytes) -> Result<FixedBytes<32>, PrisonersDilemmaErrors> {
+        let state = ChannelState::decode(&state)
+            .ok_or(PrisonersDilemmaErrors::InvalidChannelState(InvalidChannelState { cell_id: U256::ZERO }))?;
+        Ok(channel_digest(self.vm().contract_address(), self.vm().chain_id(), &state))
+    }
+
+    // When the challenge window of a disputed cell closes, 0 if none is open
+    pub fn get_challenge_deadline(&self, cell_id: U256) -> U256 {
+        self.channel_deadlines.get(cell_id)
+    }
+
+    // When the window of the current round closes once one player has
+    // committed or revealed in it and their opponent has not, 0 otherwise
+    pub fn get_reveal_deadline(&self, cell_id: U256) -> U256 {
+        self.reveal_deadline(cell_id, &self.load_cell(cell_id))
+    }
+
+    // Roots of (player1, player2)'s sealed strategies, zero for unsealed cells
+    pub fn get_sealed_roots(&self, cell_id: U256) -> (FixedBytes<32>, FixedBytes<32>) {
+        let cell = self.load_cell(cell_id);
+        (cell.player1_root, cell.player2_root)
+    }
+
+    // What both players' sealed leaves are bound to, zero for unsealed cells
+    pub fn get_cell_seal(&self, cell_id: U256) -> FixedBytes<32> {
+        self.load_cell(cell_id).seal
+    }
+
+    // What streamed cells, lapsed raises and failed payouts have credited `player` that `withdraw` would send
+    pub fn get_withdrawable(&self, player: Address) -> U256 {
+        self.balances.get(player)
+    }
+
+    // Whether a cell credits round payouts as they resolve
+    pub fn get_cell_streamed(&self, cell_id: U256) -> bool {
+        self.load_cell(cell_id).streamed
+    }
+
+    // The raise pending on a cell as (stake, top_up, player1_deposited,
+    // player2_deposited); all zero when none is proposed
+    pub fn get_pending_raise(&self, cell_id: U256) -> (U256, U256, bool, bool) {
+        let cell = self.load_cell(cell_id);
+        let flags = cell.raise_flags;
+        (cell.raise_stake, cell.raise_top_up(), flags & P1_RAISED != 0, flags & P2_RAISED != 0)
     }
 
     pub fn get_cell_counter(&self) -> U256 {
@@ -370,6 +1153,131 @@ impl PrisonersDilemma {
         
         (p1_move, p2_move, round.player1_payout, round.player2_payout)
     }
+
+    // Returns (players, max_players, stake, multiplier_bps, total_rounds, current_round, is_complete, continue_votes)
+    pub fn get_group_cell(&self, cell_id: U256) -> (Vec<Address>, u8, U256, u32, u8, u8, bool, u16) {
+        let cell = self.load_group_cell(cell_id);
+        (
+            cell.players,
+            cell.max_players,
+            cell.stake_amount,
+            cell.multiplier_bps,
+            cell.total_rounds,
+            cell.current_round,
+            cell.is_complete,
+            cell.continue_votes,
+        )
+    }
+
+    // Returns each player's move (0=contribute, 1=free-ride) and payout, empty until the round is finished
+    pub fn get_group_round_result(&self, cell_id: U256, round_number: u8) -> (Vec<u8>, Vec<U256>) {
+        let cell = self.load_group_cell(cell_id);
+        let round = (round_number as usize).checked_sub(1).and_then(|idx| cell.rounds.get(idx));
+
+        match round {
+            Some(round) if round.is_finished => (
+                round.moves.iter().map(|m| m.unwrap_or(Move::Cooperate) as u8).collect(),
+                round.payouts.clone(),
+            ),
+            _ => (Vec::new(), Vec::new()),
+        }
+    }
+
+    // Returns the moves the players chose and the moves executed after noise:
+    // (player1_intended, player2_intended, player1_executed, player2_executed)
+    pub fn get_round_moves(&self, cell_id: U256, round_number: u8) -> (u8, u8, u8, u8) {
+        let cell = self.load_cell(cell_id);
+        let round = (round_number as usize).checked_sub(1).and_then(|idx| cell.rounds.get(idx));
+
+        match round {
+            Some(round) if round.is_finished => {
+                let (p1_intended, p2_intended) = round.intended_moves();
+                let as_u8 = |m: Option<Move>| m.unwrap_or(Move::Cooperate) as u8;
+                (as_u8(p1_intended), as_u8(p2_intended), as_u8(round.player1_move), as_u8(round.player2_move))
+            }
+            _ => (0, 0, 0, 0),
+        }
+    }
+
+    // Probability, in basis points, that a move in this cell is flipped
+    pub fn get_cell_noise(&self, cell_id: U256) -> u16 {
+        self.load_cell(cell_id).noise_bps
+    }
+
+    // Probability δ, in basis points, that a cell continues after each round (0 = voting)
+    pub fn get_continuation_probability(&self, cell_id: U256) -> u16 {
+        self.load_cell(cell_id).continuation_bps
+    }
+
+    // The draws (0-9999) made after each round of a δ cell; the game continued while draw < δ
+    pub fn get_continuation_draws(&self, cell_id: U256) -> Vec<u16> {
+        self.load_cell(cell_id).continuation_draws
+    }
+
+    // The commitment `commit_move` expects for a move and salt
+    pub fn get_move_commitment(&self, cell_id: U256, player: Address, move_choice: u8, salt: FixedBytes<32>) -> FixedBytes<32> {
+        let mut data = Vec::with_capacity(85);
+        data.extend_from_slice(&cell_id.to_be_bytes::<32>());
+        data.extend_from_slice(player.as_slice());
+        data.push(move_choice);
+        data.extend_from_slice(salt.as_slice());
+        keccak256(&data)
+    }
+
+    // Total staked on each bucket of a cell
+    pub fn get_wager_pools(&self, cell_id: U256) -> Vec<U256> {
+        self.wager_pools_of(cell_id).to_vec()
+    }
+
+    // A bettor's unclaimed stake on each bucket of a cell
+    pub fn get_wagers(&self, cell_id: U256, bettor: Address) -> Vec<U256> {
+        self.wagers_of(cell_id, bettor).to_vec()
+    }
+
+    // Returns (settled, winning bucket bitmask); no winning buckets means both markets refund
+    pub fn get_wager_result(&self, cell_id: U256) -> (bool, u8) {
+        let result = self.wager_results.get(cell_id).to::<u8>();
+        (result & WAGERS_SETTLED != 0, result & !WAGERS_SETTLED)
+    }
+
+    // Escrow ledger of a cell: (deposited, paid, outstanding). Outstanding is
+    // what the cell still holds of its deposits, and is zero once it completes.
+    // Paid counts payouts, streamed credits and refunds, all drawn from
+    // outstanding, so a cell never pays out more than was deposited. Payouts
+    // whose transfer failed count as paid and wait in the player's balance
+    pub fn get_cell_escrow(&self, cell_id: U256) -> (U256, U256, U256) {
+        (self.cell_stakes.get(cell_id), self.cell_paid.get(cell_id), self.cell_outstanding.get(cell_id))
+    }
+
+    // Everything held for others: live cell deposits, unpaid payouts,
+    // unclaimed wagers and withdrawable balances
+    pub fn get_total_escrowed(&self) -> U256 {
+        self.total_escrowed.get()
+    }
+
+    // Returns (solvent, liabilities, balance): whether the contract's balance
+    // covers everything it holds in escrow
+    pub fn check_solvency(&self) -> (bool, U256, U256) {
+        let liabilities = self.total_escrowed.get();
+        let balance = self.vm().balance(self.vm().contract_address());
+        (balance >= liabilities, liabilities, balance)
+    }
+
+    // Owner only: the companion contract that mints achievement badges (zero
+    // stops minting)
+    pub fn set_badge_contract(&mut self, badges: Address) -> Result<(), PrisonersDilemmaErrors> {
+        self.lock()?;
+        if self.vm().msg_sender() != self.owner.get() {
+            return Err(PrisonersDilemmaErrors::NotOwner(NotOwner { cell_id: U256::ZERO }));
+        }
+        self.badge_contract.set(badges);
+        self.unlock();
+        Ok(())
+    }
+
+    pub fn get_badge_contract(&self) -> Address {
+        self.badge_contract.get()
+    }
 }
 
 // Private helper methods
@@ -383,67 +1291,359 @@ impl PrisonersDilemma {
         keccak256(&data).into()
     }
 
-    fn resolve_round(&mut self, cell: &mut Cell, cell_id: U256, round_idx: usize) {
-        let round = &mut cell.rounds[round_idx];
-        let p1_move = round.player1_move.unwrap();
-        let p2_move = round.player2_move.unwrap();
-        let stake = cell.stake_amount;
-        
-        // Simplified payoff calculation
-        let (p1_payout, p2_payout) = match (p1_move, p2_move) {
-            (Move::Cooperate, Move::Cooperate) => (stake, stake),
-            (Move::Defect, Move::Defect) => (stake / U256::from(2), stake / U256::from(2)),
-            (Move::Cooperate, Move::Defect) => (stake / U256::from(2), stake + stake / U256::from(2)),
-            (Move::Defect, Move::Cooperate) => (stake + stake / U256::from(2), stake / U256::from(2)),
+    // Runs an action through the game rules, tagging any error with the cell id
+    fn apply(&self, cell_id: U256, cell: Cell, action: Action) -> Result<(Cell, Vec<Effect>), PrisonersDilemmaErrors> {
+        apply(cell, action, &self.context()).map_err(|err| contract_error(cell_id, err))
+    }
+
+    fn apply_group(&self, cell_id: U256, cell: GroupCell, action: GroupAction) -> Result<(GroupCell, Vec<Effect>), PrisonersDilemmaErrors> {
+        apply_group(cell, action, &self.context()).map_err(|err| contract_error(cell_id, err))
+    }
+
+    fn context(&self) -> Context {
+        Context { min_stake: self.min_stake.get(), payoffs: PayoffMatrix::CONTRACT }
+    }
+
+    // Carries out the effects returned by the game rules: events, payouts and
+    // clearing the player index once a cell is complete. Callers store the
+    // cell first, and payouts are sent only after every other state change
+    fn run_effects(&mut self, cell_id: U256, players: &[Address], effects: Vec<Effect>) {
+        let mut payouts = Vec::new();
+        let mut completed = false;
+        for effect in effects {
+            match effect {
+                Effect::CellCreated { player1, stake } => {
+                    stylus_core::log(self.vm(), CellCreated { cell_id, player1, stake });
+                }
+                Effect::PlayerJoined { player2 } => {
+                    stylus_core::log(self.vm(), PlayerJoined { cell_id, player2 });
+                }
+                Effect::GroupPlayerJoined { player } => {
+                    stylus_core::log(self.vm(), GroupPlayerJoined { cell_id, player });
+                }
+                Effect::RoundComplete { round_num } => {
+                    stylus_core::log(self.vm(), RoundComplete { cell_id, round_num });
+                }
+                Effect::ContinuationDrawn { round_num, draw, continues } => {
+                    stylus_core::log(self.vm(), ContinuationDrawn { cell_id, round_num, draw, continues });
+                }
+                Effect::ContinuationPolicySet { player, policy } => {
+                    let (policy, param) = (policy.kind(), policy.param());
+                    stylus_core::log(self.vm(), ContinuationPolicySet { cell_id, player, policy, param });
+                }
+                Effect::ChannelStateSubmitted { rounds } => {
+                    let challenge_deadline = self.channel_deadlines.get(cell_id);
+                    stylus_core::log(self.vm(), ChannelStateSubmitted { cell_id, rounds, challenge_deadline });
+                }
+                Effect::StrategySealed { player, root } => {
+                    stylus_core::log(self.vm(), StrategySealed { cell_id, player, root });
+                }
+                Effect::SealedMovesRevealed { player, count } => {
+                    stylus_core::log(self.vm(), SealedMovesRevealed { cell_id, player, count });
+                }
+                Effect::StateChanged { from, to } => {
+                    stylus_core::log(self.vm(), CellStateChanged { cell_id, from: from as u8, to: to as u8 });
+                }
+                Effect::Payout { player, amount } => payouts.push((player, amount)),
+                Effect::Credit { player, round_num, amount } => {
+                    let amount = self.credit(cell_id, player, amount);
+                    stylus_core::log(self.vm(), PayoutCredited { cell_id, player, round_num, amount });
+                }
+                Effect::RaiseProposed { player, stake } => {
+                    stylus_core::log(self.vm(), RaiseProposed { cell_id, player, stake });
+                }
+                Effect::RaiseAccepted { player, stake } => {
+                    stylus_core::log(self.vm(), RaiseAccepted { cell_id, player, stake });
+                }
+                Effect::RaiseRefunded { player, amount } => {
+                    let amount = self.credit(cell_id, player, amount);
+                    stylus_core::log(self.vm(), RaiseRefunded { cell_id, player, amount });
+                }
+                Effect::BadgeEarned { player, badge } => {
+                    self.mint_badge(cell_id, player, badge);
+                }
+                Effect::CellComplete => {
+                    for &player in players {
+                        self.remove_active_cell(player, cell_id);
+                    }
+                    stylus_core::log(self.vm(), CellComplete { cell_id });
+                    completed = true;
+                }
+            }
+        }
+
+        // Payouts leave the cell's escrow before anything is sent
+        let payouts: Vec<_> = payouts.into_iter().map(|(player, amount)| (player, self.draw(cell_id, amount))).collect();
+        for &(_, amount) in &payouts {
+            self.release(amount);
+        }
+        if completed {
+            // Whatever the payouts left in the pot is the contract's to keep
+            let outstanding = self.cell_outstanding.get(cell_id);
+            self.release(outstanding);
+            self.cell_outstanding.delete(cell_id);
+        }
+
+        // A payout whose transfer fails is credited to the player to withdraw
+        for (player, amount) in payouts {
+            if !amount.is_zero() && self.vm().transfer_eth(player, amount).is_err() {
+                let balance = self.balances.get(player);
+                self.balances.setter(player).set(balance + amount);
+                self.escrow(amount);
+            }
+        }
+        if completed {
+            self.assert_solvent();
+        }
+    }
+
+    // Guards every mutating entry point. The SDK's entrypoint already rejects
+    // reentrant calls unless the contract is built with `reentrant`; this keeps
+    // settlement safe either way. An error reverts the lock along with the rest
+    fn lock(&mut self) -> Result<(), PrisonersDilemmaErrors> {
+        if self.reentrancy_lock.get() {
+            return Err(PrisonersDilemmaErrors::Reentrancy(Reentrancy { cell_id: U256::ZERO }));
+        }
+        self.reentrancy_lock.set(true);
+        Ok(())
+    }
+
+    fn unlock(&mut self) {
+        self.reentrancy_lock.set(false);
+    }
+
+    // The signer of a 65-byte (r, s, v) signature over `digest`, through the
+    // ecrecover precompile
+    fn recover(&self, digest: FixedBytes<32>, signature: &[u8]) -> Option<Address> {
+        let [sig @ .., v] = <[u8; 65]>::try_from(signature).ok()?;
+        let v = if v < 27 { v + 27 } else { v };
+        let mut input = Vec::with_capacity(128);
+        input.extend_from_slice(digest.as_slice());
+        input.extend_from_slice(&U256::from(v).to_be_bytes::<32>());
+        input.extend_from_slice(&sig);
+        let output = self.vm().static_call(&Call::new(), ECRECOVER, &input).ok()?;
+        let signer = Address::from_slice(output.get(12..32)?);
+        (!signer.is_zero()).then_some(signer)
+    }
+
+    // Records a stake paid into a cell
+    fn deposit(&mut self, cell_id: U256, amount: U256) {
+        let deposited = self.cell_stakes.get(cell_id);
+        self.cell_stakes.setter(cell_id).set(deposited + amount);
+        let outstanding = self.cell_outstanding.get(cell_id);
+        self.cell_outstanding.setter(cell_id).set(outstanding + amount);
+        self.escrow(amount);
+    }
+
+    // Moves a streamed round payout, or a lapsed raise's top-up, from the cell
+    // to the player's balance, returning the amount credited. Both stay escrowed
+    fn credit(&mut self, cell_id: U256, player: Address, amount: U256) -> U256 {
+        let amount = self.draw(cell_id, amount);
+        let balance = self.balances.get(player);
+        self.balances.setter(player).set(balance + amount);
+        amount
+    }
+
+    // Takes up to `amount` out of what the cell holds and counts it as paid,
+    // returning what was taken. A cell never pays out more than was deposited into it
+    fn draw(&mut self, cell_id: U256, amount: U256) -> U256 {
+        let outstanding = self.cell_outstanding.get(cell_id);
+        let amount = amount.min(outstanding);
+        self.cell_outstanding.setter(cell_id).set(outstanding - amount);
+        let paid = self.cell_paid.get(cell_id);
+        self.cell_paid.setter(cell_id).set(paid + amount);
+        amount
+    }
+
+    fn escrow(&mut self, amount: U256) {
+        let total = self.total_escrowed.get();
+        self.total_escrowed.set(total + amount);
+    }
+
+    // Releasing more than is escrowed means the ledger is wrong, so it traps
+    fn release(&mut self, amount: U256) {
+        let total = self.total_escrowed.get();
+        let total = total.checked_sub(amount).expect("released more than is escrowed");
+        self.total_escrowed.set(total);
+    }
+
+    // Debug builds trap on any settlement that leaves escrow uncovered
+    fn assert_solvent(&self) {
+        #[cfg(feature = "debug")]
+        {
+            let (solvent, liabilities, balance) = self.check_solvency();
+            assert!(solvent, "insolvent: {liabilities} escrowed against a balance of {balance}");
+        }
+    }
+
+    // Asks the badge companion to mint `badge` to `player`. A failed mint
+    // never holds up the game, so its result is ignored
+    fn mint_badge(&mut self, cell_id: U256, player: Address, badge: Badge) {
+        let badges = self.badge_contract.get();
+        if badges.is_zero() {
+            return;
+        }
+        let mint = IPrisonersDilemmaBadges::mintCall { cell_id, player, badge: badge as u8 };
+        let _ = self.vm().call(&Call::new(), badges, &mint.abi_encode());
+    }
+
+    // A completed two-player cell that `player` took part in
+    fn load_rematchable(&self, cell_id: U256, player: Address) -> Result<Cell, PrisonersDilemmaErrors> {
+        let cell = self.load_cell(cell_id);
+        if cell.player1 == Address::ZERO {
+            return Err(PrisonersDilemmaErrors::CellNotFound(CellNotFound { cell_id }));
+        }
+        if !cell.is_player(player) || cell.player2 == Address::ZERO {
+            return Err(PrisonersDilemmaErrors::NotInCell(NotInCell { cell_id }));
+        }
+        if !cell.is_complete {
+            return Err(PrisonersDilemmaErrors::CellNotComplete(CellNotComplete { cell_id }));
+        }
+        Ok(cell)
+    }
+
+    // Rejects a player who is already in as many cells as the cap allows
+    fn check_active_capacity(&self, player: Address, cell_id: U256) -> Result<(), PrisonersDilemmaErrors> {
+        if self.active_cells.get(player).len() >= self.get_max_active_cells() as usize {
+            return Err(PrisonersDilemmaErrors::TooManyActiveCells(TooManyActiveCells { cell_id }));
+        }
+        Ok(())
+    }
+
+    // Adds a cell to the player's active set and to their permanent history
+    fn enter_cell(&mut self, player: Address, cell_id: U256) {
+        self.active_cells.setter(player).push(cell_id);
+        self.player_cells.setter(player).push(cell_id);
+    }
+
+    // Points the pair at its latest cell and appends it to the pair's history
+    fn record_pair(&mut self, player1: Address, player2: Address, cell_id: U256) {
+        let key: FixedBytes<32> = self.hash_players(player1, player2).into();
+        self.players_to_cell.setter(key).set(cell_id);
+        self.pair_cells.setter(key).push(cell_id);
+    }
+
+    // One page of an append-only cell index
+    fn page(cells: &StorageVec<StorageU256>, offset: U256, limit: U256) -> Vec<U256> {
+        let len = cells.len();
+        let start = offset.saturating_to::<usize>().min(len);
+        let count = limit.saturating_to::<usize>().min(MAX_PAGE_SIZE);
+        (start..len.min(start + count)).filter_map(|i| cells.get(i)).collect()
+    }
+
+    // Takes a cell out of the lobby, moving the last open cell into its slot
+    fn close_open_cell(&mut self, cell_id: U256) {
+        let slot = self.open_cell_slots.get(cell_id).saturating_to::<usize>();
+        if slot == 0 {
+            return;
+        }
+        let last = self.open_cells.len() - 1;
+        if let Some(moved) = self.open_cells.get(last) {
+            if let Some(mut entry) = self.open_cells.setter(slot - 1) {
+                entry.set(moved);
+            }
+            self.open_cell_slots.setter(moved).set(U256::from(slot));
+        }
+        self.open_cells.erase_last();
+        self.open_cell_slots.setter(cell_id).set(U256::ZERO);
+    }
+
+    // Removes a cell from the player's active set, keeping the order of the rest
+    fn remove_active_cell(&mut self, player: Address, cell_id: U256) {
+        let mut cells = self.active_cells.setter(player);
+        let len = cells.len();
+        let Some(idx) = (0..len).find(|&i| cells.get(i) == Some(cell_id)) else {
+            return;
         };
-        
-        round.player1_payout = p1_payout;
-        round.player2_payout = p2_payout;
-        round.is_finished = true;
-        
-        stylus_core::log(self.vm(), RoundComplete { cell_id, round_num: cell.current_round });
-        
-        // Check if we've completed all rounds
-        if cell.current_round >= cell.total_rounds {
-            self.complete_cell(cell, cell_id);
-        } else {
-            // Don't auto-advance - wait for continuation decisions
-            cell.continuation_flags = 0; // Reset for next decision
+        for i in idx + 1..len {
+            let next = cells.get(i).unwrap_or_default();
+            if let Some(mut slot) = cells.setter(i - 1) {
+                slot.set(next);
+            }
         }
+        cells.erase_last();
     }
 
-    fn complete_cell(&mut self, cell: &mut Cell, cell_id: U256) {
-        cell.is_complete = true;
+    // Opens a cell with the sender's stake through the create action `action`
+    fn open_cell(&mut self, action: impl FnOnce(Address, U256) -> Action) -> Result<U256, PrisonersDilemmaErrors> {
+        let sender = self.vm().msg_sender();
+        let stake = self.vm().msg_value();
+
+        let action = action(sender, stake);
+        let (cell, effects) = self.apply(U256::ZERO, Cell::default(), action)?;
+        self.check_active_capacity(sender, U256::ZERO)?;
         
-        // Calculate total payouts
-        let mut total_p1 = U256::ZERO;
-        let mut total_p2 = U256::ZERO;
+        let cell_id = self.cell_counter.get() + U256::from(1);
+        self.cell_counter.set(cell_id);
         
-        for round in &cell.rounds {
-            if round.is_finished {
-                total_p1 += round.player1_payout;
-                total_p2 += round.player2_payout;
-            }
-        }
+        self.store_cell(cell_id, &cell);
+        self.enter_cell(sender, cell_id);
+        self.deposit(cell_id, stake);
+        self.open_cells.push(cell_id);
+        self.open_cell_slots.setter(cell_id).set(U256::from(self.open_cells.len()));
         
-        // Clear mappings
-        self.player_to_cell.setter(cell.player1).set(U256::ZERO);
-        self.player_to_cell.setter(cell.player2).set(U256::ZERO);
+        self.run_effects(cell_id, &[cell.player1, cell.player2], effects);
+        Ok(cell_id)
+    }
+
+    // Seats the sender as player 2 with their stake through the join action `action`
+    fn take_seat(&mut self, cell_id: U256, action: impl FnOnce(Address, U256) -> Action) -> Result<(), PrisonersDilemmaErrors> {
+        let sender = self.vm().msg_sender();
+        let stake = self.vm().msg_value();
         
-        // Transfer payouts
-        if total_p1 > U256::ZERO {
-            let _ = self.vm().transfer_eth(cell.player1, total_p1);
+        self.check_active_capacity(sender, cell_id)?;
+        // A rematch offer's seat is reserved for the previous opponent
+        let opponent = self.rematch_opponents.get(cell_id);
+        if !opponent.is_zero() && opponent != sender {
+            return Err(PrisonersDilemmaErrors::CellFull(CellFull { cell_id }));
         }
-        if total_p2 > U256::ZERO {
-            let _ = self.vm().transfer_eth(cell.player2, total_p2);
+        if self.wagers_of(cell_id, sender).iter().any(|stake| !stake.is_zero()) {
+            return Err(PrisonersDilemmaErrors::PlayerCannotBet(PlayerCannotBet { cell_id }));
         }
+
+        let cell = self.load_cell(cell_id);
+        let (cell, effects) = self.apply(cell_id, cell, action(sender, stake))?;
+        
+        self.store_cell(cell_id, &cell);
+        self.enter_cell(sender, cell_id);
+        self.deposit(cell_id, stake);
+        
+        self.record_pair(cell.player1, sender, cell_id);
+        self.close_open_cell(cell_id);
         
-        stylus_core::log(self.vm(), CellComplete { cell_id });
+        self.run_effects(cell_id, &[cell.player1, cell.player2], effects);
+        Ok(())
+    }
+
+    // Stores the cell after a player's turn, settles any wagers once it
+    // completes, then carries out the turn's effects. Everything is persisted
+    // before the effects transfer any ETH
+    fn finish_turn(&mut self, cell_id: U256, cell: &Cell, effects: Vec<Effect>) {
+        self.store_cell(cell_id, cell);
+        if cell.is_complete {
+            self.settle_wagers(cell_id, cell);
+        }
+        self.run_effects(cell_id, &[cell.player1, cell.player2], effects);
+    }
+
+    // Records the outcome buckets of a completed cell so bettors can claim
+    fn settle_wagers(&mut self, cell_id: U256, cell: &Cell) {
+        let winners = winning_buckets(cell);
+        self.wager_results.setter(cell_id).set(U8::from(WAGERS_SETTLED | winners));
+        stylus_core::log(self.vm(), WagersSettled { cell_id, winning_buckets: winners });
+    }
+
+    fn wagers_of(&self, cell_id: U256, bettor: Address) -> [U256; WAGER_BUCKETS] {
+        Bucket::ALL.map(|bucket| self.wagers.get(wager_key(cell_id, Some(bettor), bucket as u8)))
+    }
+
+    fn wager_pools_of(&self, cell_id: U256) -> [U256; WAGER_BUCKETS] {
+        Bucket::ALL.map(|bucket| self.wager_pools.get(wager_key(cell_id, None, bucket as u8)))
     }
 
     // Serialization
     fn store_cell(&mut self, cell_id: U256, cell: &Cell) {
-        let data = self.serialize_cell(cell);
+        let data = serialize_cell(cell);
         self.cells.setter(cell_id).set_bytes(&data);
     }
 
@@ -455,133 +1655,137 @@ impl PrisonersDilemma {
                 data_vec.push(b);
             }
         }
-        self.deserialize_cell(&data_vec)
+        deserialize_cell(&data_vec)
     }
 
-    fn serialize_cell(&self, cell: &Cell) -> Vec<u8> {
-        let mut data = Vec::with_capacity(128);
-        
-        data.extend_from_slice(cell.player1.as_slice());
-        data.extend_from_slice(cell.player2.as_slice());
-        data.extend_from_slice(&cell.stake_amount.to_be_bytes::<32>());
-        data.push(cell.total_rounds);
-        data.push(cell.current_round);
-        data.push(if cell.is_complete { 1 } else { 0 });
-        
-        // Rounds count
-        data.push(cell.rounds.len() as u8);
-        
-        // Serialize rounds
-        // Encoding: bits 0-1 = P1 move (0=None, 1=Cooperate, 2=Defect)
-        //           bits 2-3 = P2 move (0=None, 1=Cooperate, 2=Defect)
-        //           bit 4 = is_finished
-        for round in &cell.rounds {
-            let mut round_byte = 0u8;
-
-            // Encode player 1 move in bits 0-1
-            match round.player1_move {
-                Some(Move::Cooperate) => round_byte |= 0x01,
-                Some(Move::Defect) => round_byte |= 0x02,
-                None => {}, // 0x00 already (no bits set)
-            }
+    fn store_group_cell(&mut self, cell_id: U256, cell: &GroupCell) {
+        let data = serialize_group_cell(cell);
+        self.group_cells.setter(cell_id).set_bytes(&data);
+    }
 
-            // Encode player 2 move in bits 2-3
-            match round.player2_move {
-                Some(Move::Cooperate) => round_byte |= 0x04,
-                Some(Move::Defect) => round_byte |= 0x08,
-                None => {}, // 0x00 already (no bits set)
-            }
+    fn load_group_cell(&self, cell_id: U256) -> GroupCell {
+        deserialize_group_cell(&self.group_cells.get(cell_id).get_bytes())
+    }
 
-            if round.is_finished { round_byte |= 0x10; }
-            data.push(round_byte);
-            
-            if round.is_finished {
-                data.extend_from_slice(&round.player1_payout.to_be_bytes::<32>());
-                data.extend_from_slice(&round.player2_payout.to_be_bytes::<32>());
-            }
-        }
-        
-        data.push(cell.continuation_flags);
-        data
-    }
-
-    fn deserialize_cell(&self, data: &[u8]) -> Cell {
-        if data.len() < 76 {
-            // Return a default cell
-            return Cell {
-                player1: Address::ZERO,
-                player2: Address::ZERO,
-                stake_amount: U256::ZERO,
-                total_rounds: 0,
-                current_round: 0,
-                is_complete: false,
-                rounds: Vec::new(),
-                continuation_flags: 0,
-            };
-        }
-        
-        let player1 = Address::from_slice(&data[0..20]);
-        let player2 = Address::from_slice(&data[20..40]);
-        let stake_amount = U256::from_be_bytes::<32>(data[40..72].try_into().unwrap());
-        let total_rounds = data[72];
-        let current_round = data[73];
-        let is_complete = data[74] != 0;
-        let rounds_count = data[75] as usize;
-        
-        let mut rounds = Vec::with_capacity(rounds_count);
-        let mut pos = 76;
-        
-        for _ in 0..rounds_count {
-            if pos >= data.len() { break; }
-            
-            let round_byte = data[pos];
-            pos += 1;
-
-            // Decode player 1 move from bits 0-1 (0=None, 1=Cooperate, 2=Defect)
-            let player1_move = match round_byte & 0x03 {
-                0x01 => Some(Move::Cooperate),
-                0x02 => Some(Move::Defect),
-                _ => None, // 0x00 or invalid (0x03)
-            };
-
-            // Decode player 2 move from bits 2-3 (0=None, 1=Cooperate, 2=Defect)
-            let player2_move = match round_byte & 0x0C {
-                0x04 => Some(Move::Cooperate),
-                0x08 => Some(Move::Defect),
-                _ => None, // 0x00 or invalid (0x0C)
-            };
-            
-            let is_finished = (round_byte & 0x10) != 0;
-            
-            let (player1_payout, player2_payout) = if is_finished && pos + 64 <= data.len() {
-                let p1_payout = U256::from_be_bytes::<32>(data[pos..pos+32].try_into().unwrap());
-                let p2_payout = U256::from_be_bytes::<32>(data[pos+32..pos+64].try_into().unwrap());
-                pos += 64;
-                (p1_payout, p2_payout)
-            } else {
-                (U256::ZERO, U256::ZERO)
-            };
-            
-            rounds.push(Round {
-                player1_move,
-                player2_move,
-                player1_payout,
-                player2_payout,
-                is_finished,
-            });
-        }
-        
-        let continuation_flags = if pos < data.len() { data[pos] } else { 0 };
-        
-        Cell {
-            player1,
-            player2,
-            stake_amount,
-            total_rounds,
-            current_round,
-            is_complete,
-            rounds,
-            continuation_flags,
+    // When the reveal window of the cell's current round closes, 0 unless one
+    // player has revealed in it and the other has not
+    fn reveal_deadline(&self, cell_id: U256, cell: &Cell) -> U256 {
+        self.waiting_deadline(cell_id, cell).map_or(U256::ZERO, |(_, deadline)| deadline)
+    }
+
+    // The player left waiting in the current round and when their opponent's
+    // window closes; at most one of the two players has a deadline running
+    fn waiting_deadline(&self, cell_id: U256, cell: &Cell) -> Option<(Address, U256)> {
+        let round = cell.rounds.get((cell.current_round as usize).wrapping_sub(1))?;
+        if cell.is_complete || round.is_finished {
+            return None;
         }
+        [cell.player1, cell.player2]
+            .into_iter()
+            .map(|player| (player, self.reveal_deadlines.get(round_key(cell_id, cell.current_round, player))))
+            .find(|(_, deadline)| !deadline.is_zero())
+    }
+}
+
+fn cell_view(cell_id: U256, cell: &Cell, (challenge_deadline, reveal_deadline): (U256, U256)) -> CellView {
+    let (p1_decided, p1_wants, p2_decided, p2_wants) = cell.continuation_status();
+    (
+        cell_id,
+        cell.player1,
+        cell.player2,
+        cell.stake_amount,
+        cell.total_rounds,
+        cell.current_round,
+        cell.is_complete,
+        cell.is_cancelled(),
+        p1_decided,
+        p1_wants,
+        p2_decided,
+        p2_wants,
+        cell.noise_bps,
+        cell.continuation_bps,
+        cell.state as u8,
+        challenge_deadline,
+        reveal_deadline,
+        cell.rounds.len() as u8,
+    )
+}
+
+fn round_view(cell: &Cell, round: &Round) -> RoundView {
+    let stake = cell.stake_of(round);
+    if !round.is_finished {
+        return (0, 0, 0, 0, U256::ZERO, U256::ZERO, false, false, stake);
+    }
+    let (p1_intended, p2_intended) = round.intended_moves();
+    let as_u8 = |m: Option<Move>| m.unwrap_or(Move::Cooperate) as u8;
+    (
+        as_u8(round.player1_move),
+        as_u8(round.player2_move),
+        as_u8(p1_intended),
+        as_u8(p2_intended),
+        round.player1_payout,
+        round.player2_payout,
+        true,
+        round.sealed,
+        stake,
+    )
+}
+
+// Storage key of a bettor's stake on a bucket, or of the bucket's pool when `bettor` is None
+fn wager_key(cell_id: U256, bettor: Option<Address>, bucket: u8) -> FixedBytes<32> {
+    let mut data = Vec::with_capacity(53);
+    data.extend_from_slice(&cell_id.to_be_bytes::<32>());
+    data.extend_from_slice(bettor.unwrap_or(Address::ZERO).as_slice());
+    data.push(bucket);
+    keccak256(&data)
+}
+
+// Storage key of a player's commit-reveal state for a round
+fn round_key(cell_id: U256, round_num: u8, player: Address) -> FixedBytes<32> {
+    let mut data = Vec::with_capacity(53);
+    data.extend_from_slice(&cell_id.to_be_bytes::<32>());
+    data.push(round_num);
+    data.extend_from_slice(player.as_slice());
+    keccak256(&data)
+}
+
+// Tags a rule violation with the cell id it happened on
+fn contract_error(cell_id: U256, err: Error) -> PrisonersDilemmaErrors {
+    match err {
+        Error::AlreadyInCell => PrisonersDilemmaErrors::AlreadyInCell(AlreadyInCell { cell_id }),
+        Error::StakeTooLow => PrisonersDilemmaErrors::StakeTooLow(StakeTooLow { cell_id }),
+        Error::CellFull => PrisonersDilemmaErrors::CellFull(CellFull { cell_id }),
+        Error::WrongStake => PrisonersDilemmaErrors::WrongStake(WrongStake { cell_id }),
+        Error::CellIsComplete => PrisonersDilemmaErrors::CellIsComplete(CellIsComplete { cell_id }),
+        Error::NeedPlayer2 => PrisonersDilemmaErrors::NeedPlayer2(NeedPlayer2 { cell_id }),
+        Error::NotInCell => PrisonersDilemmaErrors::NotInCell(NotInCell { cell_id }),
+        Error::NoRoundStarted => PrisonersDilemmaErrors::NoRoundStarted(NoRoundStarted { cell_id }),
+        Error::RoundNotReady => PrisonersDilemmaErrors::RoundNotReady(RoundNotReady { cell_id }),
+        Error::RoundAlreadyFinished => PrisonersDilemmaErrors::RoundAlreadyFinished(RoundAlreadyFinished { cell_id }),
+        Error::MaxRoundsReached => PrisonersDilemmaErrors::MaxRoundsReached(MaxRoundsReached { cell_id }),
+        Error::InvalidGroupSize => PrisonersDilemmaErrors::InvalidGroupSize(InvalidGroupSize { cell_id }),
+        Error::InvalidMultiplier => PrisonersDilemmaErrors::InvalidMultiplier(InvalidMultiplier { cell_id }),
+        Error::WaitingForPlayers => PrisonersDilemmaErrors::WaitingForPlayers(WaitingForPlayers { cell_id }),
+        Error::InvalidProbability => PrisonersDilemmaErrors::InvalidProbability(InvalidProbability { cell_id }),
+        Error::CommitRequired => PrisonersDilemmaErrors::CommitRequired(CommitRequired { cell_id }),
+        Error::ContinuationIsRandom => PrisonersDilemmaErrors::ContinuationIsRandom(ContinuationIsRandom { cell_id }),
+        Error::CellNotFound => PrisonersDilemmaErrors::CellNotFound(CellNotFound { cell_id }),
+        Error::OpponentJoined => PrisonersDilemmaErrors::OpponentJoined(OpponentJoined { cell_id }),
+        Error::InvalidTransition => PrisonersDilemmaErrors::InvalidTransition(InvalidTransition { cell_id }),
+        Error::ChannelUnavailable => PrisonersDilemmaErrors::ChannelUnavailable(ChannelUnavailable { cell_id }),
+        Error::InvalidChannelState => PrisonersDilemmaErrors::InvalidChannelState(InvalidChannelState { cell_id }),
+        Error::StaleChannelState => PrisonersDilemmaErrors::StaleChannelState(StaleChannelState { cell_id }),
+        Error::NoChannelState => PrisonersDilemmaErrors::NoChannelState(NoChannelState { cell_id }),
+        Error::ChannelDisputed => PrisonersDilemmaErrors::ChannelDisputed(ChannelDisputed { cell_id }),
+        Error::SealedCell => PrisonersDilemmaErrors::SealedCell(SealedCell { cell_id }),
+        Error::NotSealed => PrisonersDilemmaErrors::NotSealed(NotSealed { cell_id }),
+        Error::InvalidProof => PrisonersDilemmaErrors::InvalidProof(InvalidProof { cell_id }),
+        Error::TooManyReveals => PrisonersDilemmaErrors::TooManyReveals(TooManyReveals { cell_id }),
+        Error::RaiseUnavailable => PrisonersDilemmaErrors::RaiseUnavailable(RaiseUnavailable { cell_id }),
+        Error::InvalidRaise => PrisonersDilemmaErrors::InvalidRaise(InvalidRaise { cell_id }),
+        Error::RaisePending => PrisonersDilemmaErrors::RaisePending(RaisePending { cell_id }),
+        Error::NoRaiseProposed => PrisonersDilemmaErrors::NoRaiseProposed(NoRaiseProposed { cell_id }),
+        Error::NoRevealPending => PrisonersDilemmaErrors::NoRevealPending(NoRevealPending { cell_id }),
+        Error::NotCommitReveal => PrisonersDilemmaErrors::NotCommitReveal(NotCommitReveal { cell_id }),
     }
 }
@@ -0,0 +1,74 @@
+// The cap on how many cells a player can be in at once and the ordered list
+// of their active cells
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use stylus_sdk::alloy_primitives::U256;
+
+#[test]
+fn players_are_limited_to_one_cell_by_default() {
+    let vm = deployed();
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_max_active_cells()), 1);
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(1)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_cell(P2)), cell_id);
+
+    let second = call(&vm, P2, stake(), |c| c.create_cell(1));
+    assert!(matches!(second, Err(PrisonersDilemmaErrors::TooManyActiveCells(_))));
+}
+
+#[test]
+fn finishing_a_cell_frees_its_slot() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(1)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_move(cell_id, 0)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move(cell_id, 0)));
+
+    for player in [P1, P2] {
+        assert!(call(&vm, P1, U256::ZERO, |c| c.get_player_active_cells(player)).is_empty());
+        assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_cell(player)), U256::ZERO);
+    }
+    ok(call(&vm, P2, stake(), |c| c.create_cell(1)));
+}
+
+#[test]
+fn leaving_a_cell_keeps_the_others_in_entry_order() {
+    let vm = deployed();
+    ok(call(&vm, P1, U256::ZERO, |c| c.set_max_active_cells(3)));
+    let ids: Vec<U256> = (0..3).map(|_| ok(call(&vm, P1, stake(), |c| c.create_cell(1)))).collect();
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_active_cells(P1)), ids);
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_cell(ids[0])));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_active_cells(P1)), [ids[1], ids[2]]);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_cell(P1)), ids[2]);
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_cell(ids[2])));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_active_cells(P1)), [ids[1]]);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_cell(P1)), ids[1]);
+}
+
+#[test]
+fn the_cap_applies_to_joining_too() {
+    let vm = deployed();
+    ok(call(&vm, P1, U256::ZERO, |c| c.set_max_active_cells(3)));
+    let ids: Vec<U256> = (0..3).map(|_| ok(call(&vm, P1, stake(), |c| c.create_cell(1)))).collect();
+    // Lowering the cap leaves cells already entered alone
+    ok(call(&vm, P1, U256::ZERO, |c| c.set_max_active_cells(2)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(ids[0])));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(ids[1])));
+
+    let third = call(&vm, P2, stake(), |c| c.join_cell(ids[2]));
+    assert!(matches!(third, Err(PrisonersDilemmaErrors::TooManyActiveCells(_))));
+}
+
+#[test]
+fn only_the_owner_sets_the_cap() {
+    let vm = deployed();
+    ok(call(&vm, P1, U256::ZERO, |c| c.set_max_active_cells(5)));
+    assert_eq!(call(&vm, P2, U256::ZERO, |c| c.get_max_active_cells()), 5);
+
+    let set = call(&vm, P2, U256::ZERO, |c| c.set_max_active_cells(10));
+    assert!(matches!(set, Err(PrisonersDilemmaErrors::NotOwner(_))));
+}
@@ -0,0 +1,40 @@
+// Minting achievement badges through the companion contract
+mod common;
+
+use alloy_sol_types::SolCall;
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::{IPrisonersDilemmaBadges::mintCall, PrisonersDilemmaErrors};
+use prisoners_dilemma_core::{Badge, CellState};
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+
+const BADGES: Address = Address::repeat_byte(0xbb);
+
+#[test]
+fn a_failing_mint_does_not_hold_up_the_game() {
+    let vm = deployed();
+    ok(call(&vm, P1, U256::ZERO, |c| c.set_badge_contract(BADGES)));
+    assert_eq!(call(&vm, P2, U256::ZERO, |c| c.get_badge_contract()), BADGES);
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(1)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+    // The companion rejects both first-game mints
+    for player in [P1, P2] {
+        let mint = mintCall { cell_id, player, badge: Badge::FirstGame as u8 };
+        vm.mock_call(BADGES, mint.abi_encode(), Err(Vec::new()));
+    }
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_move(cell_id, 0)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move(cell_id, 0)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_state(cell_id)), CellState::Completed as u8);
+    assert_eq!(vm.balance(P1), stake());
+    assert_eq!(vm.balance(P2), stake());
+}
+
+#[test]
+fn only_the_owner_sets_the_badge_contract() {
+    let vm = deployed();
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_badge_contract()), Address::ZERO);
+
+    let set = call(&vm, P2, U256::ZERO, |c| c.set_badge_contract(BADGES));
+    assert!(matches!(set, Err(PrisonersDilemmaErrors::NotOwner(_))));
+}
@@ -0,0 +1,119 @@
+// Off-chain play settled through `settle_offchain`, with the ecrecover
+// precompile mocked to return what it would for each real signature
+mod common;
+
+use common::{ok, stake, CONTRACT};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::H256;
+use prisoners_dilemma::{PrisonersDilemma, PrisonersDilemmaErrors};
+use prisoners_dilemma_core::{ChannelState, Move, PayoffMatrix};
+use stylus_sdk::abi::Bytes;
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const ECRECOVER: Address = Address::with_last_byte(1);
+const C: Move = Move::Cooperate;
+const D: Move = Move::Defect;
+
+struct Table {
+    vm: TestVM,
+    players: [LocalWallet; 2],
+    cell_id: U256,
+}
+
+impl Table {
+    // A started three-round cell between two wallets
+    fn new() -> Self {
+        let vm = TestVM::new();
+        vm.set_contract_address(CONTRACT);
+        let players = [1u8, 2].map(|key| LocalWallet::from_bytes(&[key; 32]).unwrap());
+        let mut table = Self { vm, players, cell_id: U256::ZERO };
+        table.call(0, U256::ZERO, |c| c.initialize(U256::from(1)));
+        table.cell_id = ok(table.call(0, stake(), |c| c.create_cell(3)));
+        let cell_id = table.cell_id;
+        ok(table.call(1, stake(), |c| c.join_cell(cell_id)));
+        table
+    }
+
+    fn address(&self, player: usize) -> Address {
+        Address::from(self.players[player].address().0)
+    }
+
+    fn call<T>(&self, player: usize, value: U256, f: impl FnOnce(&mut PrisonersDilemma) -> T) -> T {
+        common::call(&self.vm, self.address(player), value, f)
+    }
+
+    // `player`'s signature over `state`, with ecrecover mocked for it
+    fn sign(&self, player: usize, state: &ChannelState) -> Bytes {
+        let digest = ok(self.call(0, U256::ZERO, |c| c.get_channel_digest(Bytes::from(state.encode()))));
+        let signature = self.players[player].sign_hash(H256(digest.0)).unwrap();
+        let signer = signature.recover(H256(digest.0)).unwrap();
+
+        let mut input = digest.to_vec();
+        input.extend_from_slice(&U256::from(signature.v).to_be_bytes::<32>());
+        input.extend_from_slice(&signature.to_vec()[..64]);
+        let mut output = vec![0u8; 12];
+        output.extend_from_slice(signer.as_bytes());
+        self.vm.mock_static_call(ECRECOVER, input, Ok(output));
+        Bytes::from(signature.to_vec())
+    }
+
+    fn settle(&self, state: &ChannelState, signers: [usize; 2]) -> Result<(), PrisonersDilemmaErrors> {
+        let (sig1, sig2) = (self.sign(signers[0], state), self.sign(signers[1], state));
+        let (cell_id, state) = (self.cell_id, Bytes::from(state.encode()));
+        self.call(0, U256::ZERO, |c| c.settle_offchain(cell_id, state, sig1, sig2))
+    }
+}
+
+// A state for the table's cell, whose rounds are played at a third of the stake
+fn channel(table: &Table, rounds: &[(Move, Move)]) -> ChannelState {
+    ChannelState::new(table.cell_id, stake() / U256::from(3), rounds.to_vec(), &PayoffMatrix::CONTRACT)
+}
+
+#[test]
+fn a_co_signed_state_settles_the_cell_after_the_challenge_window() {
+    let table = Table::new();
+    let cell_id = table.cell_id;
+    table.vm.set_block_timestamp(1_000);
+    ok(table.settle(&channel(&table, &[(C, C)]), [0, 1]));
+
+    // The other player answers with the final state before the window closes
+    table.vm.set_block_timestamp(2_000);
+    ok(table.settle(&channel(&table, &[(C, C), (C, D), (D, D)]), [0, 1]));
+    let deadline = table.call(0, U256::ZERO, |c| c.get_challenge_deadline(cell_id));
+    assert_eq!(deadline, U256::from(2_000 + 24 * 60 * 60));
+    let (cells, _) = ok(table.call(0, U256::ZERO, |c| c.get_cells(vec![cell_id])));
+    assert_eq!(cells[0].15, deadline);
+
+    table.vm.set_block_timestamp(deadline.to::<u64>());
+    ok(table.call(1, U256::ZERO, |c| c.finalize_offchain(cell_id)));
+    assert!(table.call(0, U256::ZERO, |c| c.get_cell(cell_id)).5);
+    let (cells, _) = ok(table.call(0, U256::ZERO, |c| c.get_cells(vec![cell_id])));
+    assert_eq!(cells[0].15, U256::ZERO);
+    // The rounds pay 666_665 and 999_998 with the 1 no round used refunded,
+    // and the contract keeps what the mutual defection did not pay out
+    assert_eq!(table.vm.balance(table.address(0)), U256::from(666_666));
+    assert_eq!(table.vm.balance(table.address(1)), U256::from(999_999));
+    assert_eq!(table.vm.balance(CONTRACT), U256::from(333_335));
+}
+
+#[test]
+fn a_disputed_cell_cannot_be_finalized_early() {
+    let table = Table::new();
+    let cell_id = table.cell_id;
+    table.vm.set_block_timestamp(1_000);
+    ok(table.settle(&channel(&table, &[(C, C)]), [0, 1]));
+
+    table.vm.set_block_timestamp(1_000 + 24 * 60 * 60 - 1);
+    let early = table.call(0, U256::ZERO, |c| c.finalize_offchain(cell_id));
+    assert!(matches!(early, Err(PrisonersDilemmaErrors::ChallengeWindowOpen(_))));
+}
+
+#[test]
+fn states_need_both_players_signatures() {
+    let table = Table::new();
+    let result = table.settle(&channel(&table, &[(D, C)]), [0, 0]);
+    assert!(matches!(result, Err(PrisonersDilemmaErrors::InvalidSignature(_))));
+    assert_eq!(table.call(0, U256::ZERO, |c| c.get_cell_state(table.cell_id)), 1);
+}
@@ -0,0 +1,38 @@
+// Helpers shared by the contract tests. TestVM keeps storage on a revert, so
+// each test ends on its failing call rather than carrying on past it
+#![allow(dead_code)]
+
+use prisoners_dilemma::{PrisonersDilemma, PrisonersDilemmaErrors};
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+pub const CONTRACT: Address = Address::repeat_byte(0xcc);
+pub const P1: Address = Address::repeat_byte(1);
+pub const P2: Address = Address::repeat_byte(2);
+
+// A TestVM with the contract deployed and initialized
+pub fn deployed() -> TestVM {
+    let vm = TestVM::new();
+    vm.set_contract_address(CONTRACT);
+    call(&vm, P1, U256::ZERO, |c| c.initialize(U256::from(1)));
+    vm
+}
+
+// Calls the contract as `sender`, crediting it with `value` the way a payable call would
+pub fn call<T>(vm: &TestVM, sender: Address, value: U256, f: impl FnOnce(&mut PrisonersDilemma) -> T) -> T {
+    vm.set_sender(sender);
+    vm.set_value(value);
+    let balance = vm.balance(CONTRACT);
+    vm.set_balance(CONTRACT, balance + value);
+    f(&mut PrisonersDilemma::from(vm))
+}
+
+// Unwraps a call that should succeed, showing the revert data otherwise
+pub fn ok<T>(result: Result<T, PrisonersDilemmaErrors>) -> T {
+    result.map_err(Vec::<u8>::from).unwrap()
+}
+
+pub fn stake() -> U256 {
+    U256::from(1_000_000)
+}
@@ -0,0 +1,47 @@
+// Public goods cells played and cancelled through the contract
+mod common;
+
+use common::{call, deployed, ok, stake, CONTRACT, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+
+const P3: Address = Address::repeat_byte(3);
+
+#[test]
+fn a_group_cell_pays_out_of_its_players_stakes() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_group_cell(3, 1, 20_000)));
+    ok(call(&vm, P2, stake(), |c| c.join_group_cell(cell_id)));
+    ok(call(&vm, P3, stake(), |c| c.join_group_cell(cell_id)));
+
+    // The round is played at half a stake, which doubled covers everyone
+    // contributing. Two contributions give everyone 2/3 of a stake and the
+    // free-rider keeps their half on top
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_contribution(cell_id, true)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_contribution(cell_id, true)));
+    ok(call(&vm, P3, U256::ZERO, |c| c.submit_contribution(cell_id, false)));
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_group_cell(cell_id)).6);
+    let paid = [P1, P2, P3].map(|player| vm.balance(player));
+    assert_eq!(paid, [U256::from(666_666), U256::from(666_666), U256::from(1_166_666)]);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)).2, U256::ZERO);
+    // The free-rider's unmultiplied half stays behind
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::ZERO, U256::from(500_002)));
+    assert_eq!(vm.balance(CONTRACT), U256::from(500_002));
+}
+
+// TestVM keeps storage on a revert, so the test ends on its failing call
+#[test]
+fn an_unfilled_group_cell_can_be_cancelled_by_its_creator() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_group_cell(3, 1, 20_000)));
+    ok(call(&vm, P2, stake(), |c| c.join_group_cell(cell_id)));
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_group_cell(cell_id)));
+    assert_eq!((vm.balance(P1), vm.balance(P2)), (stake(), stake()));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::ZERO, U256::ZERO));
+    // Both players are free to take another seat
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_cell(P2)), U256::ZERO);
+
+    let joined = call(&vm, P3, stake(), |c| c.join_group_cell(cell_id));
+    assert!(matches!(joined, Err(PrisonersDilemmaErrors::CellIsComplete(_))));
+}
@@ -0,0 +1,56 @@
+// Paginated cell histories per player and per pair
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use stylus_sdk::alloy_primitives::U256;
+
+#[test]
+fn pages_stop_at_the_end_of_the_history() {
+    let vm = deployed();
+    ok(call(&vm, P1, U256::ZERO, |c| c.set_max_active_cells(3)));
+    let ids: Vec<U256> = (0..3).map(|_| ok(call(&vm, P1, stake(), |c| c.create_cell(1)))).collect();
+    let page = |offset: u64, limit: u64| call(&vm, P1, U256::ZERO, |c| c.get_player_cells(P1, U256::from(offset), U256::from(limit)));
+
+    assert_eq!(page(0, 10), ids);
+    assert_eq!(page(1, 1), [ids[1]]);
+    assert_eq!(page(2, 10), [ids[2]]);
+    assert!(page(3, 10).is_empty());
+    assert!(page(0, 0).is_empty());
+    // Offsets and limits beyond any length saturate instead of overflowing
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_player_cells(P1, U256::MAX, U256::MAX)).is_empty());
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_cells(P1, U256::ZERO, U256::MAX)), ids);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_cell_count(P1)), U256::from(3));
+}
+
+#[test]
+fn pages_hold_at_most_a_hundred_cells() {
+    let vm = deployed();
+    ok(call(&vm, P1, U256::ZERO, |c| c.set_max_active_cells(101)));
+    let ids: Vec<U256> = (0..101).map(|_| ok(call(&vm, P1, stake(), |c| c.create_cell(1)))).collect();
+
+    let first = call(&vm, P1, U256::ZERO, |c| c.get_player_cells(P1, U256::ZERO, U256::from(1_000)));
+    assert_eq!(first, ids[..100]);
+    let rest = call(&vm, P1, U256::ZERO, |c| c.get_player_cells(P1, U256::from(100), U256::from(1_000)));
+    assert_eq!(rest, ids[100..]);
+}
+
+#[test]
+fn a_pair_shares_one_history_whoever_sits_first() {
+    let vm = deployed();
+    let first = ok(call(&vm, P1, stake(), |c| c.create_cell(1)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(first)));
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_move(first, 0)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move(first, 0)));
+    // The seats swap for the second cell
+    let second = ok(call(&vm, P2, stake(), |c| c.create_cell(1)));
+    ok(call(&vm, P1, stake(), |c| c.join_cell(second)));
+
+    for (a, b) in [(P1, P2), (P2, P1)] {
+        let cells = call(&vm, P1, U256::ZERO, |c| c.get_pair_cells(a, b, U256::ZERO, U256::from(10)));
+        assert_eq!(cells, [first, second]);
+        assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_pair_cell_count(a, b)), U256::from(2));
+        assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_players_cell(a, b)), second);
+    }
+    let page = call(&vm, P1, U256::ZERO, |c| c.get_pair_cells(P2, P1, U256::from(1), U256::from(10)));
+    assert_eq!(page, [second]);
+}
@@ -0,0 +1,72 @@
+// The escrow ledger: cells pay out of their own deposits, and payouts that
+// cannot be sent wait in the player's withdrawable balance
+mod common;
+
+use common::{call, deployed, ok, stake, CONTRACT, P1, P2};
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const P3: Address = Address::repeat_byte(3);
+const COOPERATE: u8 = 0;
+const DEFECT: u8 = 1;
+
+// Plays `moves` as consecutive rounds, with both players voting to continue between them
+fn play(vm: &TestVM, cell_id: U256, moves: &[(u8, u8)]) {
+    for (idx, &(p1_move, p2_move)) in moves.iter().enumerate() {
+        if idx > 0 {
+            ok(call(vm, P1, U256::ZERO, |c| c.submit_continuation_decision(cell_id, true)));
+            ok(call(vm, P2, U256::ZERO, |c| c.submit_continuation_decision(cell_id, true)));
+        }
+        ok(call(vm, P1, U256::ZERO, |c| c.submit_move(cell_id, p1_move)));
+        ok(call(vm, P2, U256::ZERO, |c| c.submit_move(cell_id, p2_move)));
+    }
+}
+
+#[test]
+fn a_failed_payout_is_credited_for_withdrawal() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(2)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+
+    // Player 2 cannot receive ether, so the transfer to them fails
+    vm.set_balance(P2, U256::MAX);
+    play(&vm, cell_id, &[(COOPERATE, COOPERATE), (COOPERATE, COOPERATE)]);
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_cell(cell_id)).5);
+    assert_eq!(vm.balance(P1), stake());
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_withdrawable(P2)), stake());
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (stake() * U256::from(2), stake() * U256::from(2), U256::ZERO));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, stake(), stake()));
+
+    vm.set_balance(P2, U256::ZERO);
+    assert_eq!(ok(call(&vm, P2, U256::ZERO, |c| c.withdraw())), stake());
+    assert_eq!(vm.balance(P2), stake());
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::ZERO, U256::ZERO));
+}
+
+#[test]
+fn cells_pay_out_of_their_own_deposits() {
+    let vm = deployed();
+    let long = ok(call(&vm, P1, stake(), |c| c.create_cell(3)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(long)));
+    let other = ok(call(&vm, P3, stake(), |c| c.create_cell(1)));
+
+    // Three rounds played at a third of the stake pay 666_665 and 999_998,
+    // the 1 of each stake no round used is refunded, and the mutual defection
+    // leaves the rest with the contract. The other cell's deposit is untouched
+    play(&vm, long, &[(COOPERATE, COOPERATE), (COOPERATE, DEFECT), (DEFECT, DEFECT)]);
+    assert_eq!(vm.balance(P1), U256::from(666_666));
+    assert_eq!(vm.balance(P2), U256::from(999_999));
+    let kept = U256::from(333_335);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, stake(), stake() + kept));
+
+    // Rounds paying less than the pot leave the rest with the contract
+    ok(call(&vm, P2, stake(), |c| c.join_cell(other)));
+    ok(call(&vm, P3, U256::ZERO, |c| c.submit_move(other, DEFECT)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move(other, DEFECT)));
+    let half = stake() / U256::from(2);
+    assert_eq!(vm.balance(P3), half);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(other)), (stake() * U256::from(2), stake(), U256::ZERO));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::ZERO, stake() + kept));
+    assert_eq!(vm.balance(CONTRACT), stake() + kept);
+}
@@ -0,0 +1,86 @@
+// The lobby of open cells: listing, stake filters, and cells leaving it when
+// they are joined or cancelled
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const P3: Address = Address::repeat_byte(3);
+
+fn lobby(vm: &TestVM, offset: u64, min_stake: U256, max_stake: U256) -> Vec<U256> {
+    call(vm, P1, U256::ZERO, |c| c.get_open_cells(U256::from(offset), U256::from(10), min_stake, max_stake)).0
+}
+
+// Three open cells by player 1 staking one, two and three stakes
+fn three_open_cells(vm: &TestVM) -> Vec<U256> {
+    ok(call(vm, P1, U256::ZERO, |c| c.set_max_active_cells(3)));
+    (1..=3).map(|times| ok(call(vm, P1, stake() * U256::from(times), |c| c.create_cell(1)))).collect()
+}
+
+#[test]
+fn leaving_cells_swap_with_the_last_one() {
+    let vm = deployed();
+    let ids = three_open_cells(&vm);
+    assert_eq!(lobby(&vm, 0, U256::ZERO, U256::ZERO), ids);
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_cell(ids[0])));
+    assert_eq!(lobby(&vm, 0, U256::ZERO, U256::ZERO), [ids[2], ids[1]]);
+
+    // The moved cell's slot was updated, so it can leave from its new place
+    ok(call(&vm, P2, stake() * U256::from(3), |c| c.join_cell(ids[2])));
+    assert_eq!(lobby(&vm, 0, U256::ZERO, U256::ZERO), [ids[1]]);
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_cell(ids[1])));
+    assert!(lobby(&vm, 0, U256::ZERO, U256::ZERO).is_empty());
+}
+
+#[test]
+fn stake_filters_bound_the_listing() {
+    let vm = deployed();
+    let ids = three_open_cells(&vm);
+    let stakes = |times: u64| stake() * U256::from(times);
+
+    assert_eq!(lobby(&vm, 0, stakes(2), U256::ZERO), [ids[1], ids[2]]);
+    assert_eq!(lobby(&vm, 0, U256::ZERO, stakes(2)), [ids[0], ids[1]]);
+    assert_eq!(lobby(&vm, 0, stakes(2), stakes(2)), [ids[1]]);
+    assert!(lobby(&vm, 0, stakes(4), U256::ZERO).is_empty());
+    // The offset counts only cells that pass the filters
+    assert_eq!(lobby(&vm, 1, stakes(2), U256::ZERO), [ids[2]]);
+
+    let (_, creators, listed, rounds, _, _) = call(&vm, P1, U256::ZERO, |c| c.get_open_cells(U256::ZERO, U256::from(1), stakes(3), U256::ZERO));
+    assert_eq!((creators, listed, rounds), (vec![P1], vec![stakes(3)], vec![1]));
+}
+
+#[test]
+fn cancelling_refunds_the_creator_and_closes_the_cell() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(2)));
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_cell(cell_id)));
+    assert_eq!(vm.balance(P1), stake());
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (stake(), stake(), U256::ZERO));
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_player_active_cells(P1)).is_empty());
+
+    let joined = call(&vm, P2, stake(), |c| c.join_cell(cell_id));
+    assert!(matches!(joined, Err(PrisonersDilemmaErrors::CellIsComplete(_))));
+}
+
+#[test]
+fn a_joined_cell_can_no_longer_be_cancelled() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(2)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+
+    let cancelled = call(&vm, P1, U256::ZERO, |c| c.cancel_cell(cell_id));
+    assert!(matches!(cancelled, Err(PrisonersDilemmaErrors::OpponentJoined(_))));
+}
+
+#[test]
+fn only_the_creator_cancels() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(2)));
+
+    let cancelled = call(&vm, P3, U256::ZERO, |c| c.cancel_cell(cell_id));
+    assert!(matches!(cancelled, Err(PrisonersDilemmaErrors::NotInCell(_))));
+}
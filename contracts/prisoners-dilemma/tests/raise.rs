@@ -0,0 +1,57 @@
+// Stakes raised between rounds through `propose_raise` and `accept_raise`
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use stylus_sdk::alloy_primitives::U256;
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const COOPERATE: u8 = 0;
+const DEFECT: u8 = 1;
+
+fn stakes(times: u64) -> U256 {
+    stake() * U256::from(times)
+}
+
+// Both players vote on continuing after the current round
+fn vote(vm: &TestVM, cell_id: U256, votes: [bool; 2]) {
+    ok(call(vm, P1, U256::ZERO, |c| c.submit_continuation_decision(cell_id, votes[0])));
+    ok(call(vm, P2, U256::ZERO, |c| c.submit_continuation_decision(cell_id, votes[1])));
+}
+
+#[test]
+fn accepted_raises_apply_to_the_next_round_and_lapsed_ones_are_refunded() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stakes(1), |c| c.create_cell(3)));
+    ok(call(&vm, P2, stakes(1), |c| c.join_cell(cell_id)));
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_move(cell_id, COOPERATE)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move(cell_id, COOPERATE)));
+    ok(call(&vm, P1, stakes(2), |c| c.propose_raise(cell_id, stakes(3))));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_pending_raise(cell_id)), (stakes(3), stakes(2), true, false));
+    ok(call(&vm, P2, stakes(2), |c| c.accept_raise(cell_id)));
+    vote(&vm, cell_id, [true, true]);
+
+    // Round 2 is played at a third of three times the stake
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_move(cell_id, COOPERATE)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move(cell_id, DEFECT)));
+    let (cells, rounds) = ok(call(&vm, P1, U256::ZERO, |c| c.get_cells(vec![cell_id])));
+    assert_eq!((cells[0].17, rounds.len()), (2, 2));
+    assert_eq!((rounds[0].8, rounds[1].8), (stakes(1), stakes(3)));
+    assert_eq!((rounds[1].4, rounds[1].5), (stakes(1) / U256::from(2), stakes(3) / U256::from(2)));
+
+    // A raise player 2 never matches lapses when the cell ends
+    ok(call(&vm, P1, stakes(1), |c| c.propose_raise(cell_id, stakes(4))));
+    vote(&vm, cell_id, [false, true]);
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_cell(cell_id)).5);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_withdrawable(P1)), stakes(1));
+    // The two rounds used a third of the first stake and of the raised one;
+    // the rest of the raised stake is refunded alongside their payouts
+    assert_eq!((vm.balance(P1), vm.balance(P2)), (U256::from(2_500_000), U256::from(3_500_000)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (stakes(7), stakes(7), U256::ZERO));
+    assert!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()).0);
+
+    let accepted = call(&vm, P2, stakes(1), |c| c.accept_raise(cell_id));
+    assert!(matches!(accepted, Err(PrisonersDilemmaErrors::CellIsComplete(_))));
+}
@@ -0,0 +1,280 @@
+// Regression tests for the settlement path against a receiver that calls back
+// into the contract while it is being paid
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{ok, stake, CONTRACT};
+use prisoners_dilemma::{PrisonersDilemma, PrisonersDilemmaErrors};
+use stylus_sdk::alloy_primitives::{Address, B256, U256};
+use stylus_sdk::stylus_core::calls::{errors::Error, CallAccess, MutatingCallContext, StaticCallContext, ValueTransfer};
+use stylus_sdk::stylus_core::deploy::DeploymentAccess;
+use stylus_sdk::stylus_core::host::*;
+use stylus_sdk::testing::TestVM;
+
+const HONEST: Address = Address::repeat_byte(1);
+const ATTACKER: Address = Address::repeat_byte(2);
+
+// What the attacker saw each time it was paid
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Reentry {
+    cell_complete: bool,
+    rejected: bool,
+}
+
+// A call the attacker makes into the contract, given the cell being settled
+type Attack = dyn Fn(&mut PrisonersDilemma, U256) -> Result<(), PrisonersDilemmaErrors>;
+
+// A TestVM whose transfers to `ATTACKER` run `attack` against the contract
+// before returning, the way a malicious receiving contract's fallback would
+#[derive(Clone)]
+struct MaliciousHost {
+    vm: TestVM,
+    attack: Rc<Attack>,
+    cell_id: Rc<RefCell<U256>>,
+    reentries: Rc<RefCell<Vec<Reentry>>>,
+}
+
+impl MaliciousHost {
+    fn new(attack: impl Fn(&mut PrisonersDilemma, U256) -> Result<(), PrisonersDilemmaErrors> + 'static) -> Self {
+        let vm = TestVM::new();
+        vm.set_contract_address(CONTRACT);
+        Self { vm, attack: Rc::new(attack), cell_id: Rc::default(), reentries: Rc::default() }
+    }
+
+    // Calls the contract as `sender`, sending `value` along with the call
+    fn call<T>(&self, sender: Address, value: U256, f: impl FnOnce(&mut PrisonersDilemma) -> T) -> T {
+        self.vm.set_sender(sender);
+        self.vm.set_value(value);
+        let balance = self.vm.balance(CONTRACT);
+        self.vm.set_balance(CONTRACT, balance + value);
+        f(&mut PrisonersDilemma::from(self))
+    }
+}
+
+impl ValueTransfer for MaliciousHost {
+    fn transfer_eth(&self, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.vm.transfer_eth(to, amount)?;
+        if to == ATTACKER {
+            let cell_id = *self.cell_id.borrow();
+            let mut contract = PrisonersDilemma::from(self);
+            let cell_complete = contract.get_cell(cell_id).5;
+            self.vm.set_sender(ATTACKER);
+            self.vm.set_value(U256::ZERO);
+            let rejected = matches!((self.attack)(&mut contract, cell_id), Err(PrisonersDilemmaErrors::Reentrancy(_)));
+            self.reentries.borrow_mut().push(Reentry { cell_complete, rejected });
+        }
+        Ok(())
+    }
+}
+
+impl Host for MaliciousHost {}
+
+impl CryptographyAccess for MaliciousHost {
+    fn native_keccak256(&self, input: &[u8]) -> B256 {
+        self.vm.native_keccak256(input)
+    }
+}
+
+impl CalldataAccess for MaliciousHost {
+    fn read_args(&self, len: usize) -> Vec<u8> {
+        self.vm.read_args(len)
+    }
+    fn read_return_data(&self, offset: usize, size: Option<usize>) -> Vec<u8> {
+        self.vm.read_return_data(offset, size)
+    }
+    fn return_data_size(&self) -> usize {
+        self.vm.return_data_size()
+    }
+    fn write_result(&self, data: &[u8]) {
+        self.vm.write_result(data)
+    }
+}
+
+unsafe impl UnsafeDeploymentAccess for MaliciousHost {
+    unsafe fn create1(&self, code: *const u8, code_len: usize, endowment: *const u8, contract: *mut u8, revert_data_len: *mut usize) {
+        self.vm.create1(code, code_len, endowment, contract, revert_data_len)
+    }
+    unsafe fn create2(
+        &self,
+        code: *const u8,
+        code_len: usize,
+        endowment: *const u8,
+        salt: *const u8,
+        contract: *mut u8,
+        revert_data_len: *mut usize,
+    ) {
+        self.vm.create2(code, code_len, endowment, salt, contract, revert_data_len)
+    }
+}
+
+impl StorageAccess for MaliciousHost {
+    fn storage_load_bytes32(&self, key: U256) -> B256 {
+        self.vm.storage_load_bytes32(key)
+    }
+    unsafe fn storage_cache_bytes32(&self, key: U256, value: B256) {
+        self.vm.storage_cache_bytes32(key, value)
+    }
+    fn flush_cache(&self, clear: bool) {
+        self.vm.flush_cache(clear)
+    }
+}
+
+unsafe impl UnsafeCallAccess for MaliciousHost {
+    unsafe fn call_contract(&self, to: *const u8, data: *const u8, data_len: usize, value: *const u8, gas: u64, outs_len: &mut usize) -> u8 {
+        self.vm.call_contract(to, data, data_len, value, gas, outs_len)
+    }
+    unsafe fn static_call_contract(&self, to: *const u8, data: *const u8, data_len: usize, gas: u64, outs_len: &mut usize) -> u8 {
+        self.vm.static_call_contract(to, data, data_len, gas, outs_len)
+    }
+    unsafe fn delegate_call_contract(&self, to: *const u8, data: *const u8, data_len: usize, gas: u64, outs_len: &mut usize) -> u8 {
+        self.vm.delegate_call_contract(to, data, data_len, gas, outs_len)
+    }
+}
+
+impl BlockAccess for MaliciousHost {
+    fn block_basefee(&self) -> U256 {
+        self.vm.block_basefee()
+    }
+    fn block_coinbase(&self) -> Address {
+        self.vm.block_coinbase()
+    }
+    fn block_number(&self) -> u64 {
+        self.vm.block_number()
+    }
+    fn block_timestamp(&self) -> u64 {
+        self.vm.block_timestamp()
+    }
+    fn block_gas_limit(&self) -> u64 {
+        self.vm.block_gas_limit()
+    }
+}
+
+impl ChainAccess for MaliciousHost {
+    fn chain_id(&self) -> u64 {
+        self.vm.chain_id()
+    }
+}
+
+impl AccountAccess for MaliciousHost {
+    fn balance(&self, account: Address) -> U256 {
+        self.vm.balance(account)
+    }
+    fn contract_address(&self) -> Address {
+        self.vm.contract_address()
+    }
+    fn code(&self, account: Address) -> Vec<u8> {
+        self.vm.code(account)
+    }
+    fn code_size(&self, account: Address) -> usize {
+        self.vm.code_size(account)
+    }
+    fn code_hash(&self, account: Address) -> B256 {
+        self.vm.code_hash(account)
+    }
+}
+
+impl MemoryAccess for MaliciousHost {
+    fn pay_for_memory_grow(&self, pages: u16) {
+        self.vm.pay_for_memory_grow(pages)
+    }
+}
+
+impl MessageAccess for MaliciousHost {
+    fn msg_sender(&self) -> Address {
+        self.vm.msg_sender()
+    }
+    fn msg_reentrant(&self) -> bool {
+        self.vm.msg_reentrant()
+    }
+    fn msg_value(&self) -> U256 {
+        self.vm.msg_value()
+    }
+    fn tx_origin(&self) -> Address {
+        self.vm.tx_origin()
+    }
+}
+
+impl MeteringAccess for MaliciousHost {
+    fn evm_gas_left(&self) -> u64 {
+        self.vm.evm_gas_left()
+    }
+    fn evm_ink_left(&self) -> u64 {
+        self.vm.evm_ink_left()
+    }
+    fn tx_gas_price(&self) -> U256 {
+        self.vm.tx_gas_price()
+    }
+    fn tx_ink_price(&self) -> u32 {
+        self.vm.tx_ink_price()
+    }
+}
+
+impl CallAccess for MaliciousHost {
+    fn static_call(&self, context: &dyn StaticCallContext, to: Address, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.vm.static_call(context, to, data)
+    }
+    unsafe fn delegate_call(&self, context: &dyn MutatingCallContext, to: Address, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.vm.delegate_call(context, to, data)
+    }
+    fn call(&self, context: &dyn MutatingCallContext, to: Address, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.vm.call(context, to, data)
+    }
+}
+
+impl DeploymentAccess for MaliciousHost {
+    unsafe fn deploy(&self, code: &[u8], endowment: U256, salt: Option<B256>) -> Result<Address, Vec<u8>> {
+        self.vm.deploy(code, endowment, salt)
+    }
+}
+
+impl LogAccess for MaliciousHost {
+    fn emit_log(&self, input: &[u8], num_topics: usize) {
+        self.vm.emit_log(input, num_topics)
+    }
+    fn raw_log(&self, topics: &[B256], data: &[u8]) -> Result<(), &'static str> {
+        self.vm.raw_log(topics, data)
+    }
+}
+
+fn assert_solvent(host: &MaliciousHost) {
+    let (solvent, liabilities, _) = host.call(HONEST, U256::ZERO, |c| c.check_solvency());
+    assert!(solvent);
+    assert_eq!(liabilities, U256::ZERO);
+}
+
+#[test]
+fn cancelling_cannot_be_reentered_for_a_second_refund() {
+    let host = MaliciousHost::new(|c, cell_id| c.cancel_cell(cell_id));
+    host.call(HONEST, U256::ZERO, |c| c.initialize(U256::from(1)));
+    let cell_id = ok(host.call(ATTACKER, stake(), |c| c.create_cell(1)));
+    *host.cell_id.borrow_mut() = cell_id;
+
+    ok(host.call(ATTACKER, U256::ZERO, |c| c.cancel_cell(cell_id)));
+
+    assert_eq!(*host.reentries.borrow(), vec![Reentry { cell_complete: true, rejected: true }]);
+    assert_eq!(host.vm.balance(ATTACKER), stake());
+    assert_eq!(host.vm.balance(CONTRACT), U256::ZERO);
+    assert_solvent(&host);
+}
+
+#[test]
+fn payouts_are_sent_after_the_cell_is_settled() {
+    // While being paid, the attacker tries to play the finished round again
+    let host = MaliciousHost::new(|c, cell_id| c.submit_move(cell_id, 0));
+    host.call(HONEST, U256::ZERO, |c| c.initialize(U256::from(1)));
+    let cell_id = ok(host.call(HONEST, stake(), |c| c.create_cell(1)));
+    *host.cell_id.borrow_mut() = cell_id;
+    ok(host.call(ATTACKER, stake(), |c| c.join_cell(cell_id)));
+
+    ok(host.call(HONEST, U256::ZERO, |c| c.submit_move(cell_id, 0)));
+    ok(host.call(ATTACKER, U256::ZERO, |c| c.submit_move(cell_id, 1)));
+
+    assert_eq!(*host.reentries.borrow(), vec![Reentry { cell_complete: true, rejected: true }]);
+    let deposited = stake() * U256::from(2);
+    let paid = host.vm.balance(HONEST) + host.vm.balance(ATTACKER);
+    assert_eq!(paid, deposited);
+    assert_eq!(host.call(HONEST, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (deposited, paid, U256::ZERO));
+    assert_solvent(&host);
+}
@@ -0,0 +1,112 @@
+// Rematches: the offer is a new open cell reserved for the previous opponent,
+// which they join or the offering player cancels
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const P3: Address = Address::repeat_byte(3);
+
+// A one-round cell both players have finished with mutual cooperation
+fn finished(vm: &TestVM) -> U256 {
+    let cell_id = ok(call(vm, P1, stake(), |c| c.create_cell(1)));
+    ok(call(vm, P2, stake(), |c| c.join_cell(cell_id)));
+    ok(call(vm, P1, U256::ZERO, |c| c.submit_move(cell_id, 0)));
+    ok(call(vm, P2, U256::ZERO, |c| c.submit_move(cell_id, 0)));
+    cell_id
+}
+
+#[test]
+fn accepting_joins_the_offered_cell() {
+    let vm = deployed();
+    let previous = finished(&vm);
+    let cell_id = ok(call(&vm, P2, stake(), |c| c.offer_rematch(previous)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (stake(), U256::ZERO, stake()));
+    // The offer stays out of the lobby
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_open_cells(U256::ZERO, U256::from(10), U256::ZERO, U256::ZERO)).0.is_empty());
+
+    assert_eq!(ok(call(&vm, P1, stake(), |c| c.accept_rematch(previous))), cell_id);
+    // The offering player is player 1 and the first round is open
+    let (player1, player2, _, _, current_round, _) = call(&vm, P1, U256::ZERO, |c| c.get_cell(cell_id));
+    assert_eq!((player1, player2, current_round), (P2, P1, 1));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (stake() * U256::from(2), U256::ZERO, stake() * U256::from(2)));
+
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_rematch_links(previous)), (U256::ZERO, cell_id));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_rematch_links(cell_id)), (previous, U256::ZERO));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_players_cell(P1, P2)), cell_id);
+    for player in [P1, P2] {
+        assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_player_active_cells(player)), [cell_id]);
+    }
+
+    // Each cell is rematched once
+    let again = call(&vm, P1, stake(), |c| c.offer_rematch(previous));
+    assert!(matches!(again, Err(PrisonersDilemmaErrors::RematchUnavailable(_))));
+}
+
+#[test]
+fn cancelling_refunds_the_offer_and_allows_another() {
+    let vm = deployed();
+    let previous = finished(&vm);
+    let before = vm.balance(P1);
+    let cancelled = ok(call(&vm, P1, stake(), |c| c.offer_rematch(previous)));
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_cell(cancelled)));
+    assert_eq!(vm.balance(P1), before + stake());
+
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.offer_rematch(previous)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_rematch_links(previous)), (U256::ZERO, cell_id));
+    ok(call(&vm, P2, stake(), |c| c.accept_rematch(previous)));
+}
+
+#[test]
+fn only_the_opponent_takes_the_seat() {
+    let vm = deployed();
+    let previous = finished(&vm);
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.offer_rematch(previous)));
+
+    let joined = call(&vm, P3, stake(), |c| c.join_cell(cell_id));
+    assert!(matches!(joined, Err(PrisonersDilemmaErrors::CellFull(_))));
+}
+
+#[test]
+fn the_offering_player_cannot_accept() {
+    let vm = deployed();
+    let previous = finished(&vm);
+    ok(call(&vm, P1, stake(), |c| c.offer_rematch(previous)));
+
+    let accepted = call(&vm, P1, stake(), |c| c.accept_rematch(previous));
+    assert!(matches!(accepted, Err(PrisonersDilemmaErrors::NoRematchOffer(_))));
+}
+
+#[test]
+fn the_offering_player_needs_a_free_slot() {
+    let vm = deployed();
+    let previous = finished(&vm);
+    ok(call(&vm, P1, stake(), |c| c.create_cell(1)));
+
+    let offered = call(&vm, P1, stake(), |c| c.offer_rematch(previous));
+    assert!(matches!(offered, Err(PrisonersDilemmaErrors::TooManyActiveCells(_))));
+}
+
+#[test]
+fn the_accepting_player_needs_a_free_slot() {
+    let vm = deployed();
+    let previous = finished(&vm);
+    ok(call(&vm, P1, stake(), |c| c.offer_rematch(previous)));
+    ok(call(&vm, P2, stake(), |c| c.create_cell(1)));
+
+    let accepted = call(&vm, P2, stake(), |c| c.accept_rematch(previous));
+    assert!(matches!(accepted, Err(PrisonersDilemmaErrors::TooManyActiveCells(_))));
+}
+
+#[test]
+fn offers_must_meet_the_minimum_stake() {
+    let vm = deployed();
+    let previous = finished(&vm);
+
+    let offered = call(&vm, P1, U256::ZERO, |c| c.offer_rematch(previous));
+    assert!(matches!(offered, Err(PrisonersDilemmaErrors::StakeTooLow(_))));
+}
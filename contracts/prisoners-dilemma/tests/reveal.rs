@@ -0,0 +1,117 @@
+// Commit-reveal moves and the windows that stop a player stalling a round by
+// withholding their commitment or reveal
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use prisoners_dilemma_core::CellState;
+use stylus_sdk::alloy_primitives::{Address, FixedBytes, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const COOPERATE: u8 = 0;
+const DEFECT: u8 = 1;
+
+// A started three-round research cell with both players' moves committed
+fn committed(vm: &TestVM) -> U256 {
+    let cell_id = ok(call(vm, P1, stake(), |c| c.create_research_cell(3, 1000, 0)));
+    ok(call(vm, P2, stake(), |c| c.join_cell(cell_id)));
+    for (player, move_choice) in [(P1, COOPERATE), (P2, DEFECT)] {
+        let commitment = call(vm, player, U256::ZERO, |c| c.get_move_commitment(cell_id, player, move_choice, salt(player)));
+        ok(call(vm, player, U256::ZERO, |c| c.commit_move(cell_id, commitment)));
+    }
+    cell_id
+}
+
+fn salt(player: Address) -> FixedBytes<32> {
+    FixedBytes::repeat_byte(player[19])
+}
+
+#[test]
+fn the_first_reveal_opens_a_window_the_second_closes() {
+    let vm = deployed();
+    let cell_id = committed(&vm);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_reveal_deadline(cell_id)), U256::ZERO);
+
+    vm.set_block_timestamp(1_000);
+    ok(call(&vm, P1, U256::ZERO, |c| c.reveal_move(cell_id, COOPERATE, salt(P1))));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_reveal_deadline(cell_id)), U256::from(1_000 + 24 * 60 * 60));
+    let (cells, _) = ok(call(&vm, P1, U256::ZERO, |c| c.get_cells(vec![cell_id])));
+    assert_eq!(cells[0].16, U256::from(1_000 + 24 * 60 * 60));
+
+    ok(call(&vm, P2, U256::ZERO, |c| c.reveal_move(cell_id, DEFECT, salt(P2))));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_reveal_deadline(cell_id)), U256::ZERO);
+    let claim = call(&vm, P1, U256::ZERO, |c| c.claim_reveal_timeout(cell_id));
+    assert!(matches!(claim, Err(PrisonersDilemmaErrors::NoRevealPending(_))));
+}
+
+#[test]
+fn a_withheld_reveal_pays_the_pot_to_the_revealer_once_the_window_passes() {
+    let vm = deployed();
+    let cell_id = committed(&vm);
+    vm.set_block_timestamp(1_000);
+    ok(call(&vm, P1, U256::ZERO, |c| c.reveal_move(cell_id, COOPERATE, salt(P1))));
+
+    vm.set_block_timestamp(1_000 + 24 * 60 * 60);
+    ok(call(&vm, P2, U256::ZERO, |c| c.claim_reveal_timeout(cell_id)));
+    assert_eq!(vm.balance(P1), stake() * U256::from(2));
+    assert_eq!(vm.balance(P2), U256::ZERO);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_state(cell_id)), CellState::TimedOut as u8);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)).2, U256::ZERO);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_reveal_deadline(cell_id)), U256::ZERO);
+
+    let late = call(&vm, P2, U256::ZERO, |c| c.reveal_move(cell_id, DEFECT, salt(P2)));
+    assert!(matches!(late, Err(PrisonersDilemmaErrors::CellIsComplete(_))));
+}
+
+#[test]
+fn the_timeout_waits_for_the_window_to_close() {
+    let vm = deployed();
+    let cell_id = committed(&vm);
+    vm.set_block_timestamp(1_000);
+    ok(call(&vm, P1, U256::ZERO, |c| c.reveal_move(cell_id, COOPERATE, salt(P1))));
+
+    vm.set_block_timestamp(1_000 + 24 * 60 * 60 - 1);
+    let early = call(&vm, P1, U256::ZERO, |c| c.claim_reveal_timeout(cell_id));
+    assert!(matches!(early, Err(PrisonersDilemmaErrors::RevealWindowOpen(_))));
+}
+
+#[test]
+fn a_withheld_commitment_pays_the_pot_to_the_committer_once_the_window_passes() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_research_cell(3, 1000, 0)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+    vm.set_block_timestamp(1_000);
+    let commitment = call(&vm, P1, U256::ZERO, |c| c.get_move_commitment(cell_id, P1, COOPERATE, salt(P1)));
+    ok(call(&vm, P1, U256::ZERO, |c| c.commit_move(cell_id, commitment)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_reveal_deadline(cell_id)), U256::from(1_000 + 24 * 60 * 60));
+
+    vm.set_block_timestamp(1_000 + 24 * 60 * 60);
+    ok(call(&vm, P1, U256::ZERO, |c| c.claim_reveal_timeout(cell_id)));
+    assert_eq!(vm.balance(P1), stake() * U256::from(2));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_state(cell_id)), CellState::TimedOut as u8);
+}
+
+// A started three-round cell without noise or δ
+fn plain(vm: &TestVM) -> U256 {
+    let cell_id = ok(call(vm, P1, stake(), |c| c.create_cell(3)));
+    ok(call(vm, P2, stake(), |c| c.join_cell(cell_id)));
+    cell_id
+}
+
+#[test]
+fn plain_cells_reject_commitments() {
+    let vm = deployed();
+    let cell_id = plain(&vm);
+    let commitment = call(&vm, P1, U256::ZERO, |c| c.get_move_commitment(cell_id, P1, COOPERATE, salt(P1)));
+    let commit = call(&vm, P1, U256::ZERO, |c| c.commit_move(cell_id, commitment));
+    assert!(matches!(commit, Err(PrisonersDilemmaErrors::NotCommitReveal(_))));
+}
+
+#[test]
+fn plain_cells_reject_reveals() {
+    let vm = deployed();
+    let cell_id = plain(&vm);
+    let reveal = call(&vm, P1, U256::ZERO, |c| c.reveal_move(cell_id, COOPERATE, salt(P1)));
+    assert!(matches!(reveal, Err(PrisonersDilemmaErrors::NotCommitReveal(_))));
+}
@@ -0,0 +1,120 @@
+// Sealed cells played through `reveal_sealed_moves`
+mod common;
+
+use common::{deployed, ok, stake, P1, P2};
+use prisoners_dilemma::{PrisonersDilemma, PrisonersDilemmaErrors};
+use prisoners_dilemma_core::{seal_id, Move, SealedReveal, SealedStrategy};
+use stylus_sdk::alloy_primitives::{Address, B256, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+use stylus_sdk::testing::TestVM;
+
+const C: Move = Move::Cooperate;
+const D: Move = Move::Defect;
+
+// The nonce player 1 creates the sealed cells with
+const NONCE: U256 = U256::from_limbs([5, 0, 0, 0]);
+
+struct Table {
+    vm: TestVM,
+    strategies: [SealedStrategy; 2],
+    cell_id: U256,
+}
+
+impl Table {
+    // A sealed cell between two players who sealed `moves`
+    fn new(moves: [&[Move]; 2]) -> Self {
+        let vm = deployed();
+        // Another cell takes the next id first, which the strategies do not depend on
+        ok(common::call(&vm, Address::repeat_byte(3), stake(), |c| c.create_cell(1)));
+        let strategy = |player: Address, moves: &[Move]| SealedStrategy {
+            seal: seal_id(P1, NONCE),
+            player,
+            moves: moves.to_vec(),
+            seed: B256::repeat_byte(player.0[0] + 7),
+        };
+        let strategies = [strategy(P1, moves[0]), strategy(P2, moves[1])];
+        let (total_rounds, root1, root2) = (moves[0].len() as u8, strategies[0].root(), strategies[1].root());
+        let cell_id = ok(common::call(&vm, P1, stake(), |c| c.create_sealed_cell(NONCE, total_rounds, root1)));
+        assert_eq!(common::call(&vm, P2, U256::ZERO, |c| c.get_cell_seal(cell_id)), seal_id(P1, NONCE));
+        ok(common::call(&vm, P2, stake(), |c| c.join_sealed_cell(cell_id, root2)));
+        Self { vm, strategies, cell_id }
+    }
+
+    fn call<T>(&self, sender: Address, value: U256, f: impl FnOnce(&mut PrisonersDilemma) -> T) -> T {
+        common::call(&self.vm, sender, value, f)
+    }
+
+    // Reveals `player`'s sealed moves for `rounds`
+    fn reveal(&self, player: usize, rounds: &[u8]) -> Result<(), PrisonersDilemmaErrors> {
+        let reveals: Vec<_> = rounds.iter().filter_map(|&round| self.strategies[player].reveal(round)).collect();
+        let data = SealedReveal::encode_all(&reveals).into();
+        let cell_id = self.cell_id;
+        self.call(self.strategies[player].player, U256::ZERO, |c| c.reveal_sealed_moves(cell_id, data))
+    }
+}
+
+#[test]
+fn two_reveals_settle_a_sealed_cell() {
+    let table = Table::new([&[C, C, D], &[C, D, D]]);
+    let cell_id = table.cell_id;
+    assert_eq!(table.call(P1, U256::ZERO, |c| c.get_sealed_roots(cell_id)).1, table.strategies[1].root());
+
+    ok(table.reveal(0, &[1, 2, 3]));
+    assert_eq!(table.call(P1, U256::ZERO, |c| c.get_cell(cell_id)).4, 1);
+    ok(table.reveal(1, &[1, 2, 3]));
+
+    assert!(table.call(P1, U256::ZERO, |c| c.get_cell(cell_id)).5);
+    let (_, rounds) = ok(table.call(P1, U256::ZERO, |c| c.get_cells(vec![cell_id])));
+    assert!(rounds.iter().all(|round| round.6 && round.7));
+    // Played at a third of the stake, the rounds pay 666_665 and 999_998,
+    // and the 1 no round used is refunded to each player
+    assert_eq!(table.vm.balance(P1), U256::from(666_666));
+    assert_eq!(table.vm.balance(P2), U256::from(999_999));
+}
+
+#[test]
+fn moves_are_played_only_from_the_sealed_strategies() {
+    let table = Table::new([&[C, C], &[D, D]]);
+    let cell_id = table.cell_id;
+    let moved = table.call(P1, U256::ZERO, |c| c.submit_move(cell_id, 0));
+    assert!(matches!(moved, Err(PrisonersDilemmaErrors::SealedCell(_))));
+}
+
+#[test]
+fn a_seal_is_used_only_once() {
+    let table = Table::new([&[C], &[D]]);
+    let root = table.strategies[0].root();
+    let created = table.call(P1, stake(), |c| c.create_sealed_cell(NONCE, 1, root));
+    assert!(matches!(created, Err(PrisonersDilemmaErrors::SealUsed(_))));
+}
+
+#[test]
+fn a_withheld_sealed_reveal_times_out() {
+    let table = Table::new([&[C, D], &[D, D]]);
+    let cell_id = table.cell_id;
+    table.vm.set_block_timestamp(1_000);
+    ok(table.reveal(1, &[1, 2]));
+    let deadline = U256::from(1_000 + 24 * 60 * 60);
+    assert_eq!(table.call(P1, U256::ZERO, |c| c.get_reveal_deadline(cell_id)), deadline);
+
+    // Revealing again does not restart the window
+    table.vm.set_block_timestamp(2_000);
+    ok(table.reveal(1, &[1]));
+    assert_eq!(table.call(P1, U256::ZERO, |c| c.get_reveal_deadline(cell_id)), deadline);
+
+    table.vm.set_block_timestamp(deadline.to::<u64>());
+    ok(table.call(P1, U256::ZERO, |c| c.claim_reveal_timeout(cell_id)));
+    assert_eq!(table.call(P1, U256::ZERO, |c| c.get_cell_state(cell_id)), 5);
+    assert_eq!(table.vm.balance(P2), stake() * U256::from(2));
+}
+
+#[test]
+fn malformed_reveals_are_rejected() {
+    let table = Table::new([&[C], &[D]]);
+    let cell_id = table.cell_id;
+    let mut data = SealedReveal::encode_all(&[table.strategies[0].reveal(1).unwrap()]);
+    data.pop();
+
+    let revealed = table.call(P1, U256::ZERO, |c| c.reveal_sealed_moves(cell_id, data.into()));
+    assert!(matches!(revealed, Err(PrisonersDilemmaErrors::InvalidProof(_))));
+}
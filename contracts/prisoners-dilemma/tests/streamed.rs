@@ -0,0 +1,70 @@
+// Streamed cells credit each round to withdrawable balances
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use stylus_sdk::alloy_primitives::U256;
+use stylus_sdk::stylus_core::host::AccountAccess;
+
+const COOPERATE: u8 = 0;
+const DEFECT: u8 = 1;
+
+#[test]
+fn rounds_are_credited_as_they_resolve_and_withdrawn_on_request() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_streamed_cell(2, 0, 0)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_cell_streamed(cell_id)));
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_move_with_continuation(cell_id, COOPERATE, true)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move_with_continuation(cell_id, DEFECT, true)));
+    // The round is played at half the stake
+    let quarter = stake() / U256::from(4);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_withdrawable(P1)), quarter);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_withdrawable(P2)), quarter * U256::from(3));
+    // The credits come out of the two deposits, and nothing is sent yet
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (stake() * U256::from(2), stake(), stake()));
+    assert_eq!(vm.balance(P1), U256::ZERO);
+
+    assert_eq!(ok(call(&vm, P1, U256::ZERO, |c| c.withdraw())), quarter);
+    assert_eq!(vm.balance(P1), quarter);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_withdrawable(P1)), U256::ZERO);
+
+    let again = call(&vm, P1, U256::ZERO, |c| c.withdraw());
+    assert!(matches!(again, Err(PrisonersDilemmaErrors::NothingToWithdraw(_))));
+}
+
+#[test]
+fn each_round_is_credited_from_its_share_of_the_stake() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_streamed_cell(3, 0, 0)));
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+    let withdrawable = || {
+        (call(&vm, P1, U256::ZERO, |c| c.get_withdrawable(P1)), call(&vm, P1, U256::ZERO, |c| c.get_withdrawable(P2)))
+    };
+    let play = |p1_move, p2_move| {
+        ok(call(&vm, P1, U256::ZERO, |c| c.submit_move_with_continuation(cell_id, p1_move, true)));
+        ok(call(&vm, P2, U256::ZERO, |c| c.submit_move_with_continuation(cell_id, p2_move, true)));
+    };
+
+    // Rounds are played at a third of the stake, so mutual defection pays a sixth each
+    play(DEFECT, DEFECT);
+    assert_eq!(withdrawable(), (U256::from(166_666), U256::from(166_666)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)).2, U256::from(1_666_668));
+
+    // The worst a round can pay is its two shares, so later rounds are credited in full
+    play(COOPERATE, DEFECT);
+    assert_eq!(withdrawable(), (U256::from(333_332), U256::from(666_665)));
+    // Completing the cell credits the 1 wei of each stake no round used, so
+    // the cell settles to what a lump-sum cell would pay for the same moves
+    play(COOPERATE, COOPERATE);
+    assert!(call(&vm, P1, U256::ZERO, |c| c.get_cell(cell_id)).5);
+    assert_eq!(withdrawable(), (U256::from(666_666), U256::from(999_999)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_cell_escrow(cell_id)), (stake() * U256::from(2), U256::from(1_666_665), U256::ZERO));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::from(1_666_665), stake() * U256::from(2)));
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.withdraw()));
+    ok(call(&vm, P2, U256::ZERO, |c| c.withdraw()));
+    assert_eq!((vm.balance(P1), vm.balance(P2)), (U256::from(666_666), U256::from(999_999)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::ZERO, U256::from(333_335)));
+}
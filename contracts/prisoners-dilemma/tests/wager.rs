@@ -0,0 +1,58 @@
+// Spectator wagers claimed after a cell completes, and refunded when it is cancelled
+mod common;
+
+use common::{call, deployed, ok, stake, P1, P2};
+use prisoners_dilemma::PrisonersDilemmaErrors;
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::stylus_core::host::AccountAccess;
+
+const ALICE: Address = Address::repeat_byte(0xa1);
+const BOB: Address = Address::repeat_byte(0xb0);
+const COOPERATE: u8 = 0;
+
+#[test]
+fn winning_bettors_share_each_markets_pool() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(1)));
+    // Alice backs mutual cooperation and an even game, Bob any defection and player 1
+    for (bettor, bucket, times) in [(ALICE, 0, 1), (ALICE, 4, 1), (BOB, 1, 2), (BOB, 2, 1)] {
+        ok(call(&vm, bettor, stake() * U256::from(times), |c| c.place_wager(cell_id, bucket)));
+    }
+    let pools = call(&vm, P1, U256::ZERO, |c| c.get_wager_pools(cell_id));
+    assert_eq!(pools, vec![stake(), stake() * U256::from(2), stake(), U256::ZERO, stake()]);
+
+    ok(call(&vm, P2, stake(), |c| c.join_cell(cell_id)));
+    ok(call(&vm, P1, U256::ZERO, |c| c.submit_move(cell_id, COOPERATE)));
+    ok(call(&vm, P2, U256::ZERO, |c| c.submit_move(cell_id, COOPERATE)));
+    let winners = 1 << 0 | 1 << 4;
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_wager_result(cell_id)), (true, winners));
+
+    // Alice takes both pools, and Bob's claim pays nothing but clears his stakes
+    assert_eq!(ok(call(&vm, ALICE, U256::ZERO, |c| c.claim_wager(cell_id))), stake() * U256::from(5));
+    assert_eq!(vm.balance(ALICE), stake() * U256::from(5));
+    assert_eq!(ok(call(&vm, BOB, U256::ZERO, |c| c.claim_wager(cell_id))), U256::ZERO);
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::ZERO, U256::ZERO));
+
+    let again = call(&vm, ALICE, U256::ZERO, |c| c.claim_wager(cell_id));
+    assert!(matches!(again, Err(PrisonersDilemmaErrors::NoWager(_))));
+}
+
+#[test]
+fn cancelling_a_cell_refunds_its_wagers() {
+    let vm = deployed();
+    let cell_id = ok(call(&vm, P1, stake(), |c| c.create_cell(1)));
+    ok(call(&vm, ALICE, stake(), |c| c.place_wager(cell_id, 0)));
+    ok(call(&vm, ALICE, stake(), |c| c.place_wager(cell_id, 3)));
+    ok(call(&vm, BOB, stake(), |c| c.place_wager(cell_id, 1)));
+
+    ok(call(&vm, P1, U256::ZERO, |c| c.cancel_cell(cell_id)));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.get_wager_result(cell_id)), (true, 0));
+    assert_eq!(ok(call(&vm, ALICE, U256::ZERO, |c| c.claim_wager(cell_id))), stake() * U256::from(2));
+    assert_eq!(ok(call(&vm, BOB, U256::ZERO, |c| c.claim_wager(cell_id))), stake());
+    assert_eq!((vm.balance(P1), vm.balance(BOB)), (stake(), stake()));
+    assert_eq!(call(&vm, P1, U256::ZERO, |c| c.check_solvency()), (true, U256::ZERO, U256::ZERO));
+
+    // Betting closes with the cell
+    let late = call(&vm, BOB, stake(), |c| c.place_wager(cell_id, 0));
+    assert!(matches!(late, Err(PrisonersDilemmaErrors::BettingClosed(_))));
+}